@@ -0,0 +1,131 @@
+//! Fake Binance compatible web socket server replaying recorded frames.
+//!
+//! ```bash
+//! cargo run -p aggregator-server --bin fake_feed -- aggregator-server/fixtures/binance_book_ticker.ndjson
+//! ```
+//! and open the dashboard with `?crypto_provider=binance&binance_ws_address=ws://127.0.0.1:9443`.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use std::{env, fs, io};
+
+use futures::{SinkExt, StreamExt};
+use log::{info, warn};
+use serde_json::{json, Value};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::sleep;
+use tokio_tungstenite::accept_async;
+use tokio_tungstenite::tungstenite::{Error, Message};
+
+const DEFAULT_FRAMES: &str = "aggregator-server/fixtures/binance_book_ticker.ndjson";
+
+#[tokio::main]
+async fn main() -> io::Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let frames_path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_FRAMES.to_owned());
+    let address = env::var("FAKE_FEED_ADDRESS").unwrap_or_else(|_| "127.0.0.1:9443".to_owned());
+    let interval = env::var("FAKE_FEED_INTERVAL_MS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .map_or(Duration::from_millis(200), Duration::from_millis);
+
+    let frames: Arc<Vec<String>> = Arc::new(
+        fs::read_to_string(&frames_path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(str::to_owned)
+            .collect(),
+    );
+    let listener = TcpListener::bind(address.as_str()).await?;
+    info!(
+        "Replaying {} frames from {frames_path} on ws://{address}/ws",
+        frames.len()
+    );
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let frames = frames.clone();
+        tokio::spawn(async move {
+            info!("Client {peer} connected");
+            if let Err(error) = serve(stream, frames, interval).await {
+                warn!("Client {peer}: {error}");
+            }
+            info!("Client {peer} disconnected");
+        });
+    }
+}
+
+/// Answering SUBSCRIBE like the exchange and replaying the frames of the subscribed symbols in a loop
+async fn serve(
+    stream: TcpStream,
+    frames: Arc<Vec<String>>,
+    interval: Duration,
+) -> Result<(), Error> {
+    let (mut writer, mut reader) = accept_async(stream).await?.split();
+    let mut symbols = HashSet::new();
+    let mut position = 0;
+    loop {
+        tokio::select! {
+            message = reader.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    if let Some(reply) = handle_request(&text, &mut symbols) {
+                        writer.send(Message::Text(reply)).await?;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => return Ok(()),
+                Some(Ok(_)) => {}
+                Some(Err(error)) => return Err(error),
+            },
+            _ = sleep(interval), if !symbols.is_empty() => {
+                if let Some(frame) = next_frame(&frames, &mut position, &symbols) {
+                    writer.send(Message::Text(frame.clone())).await?;
+                }
+            }
+        }
+    }
+}
+
+/// Handling SUBSCRIBE/UNSUBSCRIBE requests, `params` are streams like "btcusdt@bookTicker"
+fn handle_request(text: &str, symbols: &mut HashSet<String>) -> Option<String> {
+    let request: Value = serde_json::from_str(text).ok()?;
+    let streams = request.get("params")?.as_array()?;
+    let stream_symbols = streams
+        .iter()
+        .filter_map(Value::as_str)
+        .filter_map(|stream| stream.split('@').next())
+        .map(str::to_uppercase);
+    match request.get("method")?.as_str()? {
+        "SUBSCRIBE" => symbols.extend(stream_symbols),
+        "UNSUBSCRIBE" => stream_symbols.for_each(|symbol| {
+            symbols.remove(&symbol);
+        }),
+        _ => return None,
+    }
+    Some(json!({ "result": null, "id": request.get("id") }).to_string())
+}
+
+/// Next frame of a subscribed symbol, starting over at the end of the recording
+fn next_frame<'a>(
+    frames: &'a [String],
+    position: &mut usize,
+    symbols: &HashSet<String>,
+) -> Option<&'a String> {
+    for _ in 0..frames.len() {
+        let frame = &frames[*position];
+        *position = (*position + 1) % frames.len();
+        if is_subscribed(frame, symbols) {
+            return Some(frame);
+        }
+    }
+    None
+}
+
+fn is_subscribed(frame: &str, symbols: &HashSet<String>) -> bool {
+    serde_json::from_str::<Value>(frame)
+        .ok()
+        .and_then(|frame| frame.get("s").and_then(Value::as_str).map(str::to_owned))
+        .is_some_and(|symbol| symbols.contains(&symbol))
+}
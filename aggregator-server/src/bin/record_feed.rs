@@ -0,0 +1,98 @@
+//! Recording a live price web socket session to an NDJSON file replayable by the dashboard
+//! mock provider (`mock_session_url`).
+//!
+//! ```bash
+//! RECORD_FEED_SYMBOLS=BTC/USD,EUR/USD,AAPL cargo run -p aggregator-server --bin record_feed -- session.ndjson
+//! ```
+//! records the frames of the running aggregator server until Ctrl+C.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{env, io};
+
+use futures::{SinkExt, StreamExt};
+use log::{info, warn};
+use serde_json::json;
+use tokio::time::{interval, sleep, Instant};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+
+const DEFAULT_URL: &str = "ws://127.0.0.1:7015/quotes/price";
+const DEFAULT_SYMBOLS: &str = "BTC/USD,ETH/USD,EUR/USD,USD/JPY,SPX,AAPL,MSFT";
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(9);
+
+#[tokio::main]
+async fn main() -> io::Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let output_path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "session.ndjson".to_owned());
+    let url = env::var("RECORD_FEED_URL").unwrap_or_else(|_| DEFAULT_URL.to_owned());
+    let symbols = env::var("RECORD_FEED_SYMBOLS").unwrap_or_else(|_| DEFAULT_SYMBOLS.to_owned());
+    // a raw subscribe message replaces the Twelve Data one, e.g. for Binance compatible streams
+    let raw_subscribe = env::var("RECORD_FEED_SUBSCRIBE").ok();
+    let duration = env::var("RECORD_FEED_DURATION_SECS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .map(Duration::from_secs);
+
+    let (socket, _) = connect_async(url.as_str())
+        .await
+        .map_err(|error| io::Error::new(io::ErrorKind::ConnectionRefused, error))?;
+    let (mut writer, mut reader) = socket.split();
+    let subscribe = match &raw_subscribe {
+        Some(message) => message.clone(),
+        None => json!({ "action": "subscribe", "params": { "symbols": symbols } }).to_string(),
+    };
+    writer
+        .send(Message::Text(subscribe))
+        .await
+        .map_err(io::Error::other)?;
+
+    let mut output = BufWriter::new(File::create(&output_path)?);
+    let mut heartbeat = interval(HEARTBEAT_INTERVAL);
+    let started_at = Instant::now();
+    let deadline = sleep(duration.unwrap_or(Duration::MAX));
+    tokio::pin!(deadline);
+    let mut frames: u64 = 0;
+    info!("Recording {url} to {output_path}, press Ctrl+C to stop");
+    loop {
+        tokio::select! {
+            message = reader.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let line = json!({ "received_at": now_millis(), "frame": text });
+                    writeln!(output, "{line}")?;
+                    frames += 1;
+                }
+                Some(Ok(Message::Close(_))) | None => {
+                    warn!("Connection closed by the server");
+                    break;
+                }
+                Some(Ok(_)) => {}
+                Some(Err(error)) => {
+                    warn!("Connection error: {error}");
+                    break;
+                }
+            },
+            _ = heartbeat.tick(), if raw_subscribe.is_none() => {
+                let message = json!({ "action": "heartbeat" }).to_string();
+                writer.send(Message::Text(message)).await.map_err(io::Error::other)?;
+            }
+            _ = &mut deadline => break,
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+    let _ = writer.close().await;
+    output.flush()?;
+    info!("Recorded {frames} frames in {:?}", started_at.elapsed());
+    Ok(())
+}
+
+/// Receive time in milliseconds since the Unix epoch, the same clock as the browser recorder
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
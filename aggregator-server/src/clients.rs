@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::response::Response;
+use futures::{SinkExt, StreamExt};
+use log::warn;
+
+use crate::hub::{ClientId, Hub};
+use crate::protocol::ActionMessage;
+use crate::AppState;
+
+/// Browser real-time price web socket endpoint
+pub async fn price_socket(ws: WebSocketUpgrade, State(state): State<AppState>) -> Response {
+    ws.on_upgrade(move |socket| handle_client(socket, state.hub))
+}
+
+async fn handle_client(socket: WebSocket, hub: Arc<Hub>) {
+    let (id, mut outgoing) = hub.register();
+    let (mut writer, mut reader) = socket.split();
+    loop {
+        tokio::select! {
+            text = outgoing.recv() => match text {
+                Some(text) => {
+                    if writer.send(Message::Text(text)).await.is_err() {
+                        break;
+                    }
+                }
+                None => break,
+            },
+            message = reader.next() => match message {
+                Some(Ok(Message::Text(text))) => handle_action(&hub, id, &text),
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    hub.unregister(id);
+}
+
+fn handle_action(hub: &Hub, id: ClientId, text: &str) {
+    let message: ActionMessage = match serde_json::from_str(text) {
+        Ok(message) => message,
+        Err(error) => {
+            warn!("Client {id} sent invalid action {text}: {error}");
+            return;
+        }
+    };
+    match message.action.as_str() {
+        "subscribe" => hub.subscribe(id, message.params.symbols()),
+        "unsubscribe" => hub.unsubscribe(id, message.params.symbols()),
+        "reset" => hub.reset(id),
+        "heartbeat" => hub.heartbeat(id),
+        action => warn!("Client {id} sent unknown action {action}"),
+    }
+}
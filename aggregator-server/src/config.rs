@@ -0,0 +1,48 @@
+use std::env;
+
+use crate::error::ServerError;
+
+/// Proxy Server Configuration, read from `MARKET_*` environment variables
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// Twelve Data API key, never sent to the browser
+    pub api_key: String,
+    /// Upstream rest api address
+    pub rest_address: String,
+    /// Upstream web socket address
+    pub ws_address: String,
+    /// Real-time price web socket route, shared by the upstream and the proxy
+    pub real_time_price_route: String,
+    /// Address the proxy listens on
+    pub server_address: String,
+    /// Directory with the built dashboard (`trunk build` output)
+    pub static_dir: String,
+}
+
+impl ServerConfig {
+    pub fn from_env() -> Result<Self, ServerError> {
+        let api_key = env::var("MARKET_API_KEY")
+            .ok()
+            .filter(|key| !key.is_empty())
+            .ok_or_else(|| ServerError::Config("MARKET_API_KEY is not set".to_owned()))?;
+        Ok(Self {
+            api_key,
+            rest_address: var_or("MARKET_REST_ADDRESS", "https://api.twelvedata.com"),
+            ws_address: var_or("MARKET_WS_ADDRESS", "wss://ws.twelvedata.com/v1"),
+            real_time_price_route: var_or("MARKET_REAL_TIME_PRICE_ROUTE", "/quotes/price"),
+            server_address: var_or("MARKET_SERVER_ADDRESS", "127.0.0.1:7015"),
+            static_dir: var_or("MARKET_STATIC_DIR", "dashboard/dist"),
+        })
+    }
+    /// Upstream real-time price web socket url
+    pub fn upstream_ws_url(&self) -> String {
+        format!(
+            "{}{}?apikey={}",
+            self.ws_address, self.real_time_price_route, self.api_key
+        )
+    }
+}
+
+fn var_or(key: &str, default: &str) -> String {
+    env::var(key).unwrap_or_else(|_| default.to_owned())
+}
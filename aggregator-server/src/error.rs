@@ -0,0 +1,33 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use log::error;
+use thiserror::Error;
+
+/// Proxy Server Error
+#[derive(Debug, Error)]
+pub enum ServerError {
+    #[error("Configuration error: {0}")]
+    Config(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Upstream request error: {0}")]
+    Upstream(reqwest::Error),
+}
+
+impl From<reqwest::Error> for ServerError {
+    fn from(error: reqwest::Error) -> Self {
+        // the upstream url carries the API key
+        ServerError::Upstream(error.without_url())
+    }
+}
+
+impl IntoResponse for ServerError {
+    fn into_response(self) -> Response {
+        error!("{}", self);
+        let status = match self {
+            ServerError::Upstream(_) => StatusCode::BAD_GATEWAY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, self.to_string()).into_response()
+    }
+}
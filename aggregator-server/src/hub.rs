@@ -0,0 +1,459 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use log::{debug, warn};
+use serde_json::Value;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::mpsc::{self, Receiver, Sender, UnboundedReceiver, UnboundedSender};
+
+use crate::protocol::{
+    entry_symbol, heartbeat_reply, subscribe_status_message, UpstreamEvent, HEARTBEAT_EVENT,
+    PRICE_EVENT, SUBSCRIBE_STATUS_EVENT,
+};
+
+/// Browser client identifier
+pub type ClientId = u64;
+
+/// Outgoing messages queued per client, a client that falls this far behind is disconnected
+const CLIENT_QUEUE_CAPACITY: usize = 1024;
+
+/// Command for the upstream connection
+#[derive(Debug)]
+pub enum UpstreamCommand {
+    Subscribe(Vec<String>),
+    Unsubscribe(Vec<String>),
+}
+
+/// Multiplexes a single upstream price stream to many browser clients.
+/// Every symbol is subscribed upstream once, no matter how many clients watch it.
+pub struct Hub {
+    state: Mutex<HubState>,
+    upstream: UnboundedSender<UpstreamCommand>,
+    next_client_id: AtomicU64,
+}
+
+#[derive(Default)]
+struct HubState {
+    clients: HashMap<ClientId, Client>,
+    /// Symbols subscribed upstream
+    upstream_symbols: HashSet<String>,
+    /// Last subscribe status reported upstream, by symbol
+    statuses: HashMap<String, SymbolStatus>,
+}
+
+struct Client {
+    symbols: HashSet<String>,
+    sender: Sender<String>,
+}
+
+#[derive(Clone)]
+struct SymbolStatus {
+    success: bool,
+    entry: Value,
+}
+
+impl Hub {
+    pub fn new() -> (Arc<Self>, UnboundedReceiver<UpstreamCommand>) {
+        let (upstream, commands) = mpsc::unbounded_channel();
+        let hub = Self {
+            state: Mutex::new(HubState::default()),
+            upstream,
+            next_client_id: AtomicU64::new(1),
+        };
+        (Arc::new(hub), commands)
+    }
+    /// Registering a browser client, returns the receiver of its outgoing messages
+    pub fn register(&self) -> (ClientId, Receiver<String>) {
+        let id = self.next_client_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = mpsc::channel(CLIENT_QUEUE_CAPACITY);
+        let client = Client {
+            symbols: HashSet::new(),
+            sender,
+        };
+        self.lock().clients.insert(id, client);
+        debug!("Client {id} connected");
+        (id, receiver)
+    }
+    /// Removing a browser client and releasing its symbols
+    pub fn unregister(&self, id: ClientId) {
+        let mut state = self.lock();
+        state.clients.remove(&id);
+        self.release_orphans(&mut state);
+        debug!("Client {id} disconnected");
+    }
+    /// Adding symbols to the client subscription
+    pub fn subscribe(&self, id: ClientId, symbols: Vec<String>) {
+        let mut state = self.lock();
+        let Some(client) = state.clients.get_mut(&id) else {
+            return;
+        };
+        client.symbols.extend(symbols.iter().cloned());
+
+        // symbols already known upstream are answered right away
+        let (mut success, mut fails) = (Vec::new(), Vec::new());
+        for symbol in &symbols {
+            if let Some(status) = state.statuses.get(symbol) {
+                if status.success {
+                    success.push(status.entry.clone());
+                } else {
+                    fails.push(status.entry.clone());
+                }
+            }
+        }
+        if !success.is_empty() || !fails.is_empty() {
+            let message = subscribe_status_message(success, fails);
+            if !state.send_to(id, message) {
+                self.disconnect(&mut state, vec![id]);
+                return;
+            }
+        }
+
+        let new_symbols: Vec<String> = symbols
+            .into_iter()
+            .filter(|symbol| state.upstream_symbols.insert(symbol.clone()))
+            .collect();
+        if !new_symbols.is_empty() {
+            self.send_upstream(UpstreamCommand::Subscribe(new_symbols));
+        }
+    }
+    /// Removing symbols from the client subscription
+    pub fn unsubscribe(&self, id: ClientId, symbols: Vec<String>) {
+        let mut state = self.lock();
+        if let Some(client) = state.clients.get_mut(&id) {
+            for symbol in &symbols {
+                client.symbols.remove(symbol);
+            }
+        }
+        self.release_orphans(&mut state);
+    }
+    /// Removing all symbols from the client subscription
+    pub fn reset(&self, id: ClientId) {
+        let mut state = self.lock();
+        if let Some(client) = state.clients.get_mut(&id) {
+            client.symbols.clear();
+        }
+        self.release_orphans(&mut state);
+    }
+    /// Answering the client heartbeat without going upstream
+    pub fn heartbeat(&self, id: ClientId) {
+        let mut state = self.lock();
+        if !state.send_to(id, heartbeat_reply()) {
+            self.disconnect(&mut state, vec![id]);
+        }
+    }
+    /// Symbols to subscribe after the upstream (re)connection. The commands queued while
+    /// the upstream was down are already in the symbols and are dropped, the hub queues
+    /// commands under the lock, so none of them is lost or replayed.
+    pub fn upstream_connected(
+        &self,
+        commands: &mut UnboundedReceiver<UpstreamCommand>,
+    ) -> Vec<String> {
+        let mut state = self.lock();
+        while commands.try_recv().is_ok() {}
+        state.statuses.clear();
+        state.upstream_symbols.iter().cloned().collect()
+    }
+    /// Routing an upstream message to the interested clients, true for a price
+    pub fn dispatch(&self, text: String) -> bool {
+        let event: UpstreamEvent = match serde_json::from_str(&text) {
+            Ok(event) => event,
+            Err(error) => {
+                warn!("Unexpected upstream message {text}: {error}");
+                return false;
+            }
+        };
+        let mut state = self.lock();
+        let lagging = match event.event.as_str() {
+            PRICE_EVENT => state.broadcast(&text, |client| client.symbols.contains(&event.symbol)),
+            SUBSCRIBE_STATUS_EVENT => state.dispatch_subscribe_status(&text),
+            HEARTBEAT_EVENT => Vec::new(),
+            _ => state.broadcast(&text, |_| true),
+        };
+        self.disconnect(&mut state, lagging);
+        event.event == PRICE_EVENT
+    }
+
+    /// Dropping the clients that cannot keep up, closing their queues ends their connections
+    fn disconnect(&self, state: &mut HubState, ids: Vec<ClientId>) {
+        if ids.is_empty() {
+            return;
+        }
+        for id in ids {
+            state.clients.remove(&id);
+            warn!("Client {id} disconnected, its queue is full");
+        }
+        self.release_orphans(state);
+    }
+
+    fn release_orphans(&self, state: &mut HubState) {
+        let watched: HashSet<&String> = state
+            .clients
+            .values()
+            .flat_map(|client| client.symbols.iter())
+            .collect();
+        let orphans: Vec<String> = state
+            .upstream_symbols
+            .iter()
+            .filter(|symbol| !watched.contains(symbol))
+            .cloned()
+            .collect();
+        if orphans.is_empty() {
+            return;
+        }
+        for symbol in &orphans {
+            state.upstream_symbols.remove(symbol);
+            state.statuses.remove(symbol);
+        }
+        self.send_upstream(UpstreamCommand::Unsubscribe(orphans));
+    }
+
+    fn send_upstream(&self, command: UpstreamCommand) {
+        if self.upstream.send(command).is_err() {
+            warn!("Upstream connection task is not running");
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HubState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl HubState {
+    /// Queueing the message for the client, false if the client is lagging
+    fn send_to(&self, id: ClientId, text: String) -> bool {
+        self.clients.get(&id).is_none_or(|client| client.send(text))
+    }
+    /// Queueing the message for the matching clients, returns the lagging ones
+    fn broadcast(&self, text: &str, filter: impl Fn(&Client) -> bool) -> Vec<ClientId> {
+        self.clients
+            .iter()
+            .filter(|(_, client)| filter(client))
+            .filter(|(_, client)| !client.send(text.to_owned()))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+    /// Saving the statuses and forwarding them to the clients, returns the lagging ones
+    fn dispatch_subscribe_status(&mut self, text: &str) -> Vec<ClientId> {
+        let Ok(message) = serde_json::from_str::<Value>(text) else {
+            return Vec::new();
+        };
+        let mut reported = Vec::new();
+        for (key, success) in [("success", true), ("fails", false)] {
+            let entries = message.get(key).and_then(Value::as_array);
+            for entry in entries.into_iter().flatten() {
+                if let Some(symbol) = entry_symbol(entry) {
+                    let status = SymbolStatus {
+                        success,
+                        entry: entry.clone(),
+                    };
+                    self.statuses.insert(symbol.to_owned(), status.clone());
+                    reported.push((symbol.to_owned(), status));
+                }
+            }
+        }
+        let mut lagging = Vec::new();
+        for (id, client) in &self.clients {
+            let (mut success, mut fails) = (Vec::new(), Vec::new());
+            for (symbol, status) in &reported {
+                if !client.symbols.contains(symbol) {
+                    continue;
+                }
+                if status.success {
+                    success.push(status.entry.clone());
+                } else {
+                    fails.push(status.entry.clone());
+                }
+            }
+            if (!success.is_empty() || !fails.is_empty())
+                && !client.send(subscribe_status_message(success, fails))
+            {
+                lagging.push(*id);
+            }
+        }
+        lagging
+    }
+}
+
+impl Client {
+    /// Queueing the message, false if the queue is full. A closed queue belongs to
+    /// a disconnecting client that unregisters itself.
+    fn send(&self, text: String) -> bool {
+        !matches!(self.sender.try_send(text), Err(TrySendError::Full(_)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn symbols(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn sorted(command: UpstreamCommand) -> UpstreamCommand {
+        match command {
+            UpstreamCommand::Subscribe(mut symbols) => {
+                symbols.sort();
+                UpstreamCommand::Subscribe(symbols)
+            }
+            UpstreamCommand::Unsubscribe(mut symbols) => {
+                symbols.sort();
+                UpstreamCommand::Unsubscribe(symbols)
+            }
+        }
+    }
+
+    fn next_command(commands: &mut UnboundedReceiver<UpstreamCommand>) -> Option<String> {
+        commands
+            .try_recv()
+            .ok()
+            .map(|command| format!("{:?}", sorted(command)))
+    }
+
+    fn price(symbol: &str) -> String {
+        json!({ "event": PRICE_EVENT, "symbol": symbol, "price": 1.5 }).to_string()
+    }
+
+    #[test]
+    fn symbol_is_subscribed_upstream_once() {
+        let (hub, mut commands) = Hub::new();
+        let (first, _first_messages) = hub.register();
+        let (second, _second_messages) = hub.register();
+
+        hub.subscribe(first, symbols(&["AAPL", "EUR/USD"]));
+        assert_eq!(
+            next_command(&mut commands).as_deref(),
+            Some(r#"Subscribe(["AAPL", "EUR/USD"])"#)
+        );
+        hub.subscribe(second, symbols(&["AAPL", "MSFT"]));
+        assert_eq!(
+            next_command(&mut commands).as_deref(),
+            Some(r#"Subscribe(["MSFT"])"#)
+        );
+        hub.subscribe(second, symbols(&["AAPL"]));
+        assert_eq!(next_command(&mut commands), None);
+    }
+
+    #[test]
+    fn symbol_is_released_with_the_last_client() {
+        let (hub, mut commands) = Hub::new();
+        let (first, _first_messages) = hub.register();
+        let (second, _second_messages) = hub.register();
+        hub.subscribe(first, symbols(&["AAPL", "MSFT"]));
+        hub.subscribe(second, symbols(&["AAPL"]));
+        while next_command(&mut commands).is_some() {}
+
+        hub.unsubscribe(first, symbols(&["AAPL"]));
+        assert_eq!(next_command(&mut commands), None);
+        hub.reset(first);
+        assert_eq!(
+            next_command(&mut commands).as_deref(),
+            Some(r#"Unsubscribe(["MSFT"])"#)
+        );
+        hub.unregister(second);
+        assert_eq!(
+            next_command(&mut commands).as_deref(),
+            Some(r#"Unsubscribe(["AAPL"])"#)
+        );
+        assert!(hub.upstream_connected(&mut commands).is_empty());
+    }
+
+    #[test]
+    fn commands_queued_while_down_are_coalesced_into_the_resubscription() {
+        let (hub, mut commands) = Hub::new();
+        let (client, _messages) = hub.register();
+        for _ in 0..100 {
+            hub.subscribe(client, symbols(&["AAPL", "MSFT"]));
+            hub.unsubscribe(client, symbols(&["MSFT"]));
+        }
+        hub.subscribe(client, symbols(&["EUR/USD"]));
+
+        let mut resubscribed = hub.upstream_connected(&mut commands);
+        resubscribed.sort();
+        assert_eq!(resubscribed, ["AAPL", "EUR/USD"]);
+        assert_eq!(next_command(&mut commands), None);
+        // later changes go upstream as usual
+        hub.unsubscribe(client, symbols(&["AAPL"]));
+        assert_eq!(
+            next_command(&mut commands).as_deref(),
+            Some(r#"Unsubscribe(["AAPL"])"#)
+        );
+    }
+
+    #[test]
+    fn price_goes_to_the_watching_clients() {
+        let (hub, _commands) = Hub::new();
+        let (first, mut first_messages) = hub.register();
+        let (second, mut second_messages) = hub.register();
+        hub.subscribe(first, symbols(&["AAPL"]));
+        hub.subscribe(second, symbols(&["MSFT"]));
+
+        assert!(hub.dispatch(price("AAPL")));
+        assert_eq!(first_messages.try_recv().ok(), Some(price("AAPL")));
+        assert!(second_messages.try_recv().is_err());
+        assert!(!hub.dispatch(json!({ "event": HEARTBEAT_EVENT }).to_string()));
+        assert!(!hub.dispatch("not json".to_owned()));
+        assert!(first_messages.try_recv().is_err());
+    }
+
+    #[test]
+    fn subscribe_status_is_replayed_to_later_clients() {
+        let (hub, mut commands) = Hub::new();
+        let (first, mut first_messages) = hub.register();
+        hub.subscribe(first, symbols(&["AAPL", "BAD"]));
+        let status = json!({
+            "event": SUBSCRIBE_STATUS_EVENT,
+            "status": "error",
+            "success": [{ "symbol": "AAPL", "exchange": "NASDAQ" }],
+            "fails": [{ "symbol": "BAD" }],
+        });
+        hub.dispatch(status.to_string());
+        let forwarded: Value = serde_json::from_str(&first_messages.try_recv().unwrap()).unwrap();
+        assert_eq!(forwarded["success"], status["success"]);
+        assert_eq!(forwarded["fails"], status["fails"]);
+
+        let (second, mut second_messages) = hub.register();
+        hub.subscribe(second, symbols(&["BAD"]));
+        let replayed: Value = serde_json::from_str(&second_messages.try_recv().unwrap()).unwrap();
+        assert_eq!(replayed["success"], json!([]));
+        assert_eq!(replayed["fails"], status["fails"]);
+
+        // a new upstream connection reports the statuses again
+        let mut resubscribed = hub.upstream_connected(&mut commands);
+        resubscribed.sort();
+        assert_eq!(resubscribed, symbols(&["AAPL", "BAD"]));
+        let (third, mut third_messages) = hub.register();
+        hub.subscribe(third, symbols(&["AAPL"]));
+        assert!(third_messages.try_recv().is_err());
+    }
+
+    #[test]
+    fn lagging_client_is_disconnected() {
+        let (hub, mut commands) = Hub::new();
+        let (slow, mut slow_messages) = hub.register();
+        let (fast, mut fast_messages) = hub.register();
+        hub.subscribe(slow, symbols(&["AAPL", "MSFT"]));
+        hub.subscribe(fast, symbols(&["AAPL"]));
+        while next_command(&mut commands).is_some() {}
+
+        for _ in 0..CLIENT_QUEUE_CAPACITY {
+            hub.dispatch(price("AAPL"));
+            fast_messages.try_recv().unwrap();
+        }
+        hub.dispatch(price("AAPL"));
+        assert_eq!(
+            next_command(&mut commands).as_deref(),
+            Some(r#"Unsubscribe(["MSFT"])"#)
+        );
+        assert_eq!(fast_messages.try_recv().ok(), Some(price("AAPL")));
+        // the queued messages are still delivered, then the queue closes
+        let queued = std::iter::from_fn(|| slow_messages.try_recv().ok()).count();
+        assert_eq!(queued, CLIENT_QUEUE_CAPACITY);
+        assert!(slow_messages.is_closed());
+    }
+}
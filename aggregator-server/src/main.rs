@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use axum::routing::get;
+use axum::{Json, Router};
+use dotenv::dotenv;
+use log::info;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tower_http::services::ServeDir;
+
+use crate::config::ServerConfig;
+use crate::error::ServerError;
+use crate::hub::Hub;
+use crate::proxy::PROXIED_ROUTES;
+
+/// Reconnection backoff, the same as of the dashboard price feeds
+#[path = "../../dashboard/src/common/backoff.rs"]
+mod backoff;
+/// Browser web socket clients
+mod clients;
+/// Server configuration
+mod config;
+/// Server error
+mod error;
+/// Upstream subscription multiplexer
+mod hub;
+/// Twelve Data web socket protocol
+mod protocol;
+/// Rest api proxy
+mod proxy;
+/// Upstream web socket connection
+mod upstream;
+
+/// Shared handler state
+#[derive(Clone)]
+pub struct AppState {
+    config: Arc<ServerConfig>,
+    hub: Arc<Hub>,
+    http: reqwest::Client,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), ServerError> {
+    // .env is optional; variables from the shell environment take precedence
+    dotenv().ok();
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let config = Arc::new(ServerConfig::from_env()?);
+    let (hub, commands) = Hub::new();
+    tokio::spawn(upstream::run(config.clone(), hub.clone(), commands));
+
+    let mut router = Router::new()
+        .route(
+            config.real_time_price_route.as_str(),
+            get(clients::price_socket),
+        )
+        .route("/config.json", get(dashboard_config));
+    for route in PROXIED_ROUTES {
+        router = router.route(route, get(proxy::proxy_rest));
+    }
+    let router = router
+        .fallback_service(ServeDir::new(config.static_dir.as_str()))
+        .with_state(AppState {
+            config: config.clone(),
+            hub,
+            http: reqwest::Client::new(),
+        });
+
+    let listener = TcpListener::bind(config.server_address.as_str()).await?;
+    info!(
+        "Market Aggregator proxy listening on {}",
+        config.server_address
+    );
+    axum::serve(listener, router).await?;
+    Ok(())
+}
+
+/// Dashboard configuration pointing every request to this server.
+/// Empty addresses mean the origin the dashboard was loaded from.
+async fn dashboard_config() -> Json<Value> {
+    Json(json!({
+        "api_key": "",
+        "ws_address": "",
+        "rest_address": "",
+    }))
+}
@@ -0,0 +1,78 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// Event types of the Twelve Data real-time price stream
+pub const PRICE_EVENT: &str = "price";
+pub const SUBSCRIBE_STATUS_EVENT: &str = "subscribe-status";
+pub const HEARTBEAT_EVENT: &str = "heartbeat";
+
+/// Client action message, the same format as accepted by Twelve Data
+#[derive(Deserialize, Debug)]
+pub struct ActionMessage {
+    pub action: String,
+    #[serde(default)]
+    pub params: ActionParams,
+}
+
+/// Action parameters
+#[derive(Deserialize, Debug, Default)]
+pub struct ActionParams {
+    /// Comma separated symbols
+    #[serde(default)]
+    pub symbols: String,
+}
+
+impl ActionParams {
+    pub fn symbols(&self) -> Vec<String> {
+        self.symbols
+            .split(',')
+            .map(str::trim)
+            .filter(|symbol| !symbol.is_empty())
+            .map(str::to_owned)
+            .collect()
+    }
+}
+
+/// Upstream event header
+#[derive(Deserialize, Debug, Default)]
+pub struct UpstreamEvent {
+    pub event: String,
+    #[serde(default)]
+    pub symbol: String,
+}
+
+/// Action message sent upstream
+pub fn action_message(action: &str, symbols: &[String]) -> String {
+    json!({
+        "action": action,
+        "params": { "symbols": symbols.join(",") }
+    })
+    .to_string()
+}
+
+/// Heartbeat action sent upstream
+pub fn heartbeat_message() -> String {
+    json!({ "action": "heartbeat" }).to_string()
+}
+
+/// Heartbeat reply sent to the browser
+pub fn heartbeat_reply() -> String {
+    json!({ "event": HEARTBEAT_EVENT, "status": "ok" }).to_string()
+}
+
+/// Subscribe status message sent to the browser
+pub fn subscribe_status_message(success: Vec<Value>, fails: Vec<Value>) -> String {
+    let status = if fails.is_empty() { "ok" } else { "error" };
+    json!({
+        "event": SUBSCRIBE_STATUS_EVENT,
+        "status": status,
+        "success": success,
+        "fails": fails,
+    })
+    .to_string()
+}
+
+/// Symbol of a subscribe status entry
+pub fn entry_symbol(entry: &Value) -> Option<&str> {
+    entry.get("symbol").and_then(Value::as_str)
+}
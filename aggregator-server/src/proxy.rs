@@ -0,0 +1,125 @@
+use axum::body::Body;
+use axum::extract::{OriginalUri, RawQuery, State};
+use axum::http::header;
+use axum::response::Response;
+use log::debug;
+
+use crate::error::ServerError;
+use crate::AppState;
+
+/// Twelve Data rest routes available through the proxy
+pub const PROXIED_ROUTES: [&str; 6] = [
+    "/quote",
+    "/eod",
+    "/indices",
+    "/stocks",
+    "/time_series",
+    "/symbol_search",
+];
+
+/// Forwarding a rest request upstream with the API key attached
+pub async fn proxy_rest(
+    State(state): State<AppState>,
+    OriginalUri(uri): OriginalUri,
+    RawQuery(query): RawQuery,
+) -> Result<Response, ServerError> {
+    let url = format!(
+        "{}{}?{}",
+        state.config.rest_address,
+        uri.path(),
+        with_api_key(query.as_deref().unwrap_or_default(), &state.config.api_key)
+    );
+    debug!("proxy_rest. route = {}", uri.path());
+    let upstream = state.http.get(url).send().await?;
+    let status = upstream.status();
+    let content_type = upstream
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("application/json")
+        .to_owned();
+    let body = upstream.bytes().await?;
+    Ok(Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, content_type)
+        .body(Body::from(body))
+        .unwrap_or_default())
+}
+
+/// Replacing any client supplied key with the server one
+fn with_api_key(query: &str, api_key: &str) -> String {
+    let api_key_param = format!("apikey={api_key}");
+    query
+        .split('&')
+        .filter(|param| !param.is_empty() && !is_api_key(param))
+        .chain(std::iter::once(api_key_param.as_str()))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// The parameter name is compared the way the upstream reads it: case-insensitive
+/// and percent-decoded, so "APIKEY" or "api%6Bey" do not pass a client key through
+fn is_api_key(param: &str) -> bool {
+    let name = param.split_once('=').map_or(param, |(name, _)| name);
+    percent_decode(name).eq_ignore_ascii_case(b"apikey")
+}
+
+fn percent_decode(text: &str) -> Vec<u8> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = bytes
+            .get(index + 1..index + 3)
+            .filter(|_| bytes[index] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    decoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_key_is_appended() {
+        assert_eq!(with_api_key("", "secret"), "apikey=secret");
+        assert_eq!(
+            with_api_key("symbol=AAPL&&interval=1day", "secret"),
+            "symbol=AAPL&interval=1day&apikey=secret"
+        );
+    }
+
+    #[test]
+    fn client_keys_are_stripped_in_any_spelling() {
+        for query in [
+            "apikey=client&symbol=AAPL",
+            "APIKEY=client&symbol=AAPL",
+            "symbol=AAPL&ApiKey=client",
+            "api%6Bey=client&symbol=AAPL",
+            "%41PIKEY=client&symbol=AAPL&apikey",
+        ] {
+            assert_eq!(
+                with_api_key(query, "secret"),
+                "symbol=AAPL&apikey=secret",
+                "{query}"
+            );
+        }
+        // only the exact name is a key
+        assert_eq!(
+            with_api_key("apikeys=1&my_apikey=2", "secret"),
+            "apikeys=1&my_apikey=2&apikey=secret"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures::{SinkExt, StreamExt};
+use log::{info, warn};
+use rand::Rng;
+use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::time::{interval, sleep, MissedTickBehavior};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::backoff::Backoff;
+use crate::config::ServerConfig;
+use crate::hub::{Hub, UpstreamCommand};
+use crate::protocol::{action_message, heartbeat_message};
+
+const NINE_SEC: Duration = Duration::from_secs(9);
+
+/// Keeps the single upstream Twelve Data web socket connection and feeds the hub
+pub async fn run(
+    config: Arc<ServerConfig>,
+    hub: Arc<Hub>,
+    mut commands: UnboundedReceiver<UpstreamCommand>,
+) {
+    let url = config.upstream_ws_url();
+    let mut backoff = Backoff::default();
+    loop {
+        let connected_at = Instant::now();
+        let mut received_price = false;
+        match connect_async(url.as_str()).await {
+            Ok((stream, _)) => {
+                info!("Upstream web socket connected");
+                let (mut writer, mut reader) = stream.split();
+                let symbols = hub.upstream_connected(&mut commands);
+                if !symbols.is_empty() {
+                    let msg = action_message("subscribe", &symbols);
+                    if let Err(error) = writer.send(Message::Text(msg)).await {
+                        warn!("Upstream subscribe error: {error}");
+                    }
+                }
+                let mut heartbeat = interval(NINE_SEC);
+                heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                loop {
+                    let outgoing = tokio::select! {
+                        message = reader.next() => match message {
+                            Some(Ok(Message::Text(text))) => {
+                                received_price |= hub.dispatch(text);
+                                continue;
+                            }
+                            Some(Ok(Message::Close(frame))) => {
+                                warn!("Upstream web socket closed: {frame:?}");
+                                break;
+                            }
+                            Some(Ok(_)) => continue,
+                            Some(Err(error)) => {
+                                warn!("Upstream web socket error: {error}");
+                                break;
+                            }
+                            None => break,
+                        },
+                        command = commands.recv() => match command {
+                            Some(UpstreamCommand::Subscribe(symbols)) => {
+                                action_message("subscribe", &symbols)
+                            }
+                            Some(UpstreamCommand::Unsubscribe(symbols)) => {
+                                action_message("unsubscribe", &symbols)
+                            }
+                            // the hub is gone, the server is shutting down
+                            None => return,
+                        },
+                        _ = heartbeat.tick() => heartbeat_message(),
+                    };
+                    if let Err(error) = writer.send(Message::Text(outgoing)).await {
+                        warn!("Upstream web socket send error: {error}");
+                        break;
+                    }
+                }
+            }
+            Err(error) => warn!("Upstream web socket connection error: {error}"),
+        }
+        backoff.connection_closed(received_price, connected_at.elapsed());
+        let delay = backoff.next_delay(rand::thread_rng().gen_range(0.0..1.0));
+        let attempt = backoff.attempt();
+        warn!("Upstream web socket reconnection attempt {attempt} in {delay:?}");
+        sleep(delay).await;
+    }
+}
@@ -0,0 +1,439 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::common::freshness::QuoteFreshness;
+
+/// Number of fired alerts kept in the history
+pub const HISTORY_CAPACITY: usize = 100;
+/// Default pause between two alerts of the same rule
+pub const DEFAULT_COOLDOWN_SECS: u64 = 300;
+
+/// Kind of an alert condition, without the threshold
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum AlertKind {
+    PriceCrosses,
+    ChangeBeyond,
+    SpreadWider,
+    Stale,
+}
+
+impl AlertKind {
+    /// Kinds in the editor order
+    pub const ALL: [AlertKind; 4] = [
+        AlertKind::PriceCrosses,
+        AlertKind::ChangeBeyond,
+        AlertKind::SpreadWider,
+        AlertKind::Stale,
+    ];
+}
+
+/// Condition of an alert rule
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum AlertCondition {
+    /// The price moves through the level in either direction
+    #[serde(rename = "price_crosses")]
+    PriceCrosses { level: Decimal },
+    /// The absolute percent change against the selected baseline reaches the threshold
+    #[serde(rename = "change_beyond")]
+    ChangeBeyond { percent: Decimal },
+    /// Ask minus bid is wider than the spread
+    #[serde(rename = "spread_wider")]
+    SpreadWider { spread: Decimal },
+    /// No ticks for longer than the staleness threshold of the quote type
+    #[serde(rename = "stale")]
+    Stale,
+}
+
+impl AlertCondition {
+    /// Condition of the kind, the threshold is ignored for stale quotes
+    pub fn new(kind: AlertKind, threshold: Decimal) -> Self {
+        match kind {
+            AlertKind::PriceCrosses => AlertCondition::PriceCrosses { level: threshold },
+            AlertKind::ChangeBeyond => AlertCondition::ChangeBeyond { percent: threshold },
+            AlertKind::SpreadWider => AlertCondition::SpreadWider { spread: threshold },
+            AlertKind::Stale => AlertCondition::Stale,
+        }
+    }
+
+    pub fn kind(&self) -> AlertKind {
+        match self {
+            AlertCondition::PriceCrosses { .. } => AlertKind::PriceCrosses,
+            AlertCondition::ChangeBeyond { .. } => AlertKind::ChangeBeyond,
+            AlertCondition::SpreadWider { .. } => AlertKind::SpreadWider,
+            AlertCondition::Stale => AlertKind::Stale,
+        }
+    }
+
+    pub fn threshold(&self) -> Option<Decimal> {
+        match *self {
+            AlertCondition::PriceCrosses { level } => Some(level),
+            AlertCondition::ChangeBeyond { percent } => Some(percent),
+            AlertCondition::SpreadWider { spread } => Some(spread),
+            AlertCondition::Stale => None,
+        }
+    }
+}
+
+/// Alert rule of a symbol
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct AlertRule {
+    /// Unique id of the rule
+    pub id: u64,
+    pub symbol: String,
+    pub condition: AlertCondition,
+    /// Minimum pause between two alerts of the rule
+    #[serde(default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+    /// Disabled rules are kept but not evaluated
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_cooldown_secs() -> u64 {
+    DEFAULT_COOLDOWN_SECS
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Fired alert
+#[derive(Serialize, Deserialize, PartialEq, Clone, Debug)]
+pub struct AlertEvent {
+    pub rule_id: u64,
+    pub symbol: String,
+    pub condition: AlertCondition,
+    /// Price, percent change or spread that fired the alert, `None` for stale quotes
+    pub value: Option<Decimal>,
+    /// Browser clock milliseconds
+    pub fired_at: f64,
+}
+
+/// Market state of a symbol on a tick
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct AlertInput {
+    pub price: Decimal,
+    pub bid: Option<Decimal>,
+    pub ask: Option<Decimal>,
+    /// Percent change against the selected baseline
+    pub change_percent: Option<Decimal>,
+}
+
+/// Evaluates the rules on every tick and freshness check. A rule fires when its condition
+/// starts to hold, not while it keeps holding, and not again within its cooldown.
+#[derive(Clone, Default, Debug)]
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    /// Id of the next rule, ids of the deleted rules are never reused
+    next_id: u64,
+    /// Fired alerts, the newest first
+    history: VecDeque<AlertEvent>,
+    /// The previous price by symbol, for the level crosses
+    last_price: HashMap<String, Decimal>,
+    /// Rules whose condition held at the last evaluation
+    active: HashSet<u64>,
+    /// The last alert time by rule, milliseconds
+    last_fired: HashMap<u64, f64>,
+}
+
+impl AlertEngine {
+    /// Engine of the saved rules and history. The saved next id is raised above the ids
+    /// of the rules and the history, it is missing in the data saved before it was kept.
+    pub fn new(rules: Vec<AlertRule>, history: VecDeque<AlertEvent>, next_id: u64) -> Self {
+        let used = rules
+            .iter()
+            .map(|rule| rule.id)
+            .chain(history.iter().map(|event| event.rule_id))
+            .max()
+            .map_or(1, |id| id + 1);
+        Self {
+            rules,
+            history,
+            next_id: next_id.max(used),
+            ..Default::default()
+        }
+    }
+
+    pub fn rules(&self) -> &[AlertRule] {
+        &self.rules
+    }
+
+    pub fn history(&self) -> &VecDeque<AlertEvent> {
+        &self.history
+    }
+
+    pub fn next_id(&self) -> u64 {
+        self.next_id
+    }
+    /// Adding a rule with a new id
+    pub fn add_rule(&mut self, symbol: String, condition: AlertCondition, cooldown_secs: u64) {
+        let id = self.next_id.max(1);
+        self.next_id = id + 1;
+        self.rules.push(AlertRule {
+            id,
+            symbol,
+            condition,
+            cooldown_secs,
+            enabled: true,
+        });
+    }
+    /// Enabling or disabling the rule, false if there is no such rule
+    pub fn toggle_rule(&mut self, id: u64) -> bool {
+        let Some(rule) = self.rules.iter_mut().find(|rule| rule.id == id) else {
+            return false;
+        };
+        rule.enabled = !rule.enabled;
+        self.active.remove(&id);
+        true
+    }
+    /// Removing the rule, false if there is no such rule
+    pub fn remove_rule(&mut self, id: u64) -> bool {
+        let count = self.rules.len();
+        self.rules.retain(|rule| rule.id != id);
+        self.active.remove(&id);
+        self.last_fired.remove(&id);
+        self.rules.len() != count
+    }
+
+    pub fn clear_history(&mut self) -> bool {
+        let changed = !self.history.is_empty();
+        self.history.clear();
+        changed
+    }
+    /// Price, change and spread rules of the symbol on a tick
+    pub fn on_tick(&mut self, symbol: &str, input: AlertInput, now: f64) -> Vec<AlertEvent> {
+        let previous = self.last_price.insert(symbol.to_owned(), input.price);
+        let spread = match (input.bid, input.ask) {
+            (Some(bid), Some(ask)) => Some(ask - bid),
+            _ => None,
+        };
+        self.evaluate(symbol, now, |condition| match condition {
+            AlertCondition::PriceCrosses { level } => {
+                let crossed = previous.is_some_and(|previous| {
+                    (previous < level && input.price >= level)
+                        || (previous > level && input.price <= level)
+                });
+                Some((crossed, Some(input.price)))
+            }
+            AlertCondition::ChangeBeyond { percent } => Some((
+                input
+                    .change_percent
+                    .is_some_and(|change| change.abs() >= percent),
+                input.change_percent,
+            )),
+            AlertCondition::SpreadWider { spread: limit } => {
+                Some((spread.is_some_and(|spread| spread > limit), spread))
+            }
+            AlertCondition::Stale => None,
+        })
+    }
+    /// Stale quote rules after the freshness check
+    pub fn on_freshness(
+        &mut self,
+        freshness: &HashMap<String, QuoteFreshness>,
+        now: f64,
+    ) -> Vec<AlertEvent> {
+        let stale: HashSet<&str> = freshness
+            .iter()
+            .filter(|(_, quote)| quote.stale)
+            .map(|(symbol, _)| symbol.as_str())
+            .collect();
+        let symbols: HashSet<String> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.condition == AlertCondition::Stale)
+            .map(|rule| rule.symbol.clone())
+            .collect();
+        symbols
+            .iter()
+            .flat_map(|symbol| {
+                let is_stale = stale.contains(symbol.as_str());
+                self.evaluate(symbol, now, |condition| {
+                    (condition == AlertCondition::Stale).then_some((is_stale, None))
+                })
+            })
+            .collect()
+    }
+    /// Evaluating the enabled rules of the symbol. The check returns whether the condition holds
+    /// and the value that fired it, or `None` for conditions it does not evaluate.
+    fn evaluate(
+        &mut self,
+        symbol: &str,
+        now: f64,
+        check: impl Fn(AlertCondition) -> Option<(bool, Option<Decimal>)>,
+    ) -> Vec<AlertEvent> {
+        let mut fired = Vec::new();
+        for rule in &self.rules {
+            if !rule.enabled || rule.symbol != symbol {
+                continue;
+            }
+            let Some((holds, value)) = check(rule.condition) else {
+                continue;
+            };
+            if !holds {
+                self.active.remove(&rule.id);
+                continue;
+            }
+            // still holding since the last evaluation
+            if !self.active.insert(rule.id) {
+                continue;
+            }
+            let cooldown_ms = rule.cooldown_secs as f64 * 1000.0;
+            if let Some(&last) = self.last_fired.get(&rule.id) {
+                if now - last < cooldown_ms {
+                    continue;
+                }
+            }
+            self.last_fired.insert(rule.id, now);
+            fired.push(AlertEvent {
+                rule_id: rule.id,
+                symbol: rule.symbol.clone(),
+                condition: rule.condition,
+                value,
+                fired_at: now,
+            });
+        }
+        for event in &fired {
+            self.history.push_front(event.clone());
+        }
+        self.history.truncate(HISTORY_CAPACITY);
+        fired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    fn price(price: Decimal) -> AlertInput {
+        AlertInput {
+            price,
+            bid: None,
+            ask: None,
+            change_percent: None,
+        }
+    }
+
+    fn engine(condition: AlertCondition, cooldown_secs: u64) -> AlertEngine {
+        let mut engine = AlertEngine::default();
+        engine.add_rule("AAPL".to_owned(), condition, cooldown_secs);
+        engine
+    }
+
+    #[test]
+    fn rule_ids_are_not_reused() {
+        let mut engine = AlertEngine::default();
+        engine.add_rule("AAPL".to_owned(), AlertCondition::Stale, 0);
+        engine.add_rule("MSFT".to_owned(), AlertCondition::Stale, 0);
+        assert!(engine.remove_rule(2));
+        engine.add_rule("NVDA".to_owned(), AlertCondition::Stale, 0);
+        let ids: Vec<u64> = engine.rules().iter().map(|rule| rule.id).collect();
+        assert_eq!(ids, [1, 3]);
+        assert_eq!(engine.next_id(), 4);
+    }
+
+    #[test]
+    fn next_id_is_above_the_saved_rules_and_history() {
+        let mut fired = engine(AlertCondition::Stale, 0);
+        let rules = fired.rules().to_vec();
+        let freshness = HashMap::from([(
+            "AAPL".to_owned(),
+            QuoteFreshness {
+                stale: true,
+                ..Default::default()
+            },
+        )]);
+        fired.on_freshness(&freshness, 0.0);
+        let mut history = fired.history().clone();
+        history[0].rule_id = 7;
+        assert_eq!(
+            AlertEngine::new(rules.clone(), VecDeque::new(), 0).next_id(),
+            2
+        );
+        assert_eq!(AlertEngine::new(rules.clone(), history, 0).next_id(), 8);
+        assert_eq!(AlertEngine::new(rules, VecDeque::new(), 10).next_id(), 10);
+    }
+
+    #[test]
+    fn price_cross_fires_in_both_directions() {
+        let mut engine = engine(AlertCondition::PriceCrosses { level: dec("100") }, 0);
+        // the first tick has nothing to cross from
+        assert!(engine.on_tick("AAPL", price(dec("101")), 0.0).is_empty());
+        assert!(engine.on_tick("AAPL", price(dec("102")), 1.0).is_empty());
+        let fired = engine.on_tick("AAPL", price(dec("99")), 2.0);
+        assert_eq!(fired.len(), 1);
+        assert_eq!(fired[0].value, Some(dec("99")));
+        assert!(engine.on_tick("AAPL", price(dec("98")), 3.0).is_empty());
+        assert_eq!(engine.on_tick("AAPL", price(dec("100")), 4.0).len(), 1);
+        // other symbols do not cross the level
+        assert!(engine.on_tick("MSFT", price(dec("50")), 5.0).is_empty());
+        assert!(engine.on_tick("MSFT", price(dec("150")), 6.0).is_empty());
+    }
+
+    #[test]
+    fn rule_fires_when_the_condition_starts_to_hold() {
+        let mut engine = engine(AlertCondition::SpreadWider { spread: dec("0.5") }, 0);
+        let quote = |bid, ask| AlertInput {
+            bid: Some(bid),
+            ask: Some(ask),
+            ..price(dec("100"))
+        };
+        assert_eq!(
+            engine
+                .on_tick("AAPL", quote(dec("99"), dec("100")), 0.0)
+                .len(),
+            1
+        );
+        assert!(engine
+            .on_tick("AAPL", quote(dec("99"), dec("101")), 1.0)
+            .is_empty());
+        assert!(engine
+            .on_tick("AAPL", quote(dec("99.8"), dec("100")), 2.0)
+            .is_empty());
+        assert_eq!(
+            engine
+                .on_tick("AAPL", quote(dec("99"), dec("100")), 3.0)
+                .len(),
+            1
+        );
+        assert_eq!(engine.history().len(), 2);
+    }
+
+    #[test]
+    fn cooldown_delays_the_next_alert() {
+        let mut engine = engine(AlertCondition::ChangeBeyond { percent: dec("2") }, 60);
+        let change = |percent| AlertInput {
+            change_percent: Some(percent),
+            ..price(dec("100"))
+        };
+        assert_eq!(engine.on_tick("AAPL", change(dec("-2.5")), 0.0).len(), 1);
+        assert!(engine.on_tick("AAPL", change(dec("1")), 1_000.0).is_empty());
+        // holds again within the cooldown, no alert
+        assert!(engine
+            .on_tick("AAPL", change(dec("3")), 30_000.0)
+            .is_empty());
+        assert!(engine
+            .on_tick("AAPL", change(dec("1")), 40_000.0)
+            .is_empty());
+        assert_eq!(engine.on_tick("AAPL", change(dec("3")), 61_000.0).len(), 1);
+    }
+
+    #[test]
+    fn disabled_rules_do_not_fire() {
+        let mut engine = engine(AlertCondition::ChangeBeyond { percent: dec("2") }, 0);
+        let change = AlertInput {
+            change_percent: Some(dec("5")),
+            ..price(dec("100"))
+        };
+        assert!(engine.toggle_rule(1));
+        assert!(engine.on_tick("AAPL", change, 0.0).is_empty());
+        assert!(engine.toggle_rule(1));
+        assert_eq!(engine.on_tick("AAPL", change, 1.0).len(), 1);
+    }
+}
@@ -0,0 +1,348 @@
+use log::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::common::enums::{BinanceStream, ChangeBaseline, Language, ProviderKind, QuoteType};
+use crate::common::error::MarketError;
+use crate::common::format::DisplayFormat;
+use crate::common::fx::is_currency_code;
+use crate::common::MarketResult;
+
+/// Partial configuration from a single source (config.json, localStorage, URL query).
+/// Missing fields keep the value of the previous source.
+pub type AppConfigLayer = Map<String, Value>;
+
+/// Fields the localStorage and URL query overrides may change: the user interface preferences
+/// and the provider switch
+const PREFERENCE_FIELDS: [&str; 7] = [
+    "language",
+    "display_timezone",
+    "locale",
+    "change_baseline",
+    "base_currency",
+    "provider",
+    "crypto_provider",
+];
+/// Server addresses, overridable only while config.json has no API key,
+/// so a crafted link cannot send the key to another host
+const ENDPOINT_FIELDS: [&str; 4] = [
+    "ws_address",
+    "rest_address",
+    "binance_ws_address",
+    "mock_session_url",
+];
+
+/// Runtime application configuration
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct AppConfig {
+    /// Twelve Data API key, empty when requests go through the proxy server
+    pub api_key: String,
+    /// Web socket server address, empty for the dashboard origin
+    pub ws_address: String,
+    /// Rest api server address, empty for the dashboard origin
+    pub rest_address: String,
+    /// Real-time price web socket route
+    pub real_time_price_route: String,
+    /// End of day price route
+    pub eod_route: String,
+    /// Latest quote route
+    pub quote_route: String,
+    /// Indices reference data route
+    pub indices_route: String,
+    /// Stocks reference data route
+    pub stocks_route: String,
+    /// Historical time series route
+    pub time_series_route: String,
+    /// Instrument search route
+    pub symbol_search_route: String,
+    /// Provider of reference data, quotes and price streams
+    pub provider: ProviderKind,
+    /// Provider of the cryptocurrency price stream, `None` for the main provider
+    pub crypto_provider: Option<ProviderKind>,
+    /// Binance compatible web socket server address
+    pub binance_ws_address: String,
+    /// Binance stream used for crypto quotes
+    pub binance_stream: BinanceStream,
+    /// Mock provider replay speed, 1.0 replays the recorded session in real time
+    pub mock_replay_speed: f64,
+    /// URL of a recorded session replayed by the mock provider, empty for the bundled recording
+    pub mock_session_url: String,
+    /// Recording the price feed session from the start
+    pub record_session: bool,
+    /// Seconds without ticks after which a quote is shown as stale
+    pub stale_after_secs: StaleThresholds,
+    /// Seconds without any price after which the feed is reported as delayed
+    pub feed_delay_secs: u64,
+    /// IANA timezone of the displayed times, or `exchange` for the exchange local time
+    pub display_timezone: String,
+    /// Number and date format locale
+    pub locale: String,
+    /// User interface language
+    pub language: Language,
+    /// Reference price of the price changes
+    pub change_baseline: ChangeBaseline,
+    /// Browser notifications of the fired alerts, if the user permits them
+    pub alert_notifications: bool,
+    /// Beep on the fired alerts
+    pub alert_sound: bool,
+    /// Currency of the portfolio totals
+    pub base_currency: String,
+}
+
+/// Staleness thresholds by quote type, seconds
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(default)]
+pub struct StaleThresholds {
+    pub crypto: u64,
+    pub currency: u64,
+    pub indices: u64,
+    pub us_stocks: u64,
+}
+
+impl Default for StaleThresholds {
+    /// Crypto trades around the clock, indices and stocks tick rarely and stop outside the session
+    fn default() -> Self {
+        Self {
+            crypto: 60,
+            currency: 120,
+            indices: 300,
+            us_stocks: 300,
+        }
+    }
+}
+
+impl StaleThresholds {
+    pub fn for_quote_type(&self, quote_type: QuoteType) -> u64 {
+        match quote_type {
+            QuoteType::CryptoCurrency => self.crypto,
+            QuoteType::Currency => self.currency,
+            QuoteType::Indices => self.indices,
+            QuoteType::USStocks => self.us_stocks,
+        }
+    }
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            api_key: String::new(),
+            ws_address: "wss://ws.twelvedata.com/v1".to_owned(),
+            rest_address: "https://api.twelvedata.com".to_owned(),
+            real_time_price_route: "/quotes/price".to_owned(),
+            eod_route: "/eod".to_owned(),
+            quote_route: "/quote".to_owned(),
+            indices_route: "/indices".to_owned(),
+            stocks_route: "/stocks".to_owned(),
+            time_series_route: "/time_series".to_owned(),
+            symbol_search_route: "/symbol_search".to_owned(),
+            provider: ProviderKind::TwelveData,
+            crypto_provider: None,
+            binance_ws_address: "wss://stream.binance.com:9443".to_owned(),
+            binance_stream: BinanceStream::BookTicker,
+            mock_replay_speed: 1.0,
+            mock_session_url: String::new(),
+            record_session: false,
+            stale_after_secs: StaleThresholds::default(),
+            feed_delay_secs: 30,
+            display_timezone: "Europe/Moscow".to_owned(),
+            locale: "ru-RU".to_owned(),
+            language: Language::Ru,
+            change_baseline: ChangeBaseline::Auto,
+            alert_notifications: true,
+            alert_sound: true,
+            base_currency: "USD".to_owned(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// Default configuration as a layer, the base for the other layers
+    pub fn default_layer() -> AppConfigLayer {
+        match serde_json::to_value(Self::default()) {
+            Ok(Value::Object(layer)) => layer,
+            _ => AppConfigLayer::new(),
+        }
+    }
+    /// Configuration from config.json and the user overrides (localStorage, URL query)
+    /// in ascending priority. Overrides of the fields other than the preferences are ignored.
+    pub fn from_sources(
+        file: AppConfigLayer,
+        overrides: impl IntoIterator<Item = AppConfigLayer>,
+    ) -> MarketResult<Self> {
+        let keyed = file
+            .get("api_key")
+            .and_then(Value::as_str)
+            .is_some_and(|api_key| !api_key.is_empty());
+        let overrides: Vec<AppConfigLayer> = overrides
+            .into_iter()
+            .map(|layer| restrict_override(layer, keyed))
+            .collect();
+        Self::from_layers(std::iter::once(file).chain(overrides))
+    }
+    /// Applying configuration layers over the defaults in order and validating the result
+    fn from_layers(layers: impl IntoIterator<Item = AppConfigLayer>) -> MarketResult<Self> {
+        let mut merged = Self::default_layer();
+        for layer in layers {
+            for (field, value) in layer {
+                if merged.contains_key(&field) {
+                    merged.insert(field, value);
+                } else {
+                    warn!("Unknown configuration field {field}");
+                }
+            }
+        }
+        let config: Self = serde_json::from_value(Value::Object(merged))
+            .map_err(|error| MarketError::Config(error.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Time and number formatting, the default one if the configuration is invalid
+    pub fn display_format(&self) -> DisplayFormat {
+        DisplayFormat::new(&self.display_timezone, &self.locale).unwrap_or_default()
+    }
+
+    fn validate(&self) -> MarketResult<()> {
+        // an empty address means the same origin as the dashboard
+        if !self.ws_address.is_empty() && !has_scheme(&self.ws_address, &["ws://", "wss://"]) {
+            return Err(MarketError::Config(format!(
+                "ws_address must start with ws:// or wss://, got \"{}\"",
+                self.ws_address
+            )));
+        }
+        if !self.rest_address.is_empty()
+            && !has_scheme(&self.rest_address, &["http://", "https://"])
+        {
+            return Err(MarketError::Config(format!(
+                "rest_address must start with http:// or https://, got \"{}\"",
+                self.rest_address
+            )));
+        }
+        if !has_scheme(&self.binance_ws_address, &["ws://", "wss://"]) {
+            return Err(MarketError::Config(format!(
+                "binance_ws_address must start with ws:// or wss://, got \"{}\"",
+                self.binance_ws_address
+            )));
+        }
+        if !(self.mock_replay_speed.is_finite() && self.mock_replay_speed > 0.0) {
+            return Err(MarketError::Config(format!(
+                "mock_replay_speed must be a positive number, got {}",
+                self.mock_replay_speed
+            )));
+        }
+        let thresholds = self.stale_after_secs;
+        if QuoteType::ALL
+            .into_iter()
+            .any(|quote_type| thresholds.for_quote_type(quote_type) == 0)
+            || self.feed_delay_secs == 0
+        {
+            return Err(MarketError::Config(
+                "stale_after_secs and feed_delay_secs must be positive".to_owned(),
+            ));
+        }
+        DisplayFormat::new(&self.display_timezone, &self.locale)?;
+        if !is_currency_code(&self.base_currency) {
+            return Err(MarketError::Config(format!(
+                "base_currency must be an upper case currency code like \"USD\", got \"{}\"",
+                self.base_currency
+            )));
+        }
+        let routes = [
+            ("real_time_price_route", &self.real_time_price_route),
+            ("eod_route", &self.eod_route),
+            ("quote_route", &self.quote_route),
+            ("indices_route", &self.indices_route),
+            ("stocks_route", &self.stocks_route),
+            ("time_series_route", &self.time_series_route),
+            ("symbol_search_route", &self.symbol_search_route),
+        ];
+        for (name, route) in routes {
+            if !route.starts_with('/') {
+                return Err(MarketError::Config(format!(
+                    "{name} must start with '/', got \"{route}\""
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn has_scheme(address: &str, schemes: &[&str]) -> bool {
+    schemes
+        .iter()
+        .any(|scheme| address.starts_with(scheme) && address.len() > scheme.len())
+}
+
+/// Override layer without the fields it may not change
+fn restrict_override(layer: AppConfigLayer, keyed: bool) -> AppConfigLayer {
+    layer
+        .into_iter()
+        .filter(|(field, _)| {
+            let allowed = PREFERENCE_FIELDS.contains(&field.as_str())
+                || (!keyed && ENDPOINT_FIELDS.contains(&field.as_str()));
+            if !allowed {
+                warn!("Configuration field {field} cannot be overridden, ignoring");
+            }
+            allowed
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn layer(value: Value) -> AppConfigLayer {
+        match value {
+            Value::Object(layer) => layer,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn overrides_change_the_preferences() {
+        let file = layer(json!({ "api_key": "secret", "language": "ru" }));
+        let storage = layer(json!({ "language": "en", "base_currency": "EUR" }));
+        let query = layer(json!({ "change_baseline": "previous_close", "provider": "mock" }));
+        let config = AppConfig::from_sources(file, [storage, query]).unwrap();
+        assert_eq!(config.language, Language::En);
+        assert_eq!(config.base_currency, "EUR");
+        assert_eq!(config.change_baseline, ChangeBaseline::PreviousClose);
+        assert_eq!(config.provider, ProviderKind::Mock);
+    }
+
+    #[test]
+    fn overrides_cannot_redirect_the_api_key() {
+        let file = layer(json!({ "api_key": "secret" }));
+        let query = layer(json!({
+            "api_key": "other",
+            "rest_address": "https://attacker.example",
+            "ws_address": "wss://attacker.example",
+            "binance_ws_address": "wss://attacker.example",
+            "eod_route": "@attacker.example/",
+            "record_session": true,
+        }));
+        let config = AppConfig::from_sources(file, [query]).unwrap();
+        assert_eq!(
+            config,
+            AppConfig {
+                api_key: "secret".to_owned(),
+                ..AppConfig::default()
+            }
+        );
+    }
+
+    #[test]
+    fn endpoints_are_overridable_without_an_api_key() {
+        let file = layer(json!({ "api_key": "", "rest_address": "" }));
+        let query = layer(json!({
+            "rest_address": "http://127.0.0.1:7015",
+            "api_key": "injected",
+        }));
+        let config = AppConfig::from_sources(file, [query]).unwrap();
+        assert_eq!(config.rest_address, "http://127.0.0.1:7015");
+        assert_eq!(config.api_key, "");
+    }
+}
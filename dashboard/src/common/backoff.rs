@@ -0,0 +1,89 @@
+// Also compiled into the aggregator server, std only
+
+use std::time::Duration;
+
+/// Delay before the first reconnection attempt
+pub const BASE_DELAY: Duration = Duration::from_secs(1);
+/// Upper bound of the reconnection delay
+pub const MAX_DELAY: Duration = Duration::from_secs(60);
+/// Uptime after which a connection without prices still counts as healthy
+pub const MIN_HEALTHY_UPTIME: Duration = Duration::from_secs(30);
+
+/// Consecutive failed connection attempts. Only a healthy connection resets them, one that
+/// delivered a price or stayed open for `MIN_HEALTHY_UPTIME`: a server closing right after
+/// the subscription (bad key, plan limit) is retried with a growing delay.
+#[derive(Default, Debug)]
+pub struct Backoff {
+    attempt: u32,
+}
+
+impl Backoff {
+    /// Closing the connection, healthy ones reset the attempts
+    pub fn connection_closed(&mut self, received_price: bool, uptime: Duration) {
+        if received_price || uptime >= MIN_HEALTHY_UPTIME {
+            self.attempt = 0;
+        }
+    }
+    /// Delay before the next attempt with `random` in [0, 1)
+    pub fn next_delay(&mut self, random: f64) -> Duration {
+        self.attempt = self.attempt.saturating_add(1);
+        backoff_delay(self.attempt, random)
+    }
+    /// Number of the last attempt, 0 after a healthy connection
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+}
+
+/// Exponential backoff with "equal jitter": half of the delay is fixed, the other half is random
+fn backoff_delay(attempt: u32, random: f64) -> Duration {
+    let exponential = BASE_DELAY.saturating_mul(1 << attempt.saturating_sub(1).min(16));
+    let capped = exponential.min(MAX_DELAY);
+    capped / 2 + capped.mul_f64(random / 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_grows_to_the_cap() {
+        assert_eq!(backoff_delay(1, 0.0), Duration::from_millis(500));
+        assert_eq!(backoff_delay(2, 0.0), Duration::from_secs(1));
+        assert_eq!(
+            backoff_delay(3, 0.999),
+            Duration::from_secs(4).mul_f64(0.5 + 0.999 / 2.0)
+        );
+        assert_eq!(backoff_delay(10, 0.0), MAX_DELAY / 2);
+        assert_eq!(backoff_delay(u32::MAX, 1.0), MAX_DELAY);
+    }
+
+    #[test]
+    fn connection_closed_at_once_grows_the_delay() {
+        let mut backoff = Backoff::default();
+        let delays: Vec<Duration> = (0..10)
+            .map(|_| {
+                backoff.connection_closed(false, Duration::from_millis(100));
+                backoff.next_delay(0.0)
+            })
+            .collect();
+        assert_eq!(delays[0], Duration::from_millis(500));
+        assert_eq!(delays[3], Duration::from_secs(4));
+        assert_eq!(delays[9], MAX_DELAY / 2);
+        assert_eq!(backoff.attempt(), 10);
+    }
+
+    #[test]
+    fn healthy_connection_resets_the_delay() {
+        let mut backoff = Backoff::default();
+        for _ in 0..6 {
+            backoff.next_delay(0.0);
+        }
+        backoff.connection_closed(true, Duration::ZERO);
+        assert_eq!(backoff.attempt(), 0);
+        assert_eq!(backoff.next_delay(0.0), BASE_DELAY / 2);
+        backoff.next_delay(0.0);
+        backoff.connection_closed(false, MIN_HEALTHY_UPTIME);
+        assert_eq!(backoff.next_delay(1.0), BASE_DELAY);
+    }
+}
@@ -0,0 +1,317 @@
+use std::collections::{HashMap, VecDeque};
+
+use chrono::{DateTime, NaiveTime, Offset, TimeZone, Timelike};
+use chrono_tz::Tz;
+
+use crate::common::entities::{Bar, PriceTick, Quote};
+use crate::common::enums::TimeSeriesInterval;
+use crate::common::market_hours::{quote_exchange_hours, ExchangeHours};
+
+/// Live candle interval
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum CandleInterval {
+    OneSecond,
+    OneMinute,
+    FiveMinutes,
+    OneHour,
+}
+
+impl CandleInterval {
+    /// Intervals in ascending order
+    pub const ALL: [CandleInterval; 4] = [
+        CandleInterval::OneSecond,
+        CandleInterval::OneMinute,
+        CandleInterval::FiveMinutes,
+        CandleInterval::OneHour,
+    ];
+
+    pub fn seconds(self) -> i64 {
+        match self {
+            CandleInterval::OneSecond => 1,
+            CandleInterval::OneMinute => 60,
+            CandleInterval::FiveMinutes => 5 * 60,
+            CandleInterval::OneHour => 3600,
+        }
+    }
+    /// Live interval matching the time series interval
+    pub fn from_time_series(interval: TimeSeriesInterval) -> Option<Self> {
+        match interval {
+            TimeSeriesInterval::OneMinute => Some(CandleInterval::OneMinute),
+            TimeSeriesInterval::FiveMinutes => Some(CandleInterval::FiveMinutes),
+            TimeSeriesInterval::OneHour => Some(CandleInterval::OneHour),
+            _ => None,
+        }
+    }
+}
+
+/// Exchange local time: hourly candles start at the session open minute,
+/// e.g. 9:30, 10:30... in New York for US stocks
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct ExchangeClock {
+    /// Exchange timezone
+    pub timezone: Tz,
+    /// Regular session opening time in the exchange timezone
+    pub session_open: NaiveTime,
+}
+
+impl Default for ExchangeClock {
+    /// Round the clock markets: cryptocurrencies and currencies
+    fn default() -> Self {
+        Self {
+            timezone: Tz::UTC,
+            session_open: NaiveTime::MIN,
+        }
+    }
+}
+
+impl ExchangeClock {
+    /// Clock of the quote exchange by its MIC code, or by the exchange name
+    pub fn for_quote(quote: &Quote) -> Self {
+        quote_exchange_hours(quote).map_or_else(Self::default, Self::for_hours)
+    }
+
+    fn for_hours(hours: &ExchangeHours) -> Self {
+        Self {
+            timezone: hours.timezone,
+            session_open: NaiveTime::from_num_seconds_from_midnight_opt(hours.open * 60, 0)
+                .unwrap_or(NaiveTime::MIN),
+        }
+    }
+    /// Start of the bucket containing the timestamp. Buckets are aligned in the exchange local time,
+    /// so daylight saving changes and non hour offsets keep candles on the exchange clock.
+    pub fn bucket_start(&self, interval: CandleInterval, timestamp: i64) -> i64 {
+        let offset = DateTime::from_timestamp(timestamp, 0)
+            .map(|utc| {
+                self.timezone
+                    .offset_from_utc_datetime(&utc.naive_utc())
+                    .fix()
+                    .local_minus_utc()
+            })
+            .unwrap_or(0);
+        let local = timestamp + i64::from(offset);
+        let seconds = interval.seconds();
+        let anchor = match interval {
+            CandleInterval::OneHour => {
+                i64::from(self.session_open.num_seconds_from_midnight()) % seconds
+            }
+            _ => 0,
+        };
+        local - (local - anchor).rem_euclid(seconds) - i64::from(offset)
+    }
+}
+
+/// Rolling candles of one symbol at one interval
+#[derive(Clone, Debug)]
+pub struct CandleSeries {
+    interval: CandleInterval,
+    capacity: usize,
+    bars: VecDeque<Bar>,
+    /// The last cumulative day volume, bar volume is its growth
+    last_day_volume: Option<f64>,
+}
+
+impl CandleSeries {
+    pub fn new(interval: CandleInterval, capacity: usize) -> Self {
+        Self {
+            interval,
+            capacity: capacity.max(1),
+            bars: VecDeque::new(),
+            last_day_volume: None,
+        }
+    }
+    /// Adding a tick. Ticks older than the last candle are ignored.
+    pub fn push(&mut self, clock: &ExchangeClock, price: f64, day_volume: f64, timestamp: i64) {
+        let start = clock.bucket_start(self.interval, timestamp);
+        let volume = match self.last_day_volume {
+            Some(last) if day_volume >= last => day_volume - last,
+            // the day volume is reset at the start of a new trading day
+            Some(_) => day_volume,
+            None => 0.0,
+        };
+        match self.bars.back_mut() {
+            Some(bar) if bar.time > start => return,
+            Some(bar) if bar.time == start => {
+                bar.high = bar.high.max(price);
+                bar.low = bar.low.min(price);
+                bar.close = price;
+                bar.volume += volume;
+            }
+            _ => {
+                if self.bars.len() == self.capacity {
+                    self.bars.pop_front();
+                }
+                self.bars.push_back(Bar {
+                    time: start,
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume,
+                });
+            }
+        }
+        if day_volume > 0.0 {
+            self.last_day_volume = Some(day_volume);
+        }
+    }
+    /// The current (open) candle
+    pub fn last(&self) -> Option<&Bar> {
+        self.bars.back()
+    }
+}
+
+/// Builds rolling OHLCV candles at several intervals from the price ticks of every symbol
+#[derive(Clone, Debug)]
+pub struct CandleAggregator {
+    intervals: Vec<CandleInterval>,
+    capacity: usize,
+    clocks: HashMap<String, ExchangeClock>,
+    series: HashMap<String, Vec<CandleSeries>>,
+}
+
+impl CandleAggregator {
+    /// Keeping up to `capacity` candles per symbol and interval
+    pub fn new(intervals: &[CandleInterval], capacity: usize) -> Self {
+        Self {
+            intervals: intervals.to_vec(),
+            capacity,
+            clocks: HashMap::new(),
+            series: HashMap::new(),
+        }
+    }
+    /// Aligning the candles of the symbol to the exchange clock, UTC by default
+    pub fn set_clock(&mut self, symbol: &str, clock: ExchangeClock) {
+        self.clocks.insert(symbol.to_owned(), clock);
+    }
+
+    pub fn push(&mut self, tick: &PriceTick) {
+        let clock = self.clocks.get(&tick.symbol).copied().unwrap_or_default();
+        let series = self.series.entry(tick.symbol.clone()).or_insert_with(|| {
+            self.intervals
+                .iter()
+                .map(|&interval| CandleSeries::new(interval, self.capacity))
+                .collect()
+        });
+        for candles in series {
+            candles.push(&clock, tick.price, tick.day_volume, tick.timestamp);
+        }
+    }
+    /// The current (open) candle of the symbol at every interval
+    pub fn last_candles(&self, symbol: &str) -> HashMap<CandleInterval, Bar> {
+        self.series
+            .get(symbol)
+            .into_iter()
+            .flatten()
+            .filter_map(|series| series.last().map(|bar| (series.interval, *bar)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::common::market_hours::exchange_hours;
+
+    /// Unix timestamp of the local time in the timezone
+    fn local(timezone: Tz, date: (i32, u32, u32), time: (u32, u32)) -> i64 {
+        let naive = NaiveDate::from_ymd_opt(date.0, date.1, date.2)
+            .and_then(|date| date.and_hms_opt(time.0, time.1, 0))
+            .unwrap();
+        timezone
+            .from_local_datetime(&naive)
+            .single()
+            .unwrap()
+            .timestamp()
+    }
+
+    fn new_york() -> ExchangeClock {
+        ExchangeClock::for_hours(exchange_hours("XNYS").unwrap())
+    }
+
+    #[test]
+    fn hourly_candles_start_at_the_session_open_minute() {
+        let clock = new_york();
+        let ny = Tz::America__New_York;
+        let tick = local(ny, (2025, 6, 2), (10, 45));
+        assert_eq!(
+            clock.bucket_start(CandleInterval::OneHour, tick),
+            local(ny, (2025, 6, 2), (10, 30))
+        );
+        assert_eq!(clock.bucket_start(CandleInterval::FiveMinutes, tick), tick);
+        assert_eq!(
+            clock.bucket_start(CandleInterval::OneMinute, tick + 59),
+            tick
+        );
+        // round the clock markets keep whole UTC hours
+        assert_eq!(
+            ExchangeClock::default().bucket_start(CandleInterval::OneHour, tick),
+            tick - 45 * 60
+        );
+    }
+
+    #[test]
+    fn hourly_candles_follow_the_daylight_saving_change() {
+        let clock = new_york();
+        let ny = Tz::America__New_York;
+        // clocks went forward on Sunday, March 9, 2025
+        for date in [(2025, 3, 7), (2025, 3, 10)] {
+            assert_eq!(
+                clock.bucket_start(CandleInterval::OneHour, local(ny, date, (10, 45))),
+                local(ny, date, (10, 30))
+            );
+        }
+        let before = local(ny, (2025, 3, 7), (10, 30));
+        let after = local(ny, (2025, 3, 10), (10, 30));
+        assert_eq!(after - before, 3 * 24 * 3600 - 3600);
+    }
+
+    #[test]
+    fn candles_align_to_a_half_hour_offset() {
+        let kolkata = Tz::Asia__Kolkata;
+        let clock = ExchangeClock {
+            timezone: kolkata,
+            session_open: NaiveTime::from_hms_opt(9, 15, 0).unwrap(),
+        };
+        let tick = local(kolkata, (2025, 1, 6), (10, 22));
+        assert_eq!(
+            clock.bucket_start(CandleInterval::OneHour, tick),
+            local(kolkata, (2025, 1, 6), (10, 15))
+        );
+        assert_eq!(
+            clock.bucket_start(CandleInterval::FiveMinutes, tick),
+            local(kolkata, (2025, 1, 6), (10, 20))
+        );
+    }
+
+    #[test]
+    fn bar_volume_is_the_day_volume_growth() {
+        let clock = ExchangeClock::default();
+        let mut series = CandleSeries::new(CandleInterval::OneMinute, 10);
+        series.push(&clock, 10.0, 1000.0, 60);
+        series.push(&clock, 11.0, 1500.0, 70);
+        series.push(&clock, 9.0, 1600.0, 80);
+        let bar = *series.last().unwrap();
+        assert_eq!(
+            (bar.open, bar.high, bar.low, bar.close, bar.volume),
+            (10.0, 11.0, 9.0, 9.0, 600.0)
+        );
+        // a new trading day starts the cumulative volume over
+        series.push(&clock, 9.5, 200.0, 120);
+        assert_eq!(series.last().unwrap().time, 120);
+        assert_eq!(series.last().unwrap().volume, 200.0);
+        series.push(&clock, 9.6, 250.0, 130);
+        assert_eq!(series.last().unwrap().volume, 250.0);
+    }
+
+    #[test]
+    fn late_ticks_are_ignored() {
+        let clock = ExchangeClock::default();
+        let mut series = CandleSeries::new(CandleInterval::OneMinute, 10);
+        series.push(&clock, 10.0, 0.0, 120);
+        series.push(&clock, 20.0, 0.0, 60);
+        let bar = series.last().unwrap();
+        assert_eq!((bar.time, bar.high), (120, 10.0));
+    }
+}
@@ -0,0 +1,138 @@
+use rust_decimal::Decimal;
+
+use crate::common::entities::Quote;
+use crate::common::enums::{ChangeBaseline, QuoteType};
+use crate::common::price::{Price, PriceChange};
+
+impl ChangeBaseline {
+    /// The baseline used for the quote type: `Auto` is resolved, and the rolling 24 hours
+    /// change is only known for crypto
+    pub fn resolve(self, quote_type: QuoteType) -> ChangeBaseline {
+        match (self, quote_type) {
+            (ChangeBaseline::Auto | ChangeBaseline::Rolling24h, QuoteType::CryptoCurrency) => {
+                ChangeBaseline::Rolling24h
+            }
+            (ChangeBaseline::Auto | ChangeBaseline::Rolling24h, _) => ChangeBaseline::PreviousClose,
+            (baseline, _) => baseline,
+        }
+    }
+}
+
+/// Reference price of the change by the last quote. The rolling price is derived from the
+/// 24 hours percent change of the quote close.
+pub fn change_base(baseline: ChangeBaseline, quote_type: QuoteType, quote: &Quote) -> Price {
+    match baseline.resolve(quote_type) {
+        ChangeBaseline::SessionOpen => quote.open,
+        ChangeBaseline::Rolling24h => {
+            let (Some(close), Some(percent)) =
+                (quote.close.value(), quote.rolling_1d_change.value())
+            else {
+                return Price::default();
+            };
+            let base = close
+                .checked_mul(Decimal::ONE_HUNDRED)
+                .and_then(|value| value.checked_div(Decimal::ONE_HUNDRED + percent));
+            base.map(Price::new).unwrap_or_default()
+        }
+        ChangeBaseline::Auto | ChangeBaseline::PreviousClose => quote.previous_close,
+    }
+}
+
+/// Change of the live price, or of the last quote close before the first tick, against the
+/// baseline. The same calculation serves the REST snapshot and the price stream.
+pub fn price_change(
+    baseline: ChangeBaseline,
+    quote_type: QuoteType,
+    quote: Option<&Quote>,
+    live_price: Option<Price>,
+) -> Option<PriceChange> {
+    let quote = quote?;
+    let price = live_price.unwrap_or(quote.close);
+    PriceChange::between(price, change_base(baseline, quote_type, quote))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    fn quote() -> Quote {
+        serde_json::from_value(serde_json::json!({
+            "symbol": "BTC/USD",
+            "name": "Bitcoin US Dollar",
+            "exchange": "Coinbase Pro",
+            "datetime": "2025-07-01",
+            "timestamp": 0,
+            "open": "98",
+            "close": "110",
+            "previous_close": "100",
+            "rolling_1d_change": "10",
+            "is_market_open": true,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn auto_baseline_depends_on_the_quote_type() {
+        let resolve = |baseline: ChangeBaseline, quote_type| baseline.resolve(quote_type);
+        assert_eq!(
+            resolve(ChangeBaseline::Auto, QuoteType::CryptoCurrency),
+            ChangeBaseline::Rolling24h
+        );
+        assert_eq!(
+            resolve(ChangeBaseline::Auto, QuoteType::USStocks),
+            ChangeBaseline::PreviousClose
+        );
+        // the rolling change is only known for crypto
+        assert_eq!(
+            resolve(ChangeBaseline::Rolling24h, QuoteType::Currency),
+            ChangeBaseline::PreviousClose
+        );
+        assert_eq!(
+            resolve(ChangeBaseline::SessionOpen, QuoteType::CryptoCurrency),
+            ChangeBaseline::SessionOpen
+        );
+    }
+
+    #[test]
+    fn base_price_of_every_baseline() {
+        let quote = quote();
+        let base = |baseline| change_base(baseline, QuoteType::CryptoCurrency, &quote);
+        assert_eq!(
+            base(ChangeBaseline::PreviousClose).value(),
+            Some(dec("100"))
+        );
+        assert_eq!(base(ChangeBaseline::SessionOpen).value(), Some(dec("98")));
+        // 110 is 10% above the price 24 hours ago
+        assert_eq!(base(ChangeBaseline::Rolling24h).value(), Some(dec("100")));
+    }
+
+    #[test]
+    fn live_price_replaces_the_close() {
+        let quote = quote();
+        let snapshot = price_change(
+            ChangeBaseline::PreviousClose,
+            QuoteType::CryptoCurrency,
+            Some(&quote),
+            None,
+        )
+        .unwrap();
+        assert_eq!(snapshot.absolute, dec("10"));
+        assert_eq!(snapshot.percent, Some(dec("10")));
+        let live = price_change(
+            ChangeBaseline::PreviousClose,
+            QuoteType::CryptoCurrency,
+            Some(&quote),
+            Some(Price::parse("95")),
+        )
+        .unwrap();
+        assert_eq!(live.absolute, dec("-5"));
+        assert_eq!(
+            price_change(ChangeBaseline::Auto, QuoteType::USStocks, None, None),
+            None
+        );
+    }
+}
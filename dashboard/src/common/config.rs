@@ -1,10 +1,9 @@
-use std::collections::HashSet;
-
 use linked_hash_set::LinkedHashSet;
 
-use crate::common::enums::QuoteType;
+use crate::common::entities::Watchlist;
+use crate::common::enums::{QuoteSort, QuoteType};
 
-/// Dashboard Display Configuration.
+/// Default Dashboard Display Configuration.
 pub struct DashboardConfiguration;
 
 impl DashboardConfiguration {
@@ -60,23 +59,24 @@ impl DashboardConfiguration {
         s.insert("XOM".to_owned());
         s
     }
-    /// Getting list of quote symbols by quote type
-    pub fn get_quote_symbols(quote_type: QuoteType) -> LinkedHashSet<String> {
-        match quote_type {
-            QuoteType::CryptoCurrency => Self::get_crypto_currencies_symbols(),
-            QuoteType::Currency => Self::get_currencies_symbols(),
-            QuoteType::Indices => Self::get_indices_symbols(),
-            QuoteType::USStocks => Self::get_us_stocks(),
-        }
-    }
-    /// Getting all quote symbols
-    pub fn get_all_quote_symbols() -> HashSet<String> {
-        let all = [
-            Self::get_crypto_currencies_symbols(),
-            Self::get_currencies_symbols(),
-            Self::get_indices_symbols(),
-            Self::get_us_stocks(),
-        ];
-        all.into_iter().flatten().collect::<HashSet<_>>()
+    /// Default watchlists, one per quote type
+    pub fn get_default_watchlists() -> Vec<Watchlist> {
+        QuoteType::ALL
+            .into_iter()
+            .map(|quote_type| {
+                let symbols = match quote_type {
+                    QuoteType::CryptoCurrency => Self::get_crypto_currencies_symbols(),
+                    QuoteType::Currency => Self::get_currencies_symbols(),
+                    QuoteType::Indices => Self::get_indices_symbols(),
+                    QuoteType::USStocks => Self::get_us_stocks(),
+                };
+                Watchlist {
+                    name: String::new(),
+                    quote_type,
+                    symbols: symbols.into_iter().collect(),
+                    sort: QuoteSort::Manual,
+                }
+            })
+            .collect()
     }
 }
@@ -1,6 +1,13 @@
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::common::enums::{Language, QuoteSort, QuoteType};
+use crate::common::error::MarketError;
+use crate::common::i18n::Text;
+use crate::common::price::Price;
+use crate::common::MarketResult;
+
 /// Real time price data
 #[derive(PartialEq, Clone, Default, Debug)]
 pub struct RealTimePriceData {
@@ -16,8 +23,72 @@ pub struct RealTimePriceData {
     pub change: f64,
     /// Price change(percent)
     pub change_percentage: f64,
-    /// Data acquisition time
-    pub time: String,
+    /// Trading volume of the current day, 0 if not provided
+    pub day_volume: f64,
+    /// Unix timestamp of the price
+    pub timestamp: i64,
+}
+
+/// Real-time price tick, the same for every provider
+#[derive(PartialEq, Clone, Default, Debug)]
+pub struct PriceTick {
+    /// Symbol ticker of the instrument
+    pub symbol: String,
+    /// Real-time price
+    pub price: f64,
+    /// Bid price, 0 if not provided
+    pub bid: f64,
+    /// Ask price, 0 if not provided
+    pub ask: f64,
+    /// Trading volume of the current day, 0 if not provided
+    pub day_volume: f64,
+    /// Unix timestamp of the tick
+    pub timestamp: i64,
+}
+
+/// Result of a price feed subscription, the same for every provider
+#[derive(PartialEq, Clone, Default, Debug)]
+pub struct SubscribeStatus {
+    /// Symbols streamed from now on
+    pub success: Vec<String>,
+    /// Symbols rejected by the provider, e.g. unknown or over the plan limit
+    pub fails: Vec<String>,
+}
+
+/// Raw price feed frame of a recorded session
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct SessionFrame {
+    /// Local receive time, milliseconds since the Unix epoch
+    pub received_at: f64,
+    /// Frame text as received
+    pub frame: String,
+}
+
+/// User watchlist, a dashboard block
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Watchlist {
+    /// Block title, empty for the translated quote type title
+    #[serde(default)]
+    pub name: String,
+    /// Type of the instruments, selects the columns and the price stream provider
+    pub quote_type: QuoteType,
+    /// Symbols in display order
+    #[serde(default)]
+    pub symbols: Vec<String>,
+    /// Row order chosen by the user
+    #[serde(default)]
+    pub sort: QuoteSort,
+}
+
+impl Watchlist {
+    /// Block title in the language
+    pub fn title(&self, language: Language) -> String {
+        if self.name.is_empty() {
+            language.text(Text::QuoteType(self.quote_type))
+        } else {
+            self.name.clone()
+        }
+    }
 }
 
 /// End of day data
@@ -32,7 +103,8 @@ pub struct EndOfDay {
     /// Time in defined timezone referring to when the bar with specified interval was opened
     pub timestamp: i64,
     /// The most recent end of day close price
-    pub close: String,
+    #[serde(default)]
+    pub close: Price,
 }
 
 /// Latest quote
@@ -55,22 +127,29 @@ pub struct Quote {
     /// Unix timestamp of the last price
     pub timestamp: i64,
     /// Price at the opening of current bar
-    pub open: String,
+    #[serde(default)]
+    pub open: Price,
     /// Highest price which occurred during the current bar
-    pub high: String,
+    #[serde(default)]
+    pub high: Price,
     /// Lowest price which occurred during the current bar
-    pub low: String,
+    #[serde(default)]
+    pub low: Price,
     /// Close price at the end of the ba
-    pub close: String,
+    #[serde(default)]
+    pub close: Price,
     #[serde(default)]
     /// Trading volume during the bar
     pub volume: String,
     /// Close price at the end of the previous bar
-    pub previous_close: String,
+    #[serde(default)]
+    pub previous_close: Price,
     /// Close - previous_close
-    pub change: String,
+    #[serde(default)]
+    pub change: Price,
     /// (close - previous_close) / previous_close * 100
-    pub percent_change: String,
+    #[serde(default)]
+    pub percent_change: Price,
     /// Average volume of the specified period
     #[serde(default)]
     pub average_volume: String,
@@ -81,35 +160,39 @@ pub struct Quote {
     pub fifty_two_week: FiftyTwoWeek,
     /// Percent change in price between the current and the backward one, where period is 1 day, available for crypto
     #[serde(default)]
-    pub rolling_1d_change: String,
+    pub rolling_1d_change: Price,
     /// Percent change in price between the current and the backward one, where period is 7 days, available for crypto
     #[serde(default)]
-    pub rolling_7d_change: String,
+    pub rolling_7d_change: Price,
     /// Percent change in price between the current and the backward one, where period specified in request param rolling_period, available for crypto
     #[serde(default)]
-    pub rolling_period_change: String,
+    pub rolling_period_change: Price,
     /// Diff between the regular close price and the latest extended price
     #[serde(default)]
-    pub extended_change: String,
+    pub extended_change: Price,
     /// Percent change in price between the regular close price and the latest extended price
     #[serde(default)]
-    pub extended_percent_change: String,
+    pub extended_percent_change: Price,
     /// Latest extended price
     #[serde(default)]
-    pub extended_price: String,
+    pub extended_price: Price,
     /// Unix timestamp of the last extended price
     #[serde(default)]
     pub extended_timestamp: String,
 }
 /// Collection of 52-week metrics
 #[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
+#[serde(default)]
 pub struct FiftyTwoWeek {
-    pub low: String,
-    pub high: String,
-    pub low_change: String,
-    pub high_change: String,
-    pub low_change_percent: String,
-    pub high_change_percent: String,
+    pub low: Price,
+    pub high: Price,
+    /// Close - 52-week low
+    pub low_change: Price,
+    /// Close - 52-week high
+    pub high_change: Price,
+    pub low_change_percent: Price,
+    pub high_change_percent: Price,
+    /// "low - high"
     pub range: String,
 }
 
@@ -172,48 +255,134 @@ pub struct Stock {
     /// Market identifier code (MIC) under ISO 10383 standard
     pub mic_code: String,
 }
-/// Real-time price message
-#[derive(Serialize, Deserialize, Debug, Default)]
-pub struct PriceMessage {
-    /// Type of event
-    pub event: String,
-    /// Symbol ticker of instrument
+
+/// Symbol search response
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
+pub struct SymbolSearchResults {
+    #[serde(default)]
+    pub data: Vec<SymbolMatch>,
+}
+/// Instrument found by the symbol search
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
+pub struct SymbolMatch {
+    /// Instrument symbol (ticker)
     pub symbol: String,
-    /// Currency base
+    /// Full name of instrument
+    #[serde(rename = "instrument_name")]
+    pub name: String,
+    /// Exchange where instrument is traded
     #[serde(default)]
-    pub currency_base: String,
-    /// Currency quote
+    pub exchange: String,
+    /// Market identifier code (MIC) under ISO 10383 standard
     #[serde(default)]
-    pub currency_quote: String,
+    pub mic_code: String,
+    /// Type of instrument, e.g. "Common Stock", "Digital Currency", "Physical Currency"
     #[serde(default)]
-    pub exchange: String,
-    /// Symbol ticker of instrument
+    pub instrument_type: String,
+    /// Country where the instrument is located
     #[serde(default)]
-    pub timestamp: i64,
-    /// Real-time price for the underlying instrument
+    pub country: String,
+}
+/// Time series response
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
+pub struct TimeSeries {
+    /// Instrument and interval of the series
+    pub meta: TimeSeriesMeta,
+    /// Bars, the newest first
     #[serde(default)]
-    pub price: f64,
-    /// Bid price
+    pub values: Vec<TimeSeriesValue>,
+}
+/// Time series meta data
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
+pub struct TimeSeriesMeta {
+    /// Instrument symbol (ticker)
+    pub symbol: String,
+    /// Interval between two consecutive points
+    pub interval: String,
+    /// Currency in which instrument is traded by ISO 4217 standard
     #[serde(default)]
-    pub bid: f64,
-    /// Ask price
+    pub currency: String,
+    /// Timezone of the exchange
     #[serde(default)]
-    pub ask: f64,
-    /// Real-time price for the underlying instrument
+    pub exchange_timezone: String,
+    /// Exchange where instrument is traded
     #[serde(default)]
-    pub day_volume: f64,
+    pub exchange: String,
+    /// Type of instrument
+    #[serde(default, rename = "type")]
+    pub instrument_type: String,
+}
+/// Single time series bar as returned by the api
+#[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
+pub struct TimeSeriesValue {
+    /// Bar opening time, "2024-06-17 15:59:00" or "2024-06-17" for daily and longer intervals
+    pub datetime: String,
+    /// Price at the opening of the bar
+    pub open: String,
+    /// Highest price of the bar
+    pub high: String,
+    /// Lowest price of the bar
+    pub low: String,
+    /// Price at the closing of the bar
+    pub close: String,
+    /// Trading volume, not provided for currencies and indices
+    #[serde(default)]
+    pub volume: String,
+}
+/// Price bar (OHLCV)
+#[derive(PartialEq, Clone, Copy, Default, Debug)]
+pub struct Bar {
+    /// Unix timestamp of the bar opening
+    pub time: i64,
+    /// Price at the opening of the bar
+    pub open: f64,
+    /// Highest price of the bar
+    pub high: f64,
+    /// Lowest price of the bar
+    pub low: f64,
+    /// Price at the closing of the bar
+    pub close: f64,
+    /// Trading volume, 0 if not provided
+    pub volume: f64,
 }
 
-/// Web socket response event
-#[derive(Serialize, Deserialize, Debug)]
-pub struct WSResponseEvent {
-    pub event: String,
+impl TimeSeries {
+    /// Bars in ascending time order. The series must be requested with the UTC timezone.
+    pub fn bars(&self) -> MarketResult<Vec<Bar>> {
+        let mut bars = self
+            .values
+            .iter()
+            .map(TimeSeriesValue::to_bar)
+            .collect::<MarketResult<Vec<_>>>()?;
+        bars.sort_by_key(|bar| bar.time);
+        Ok(bars)
+    }
 }
 
-impl Default for WSResponseEvent {
-    fn default() -> Self {
-        Self {
-            event: "unknown".to_owned(),
-        }
+impl TimeSeriesValue {
+    fn to_bar(&self) -> MarketResult<Bar> {
+        let time = NaiveDateTime::parse_from_str(&self.datetime, "%Y-%m-%d %H:%M:%S")
+            .or_else(|_| {
+                NaiveDate::parse_from_str(&self.datetime, "%Y-%m-%d")
+                    .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default())
+            })
+            .map_err(|_| {
+                MarketError::General(format!("Invalid time series datetime {}", self.datetime))
+            })?
+            .and_utc()
+            .timestamp();
+        let parse = |value: &str| {
+            value
+                .parse::<f64>()
+                .map_err(|_| MarketError::General(format!("Invalid time series value {value}")))
+        };
+        Ok(Bar {
+            time,
+            open: parse(&self.open)?,
+            high: parse(&self.high)?,
+            low: parse(&self.low)?,
+            close: parse(&self.close)?,
+            volume: self.volume.parse().unwrap_or(0.0),
+        })
     }
 }
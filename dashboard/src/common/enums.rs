@@ -1,11 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+use crate::common::entities::{PriceTick, SubscribeStatus};
+
 /// Quote Type
-#[derive(PartialEq, Clone)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub enum QuoteType {
+    #[serde(rename = "crypto")]
     CryptoCurrency,
+    #[serde(rename = "currency")]
     Currency,
+    #[serde(rename = "indices")]
     Indices,
+    #[serde(rename = "us_stocks")]
     USStocks,
 }
+
+impl QuoteType {
+    /// Quote types in the dashboard order
+    pub const ALL: [QuoteType; 4] = [
+        QuoteType::CryptoCurrency,
+        QuoteType::Currency,
+        QuoteType::Indices,
+        QuoteType::USStocks,
+    ];
+    /// Crypto and currency blocks show bid and ask
+    pub fn component_type(self) -> QuotesComponentType {
+        match self {
+            QuoteType::CryptoCurrency | QuoteType::Currency => QuotesComponentType::BidAsk,
+            QuoteType::Indices | QuoteType::USStocks => QuotesComponentType::OnlyPrice,
+        }
+    }
+}
 /// Quotes Component Type
 #[derive(PartialEq, Clone, Debug, Default)]
 pub enum QuotesComponentType {
@@ -13,22 +38,226 @@ pub enum QuotesComponentType {
     BidAsk,
     OnlyPrice,
 }
-/// Web socket response event type
+/// Direction of the last price change
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum PriceMove {
+    Up,
+    Down,
+    #[default]
+    Unchanged,
+}
+/// Trading session of a market
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MarketSession {
+    /// Regular trading hours
+    Open,
+    /// Extended hours before the opening
+    PreMarket,
+    /// Extended hours after the closing
+    PostMarket,
+    /// Weekend, holiday or night
+    Closed,
+}
+
+impl MarketSession {
+    /// Prices may update
+    pub fn is_trading(&self) -> bool {
+        !matches!(self, MarketSession::Closed)
+    }
+}
+/// Web socket connection state
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum ConnectionState {
+    /// First connection attempt is in progress
+    #[default]
+    Connecting,
+    /// Connection is open and the subscription is sent
+    Live,
+    /// Connection was lost, waiting for the next attempt
+    Reconnecting,
+    /// All reconnection attempts failed
+    Failed,
+}
+/// Streaming price feed event
 #[derive(Debug)]
-pub enum WSResponseEventType {
-    SubscribeStatus,
-    Price,
+pub enum FeedEvent {
+    /// Real-time price
+    Price(PriceTick),
+    /// Subscription status
+    SubscribeStatus(SubscribeStatus),
+    /// Heartbeat response
     Heartbeat,
-    Unknown,
+    /// Unsupported message, raw payload
+    Unknown(String),
+}
+/// Market data provider
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ProviderKind {
+    /// Twelve Data rest api and web socket
+    #[serde(rename = "twelvedata")]
+    TwelveData,
+    /// Binance compatible public web socket streams, crypto only
+    #[serde(rename = "binance")]
+    Binance,
+    /// Bundled fixtures and a replayed price session, works offline
+    #[serde(rename = "mock")]
+    Mock,
+}
+/// User interface language
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "ru")]
+    Ru,
+    #[serde(rename = "en")]
+    En,
 }
 
-impl From<String> for WSResponseEventType {
-    fn from(value: String) -> Self {
-        match value.as_str() {
-            "price" => WSResponseEventType::Price,
-            "heartbeat" => WSResponseEventType::Heartbeat,
-            "subscribe-status" => WSResponseEventType::SubscribeStatus,
-            _ => WSResponseEventType::Unknown,
+impl Language {
+    /// Languages in the switcher order
+    pub const ALL: [Language; 2] = [Language::Ru, Language::En];
+}
+/// Reference price of the price change
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum ChangeBaseline {
+    /// Rolling 24 hours for crypto, the previous close for the rest
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+    /// Close of the previous session
+    #[serde(rename = "previous_close")]
+    PreviousClose,
+    /// Opening of the current session
+    #[serde(rename = "session_open")]
+    SessionOpen,
+    /// Price 24 hours ago, crypto only
+    #[serde(rename = "rolling_24h")]
+    Rolling24h,
+}
+
+impl ChangeBaseline {
+    /// Baselines in the switcher order
+    pub const ALL: [ChangeBaseline; 4] = [
+        ChangeBaseline::Auto,
+        ChangeBaseline::PreviousClose,
+        ChangeBaseline::SessionOpen,
+        ChangeBaseline::Rolling24h,
+    ];
+}
+/// Sortable column of the quote table
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SortColumn {
+    #[serde(rename = "instrument")]
+    Instrument,
+    #[serde(rename = "price")]
+    Price,
+    #[serde(rename = "change")]
+    Change,
+    #[serde(rename = "change_percent")]
+    ChangePercent,
+    #[serde(rename = "time")]
+    Time,
+}
+/// Row order of a watchlist block
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum QuoteSort {
+    /// Watchlist order
+    #[default]
+    #[serde(rename = "manual")]
+    Manual,
+    /// Sorted by the column. Rows keep their places between ticks and move only when
+    /// the sort, the symbols or the set of rows with a value change.
+    #[serde(rename = "column")]
+    Column {
+        column: SortColumn,
+        descending: bool,
+    },
+    /// Largest absolute percent change first, reordered on every tick
+    #[serde(rename = "top_movers")]
+    TopMovers,
+}
+
+impl QuoteSort {
+    /// Sort after a click on the column header: ascending, descending, then the watchlist order
+    pub fn toggle(self, column: SortColumn) -> QuoteSort {
+        match self {
+            QuoteSort::Column {
+                column: current,
+                descending,
+            } if current == column => {
+                if descending {
+                    QuoteSort::Manual
+                } else {
+                    QuoteSort::Column {
+                        column,
+                        descending: true,
+                    }
+                }
+            }
+            _ => QuoteSort::Column {
+                column,
+                descending: false,
+            },
         }
     }
 }
+/// Binance stream type
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum BinanceStream {
+    /// Best bid/ask updates in real time
+    #[serde(rename = "bookTicker")]
+    BookTicker,
+    /// Rolling 24 hour statistics with the last price, every second
+    #[serde(rename = "ticker")]
+    Ticker,
+}
+/// Time series bar interval
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum TimeSeriesInterval {
+    #[default]
+    OneMinute,
+    FiveMinutes,
+    FifteenMinutes,
+    ThirtyMinutes,
+    OneHour,
+    FourHours,
+    OneDay,
+    OneWeek,
+    OneMonth,
+}
+
+impl TimeSeriesInterval {
+    /// Intervals in ascending order
+    pub const ALL: [TimeSeriesInterval; 9] = [
+        TimeSeriesInterval::OneMinute,
+        TimeSeriesInterval::FiveMinutes,
+        TimeSeriesInterval::FifteenMinutes,
+        TimeSeriesInterval::ThirtyMinutes,
+        TimeSeriesInterval::OneHour,
+        TimeSeriesInterval::FourHours,
+        TimeSeriesInterval::OneDay,
+        TimeSeriesInterval::OneWeek,
+        TimeSeriesInterval::OneMonth,
+    ];
+    /// Twelve Data `interval` parameter
+    pub fn api_name(self) -> &'static str {
+        match self {
+            TimeSeriesInterval::OneMinute => "1min",
+            TimeSeriesInterval::FiveMinutes => "5min",
+            TimeSeriesInterval::FifteenMinutes => "15min",
+            TimeSeriesInterval::ThirtyMinutes => "30min",
+            TimeSeriesInterval::OneHour => "1h",
+            TimeSeriesInterval::FourHours => "4h",
+            TimeSeriesInterval::OneDay => "1day",
+            TimeSeriesInterval::OneWeek => "1week",
+            TimeSeriesInterval::OneMonth => "1month",
+        }
+    }
+}
+/// Price chart type
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum ChartKind {
+    #[default]
+    Candles,
+    Line,
+}
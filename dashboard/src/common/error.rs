@@ -1,4 +1,5 @@
 use derive_more::From;
+use gloo::storage::errors::StorageError;
 use gloo::utils::errors::JsError;
 use thiserror::Error;
 
@@ -7,6 +8,9 @@ use thiserror::Error;
 pub enum MarketError {
     #[error("General error: {0}")]
     General(String),
+    #[error("Configuration error: {0}")]
+    #[from(ignore)]
+    Config(String),
     #[error("JsError error: {0}")]
     JsError(JsError),
     #[error("Gloo net error: {0}")]
@@ -15,4 +19,6 @@ pub enum MarketError {
     SerdeJsonError(serde_json::error::Error),
     #[error("Web socket error: {0}")]
     WebSocketError(gloo_net::websocket::WebSocketError),
+    #[error("Storage error: {0}")]
+    StorageError(StorageError),
 }
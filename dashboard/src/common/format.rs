@@ -0,0 +1,295 @@
+use std::str::FromStr;
+
+use chrono::DateTime;
+use chrono_tz::Tz;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::common::entities::Quote;
+use crate::common::error::MarketError;
+use crate::common::market_hours::quote_exchange_hours;
+use crate::common::price::Price;
+use crate::common::MarketResult;
+
+/// Placeholder of a missing or invalid value
+pub const MISSING: &str = "—";
+/// `display_timezone` value showing the times in the exchange local time
+pub const EXCHANGE_TIMEZONE: &str = "exchange";
+
+/// Timezone of the displayed times
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum DisplayTimezone {
+    /// IANA timezone, with daylight saving time
+    Zone(Tz),
+    /// Local time of the instrument exchange, UTC for crypto and currencies
+    Exchange,
+}
+
+/// Number and date formatting conventions
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum NumberLocale {
+    Ru,
+    EnUs,
+    EnGb,
+    De,
+    Fr,
+}
+
+impl NumberLocale {
+    /// Supported BCP 47 tags
+    pub const TAGS: [&'static str; 5] = ["ru-RU", "en-US", "en-GB", "de-DE", "fr-FR"];
+
+    fn from_tag(tag: &str) -> Option<Self> {
+        match tag {
+            "ru-RU" => Some(NumberLocale::Ru),
+            "en-US" => Some(NumberLocale::EnUs),
+            "en-GB" => Some(NumberLocale::EnGb),
+            "de-DE" => Some(NumberLocale::De),
+            "fr-FR" => Some(NumberLocale::Fr),
+            _ => None,
+        }
+    }
+    /// Thousands separator and decimal mark
+    fn separators(&self) -> (char, char) {
+        match self {
+            NumberLocale::Ru => ('\u{a0}', ','),
+            NumberLocale::EnUs | NumberLocale::EnGb => (',', '.'),
+            NumberLocale::De => ('.', ','),
+            NumberLocale::Fr => ('\u{202f}', ','),
+        }
+    }
+
+    fn date_format(&self) -> &'static str {
+        match self {
+            NumberLocale::Ru | NumberLocale::De => "%d.%m.%y",
+            NumberLocale::EnUs => "%m/%d/%y",
+            NumberLocale::EnGb | NumberLocale::Fr => "%d/%m/%y",
+        }
+    }
+}
+
+/// User selected timezone and number formatting of every component
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct DisplayFormat {
+    timezone: DisplayTimezone,
+    locale: NumberLocale,
+}
+
+impl Default for DisplayFormat {
+    /// Moscow time, Russian number formatting
+    fn default() -> Self {
+        Self {
+            timezone: DisplayTimezone::Zone(Tz::Europe__Moscow),
+            locale: NumberLocale::Ru,
+        }
+    }
+}
+
+impl DisplayFormat {
+    /// Format by an IANA timezone name or `exchange`, and a locale tag
+    pub fn new(timezone: &str, locale: &str) -> MarketResult<Self> {
+        let timezone = if timezone == EXCHANGE_TIMEZONE {
+            DisplayTimezone::Exchange
+        } else {
+            DisplayTimezone::Zone(Tz::from_str(timezone).map_err(|_| {
+                MarketError::Config(format!(
+                    "display_timezone must be an IANA timezone or \"{EXCHANGE_TIMEZONE}\", got \"{timezone}\""
+                ))
+            })?)
+        };
+        let locale = NumberLocale::from_tag(locale).ok_or_else(|| {
+            MarketError::Config(format!(
+                "locale must be one of {}, got \"{locale}\"",
+                NumberLocale::TAGS.join(", ")
+            ))
+        })?;
+        Ok(Self { timezone, locale })
+    }
+    /// The format with the exchange local time of the quote, if times are shown in exchange time
+    pub fn for_quote(self, quote: Option<&Quote>) -> Self {
+        if self.timezone != DisplayTimezone::Exchange {
+            return self;
+        }
+        let timezone = quote
+            .and_then(quote_exchange_hours)
+            .map_or(Tz::UTC, |hours| hours.timezone);
+        Self {
+            timezone: DisplayTimezone::Zone(timezone),
+            ..self
+        }
+    }
+    /// Time of the unix timestamp
+    pub fn time(&self, timestamp: i64) -> String {
+        self.timestamp(timestamp, "%H:%M:%S")
+    }
+    /// Date and time of the unix timestamp
+    pub fn date_time(&self, timestamp: i64) -> String {
+        self.timestamp(timestamp, &format!("{} %H:%M", self.locale.date_format()))
+    }
+    /// Number rounded to the decimal places, with thousands separators
+    pub fn number(&self, value: f64, decimals: u32) -> String {
+        if !value.is_finite() {
+            return MISSING.to_owned();
+        }
+        self.localize(&format!("{:.*}", decimals as usize, value))
+    }
+    /// Exact price rounded to the decimal places, a dash if the price is missing
+    pub fn price(&self, price: Price, decimals: u32) -> String {
+        self.decimal(price.value(), decimals)
+    }
+    /// Decimal rounded half away from zero, a dash if the value is missing
+    pub fn decimal(&self, value: Option<Decimal>, decimals: u32) -> String {
+        match value {
+            Some(value) => {
+                let rounded =
+                    value.round_dp_with_strategy(decimals, RoundingStrategy::MidpointAwayFromZero);
+                self.localize(&format!("{:.*}", decimals as usize, rounded))
+            }
+            None => MISSING.to_owned(),
+        }
+    }
+    /// Change with an explicit plus sign, a dash if the value is missing
+    pub fn change(&self, value: Option<Decimal>, decimals: u32) -> String {
+        let number = self.decimal(value, decimals);
+        let positive = value.is_some_and(|value| value.is_sign_positive() && !value.is_zero());
+        if positive && number.chars().any(|digit| matches!(digit, '1'..='9')) {
+            format!("+{number}")
+        } else {
+            number
+        }
+    }
+
+    /// Thousands separators and the decimal mark of the locale for a formatted number
+    fn localize(&self, text: &str) -> String {
+        let (group, decimal) = self.locale.separators();
+        let (sign, digits) = match text.strip_prefix('-') {
+            // rounded to zero, no sign
+            Some(digits) if digits.bytes().all(|byte| matches!(byte, b'0' | b'.')) => ("", digits),
+            Some(digits) => ("-", digits),
+            None => ("", text),
+        };
+        let (integer, fraction) = match digits.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (digits, None),
+        };
+        let mut result = String::from(sign);
+        for (index, digit) in integer.chars().enumerate() {
+            if index > 0 && (integer.len() - index) % 3 == 0 {
+                result.push(group);
+            }
+            result.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            result.push(decimal);
+            result.push_str(fraction);
+        }
+        result
+    }
+
+    fn timestamp(&self, timestamp: i64, format: &str) -> String {
+        let timezone = match self.timezone {
+            DisplayTimezone::Zone(timezone) => timezone,
+            DisplayTimezone::Exchange => Tz::UTC,
+        };
+        DateTime::from_timestamp(timestamp.max(0), 0)
+            .unwrap_or_default()
+            .with_timezone(&timezone)
+            .format(format)
+            .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2025-07-01 13:30:00 UTC, summer time in New York and London
+    const SUMMER: i64 = 1_751_376_600;
+    /// 2025-01-06 13:30:00 UTC
+    const WINTER: i64 = 1_736_170_200;
+
+    fn format(timezone: &str, locale: &str) -> DisplayFormat {
+        DisplayFormat::new(timezone, locale).unwrap()
+    }
+
+    fn dec(value: &str) -> Option<Decimal> {
+        Some(value.parse().unwrap())
+    }
+
+    #[test]
+    fn numbers_use_the_locale_separators() {
+        let value = dec("1234567.891");
+        assert_eq!(
+            format("UTC", "ru-RU").decimal(value, 2),
+            "1\u{a0}234\u{a0}567,89"
+        );
+        assert_eq!(format("UTC", "en-US").decimal(value, 2), "1,234,567.89");
+        assert_eq!(format("UTC", "de-DE").decimal(value, 2), "1.234.567,89");
+        assert_eq!(
+            format("UTC", "fr-FR").decimal(value, 2),
+            "1\u{202f}234\u{202f}567,89"
+        );
+        assert_eq!(format("UTC", "en-GB").number(-999.5, 0), "-1,000");
+        assert_eq!(format("UTC", "en-GB").number(f64::NAN, 2), MISSING);
+    }
+
+    #[test]
+    fn decimals_round_half_away_from_zero() {
+        let en = format("UTC", "en-US");
+        assert_eq!(en.decimal(dec("1.005"), 2), "1.01");
+        assert_eq!(en.decimal(dec("-1.005"), 2), "-1.01");
+        assert_eq!(en.decimal(dec("-0.001"), 2), "0.00");
+        assert_eq!(en.decimal(dec("2"), 3), "2.000");
+        assert_eq!(en.decimal(None, 2), MISSING);
+        assert_eq!(en.price(Price::parse("abc"), 2), MISSING);
+    }
+
+    #[test]
+    fn positive_changes_have_a_plus_sign() {
+        let en = format("UTC", "en-US");
+        assert_eq!(en.change(dec("1.5"), 2), "+1.50");
+        assert_eq!(en.change(dec("-1.5"), 2), "-1.50");
+        assert_eq!(en.change(dec("0.001"), 2), "0.00");
+        assert_eq!(en.change(dec("0"), 2), "0.00");
+        assert_eq!(en.change(None, 2), MISSING);
+    }
+
+    #[test]
+    fn times_follow_the_daylight_saving_time() {
+        let new_york = format("America/New_York", "en-US");
+        assert_eq!(new_york.time(SUMMER), "09:30:00");
+        assert_eq!(new_york.time(WINTER), "08:30:00");
+        assert_eq!(new_york.date_time(SUMMER), "07/01/25 09:30");
+        assert_eq!(
+            format("Europe/London", "en-GB").date_time(WINTER),
+            "06/01/25 13:30"
+        );
+        assert_eq!(
+            format("Europe/Moscow", "ru-RU").date_time(SUMMER),
+            "01.07.25 16:30"
+        );
+    }
+
+    #[test]
+    fn exchange_time_of_the_quote() {
+        let quote: Quote = serde_json::from_value(serde_json::json!({
+            "symbol": "SAP",
+            "name": "SAP SE",
+            "exchange": "XETR",
+            "datetime": "2025-07-01",
+            "timestamp": 0,
+            "is_market_open": true,
+        }))
+        .unwrap();
+        let exchange = format(EXCHANGE_TIMEZONE, "de-DE");
+        assert_eq!(exchange.for_quote(Some(&quote)).time(SUMMER), "15:30:00");
+        assert_eq!(exchange.for_quote(None).time(SUMMER), "13:30:00");
+        let moscow = format("Europe/Moscow", "de-DE");
+        assert_eq!(moscow.for_quote(Some(&quote)), moscow);
+    }
+
+    #[test]
+    fn unknown_timezone_and_locale_are_rejected() {
+        assert!(DisplayFormat::new("Mars/Olympus", "en-US").is_err());
+        assert!(DisplayFormat::new("UTC", "en-AU").is_err());
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::common::enums::PriceMove;
+
+/// Last update state of a quote
+#[derive(PartialEq, Clone, Copy, Default, Debug)]
+pub struct QuoteFreshness {
+    /// Number of received ticks, a new value restarts the flash animation
+    pub ticks: u64,
+    /// Direction of the last tick
+    pub last_move: PriceMove,
+    /// No ticks for longer than the staleness threshold
+    pub stale: bool,
+}
+
+/// Tracks when every quote and the price feed as a whole were last updated.
+/// Times are browser clock milliseconds, quotes without ticks count from the start.
+/// The quote states are shared with the view and copied only when they change.
+#[derive(Clone, Debug)]
+pub struct FreshnessTracker {
+    started_at: f64,
+    /// The last price of any symbol, or the moment the feed went live if later
+    last_price_at: f64,
+    last_tick_at: HashMap<String, f64>,
+    quotes: Rc<HashMap<String, QuoteFreshness>>,
+}
+
+impl FreshnessTracker {
+    pub fn new(now: f64) -> Self {
+        Self {
+            started_at: now,
+            last_price_at: now,
+            last_tick_at: HashMap::new(),
+            quotes: Rc::default(),
+        }
+    }
+
+    pub fn record_tick(&mut self, symbol: &str, last_move: PriceMove, now: f64) {
+        self.last_price_at = now;
+        self.last_tick_at.insert(symbol.to_owned(), now);
+        let quote = Rc::make_mut(&mut self.quotes)
+            .entry(symbol.to_owned())
+            .or_default();
+        quote.ticks += 1;
+        quote.last_move = last_move;
+        quote.stale = false;
+    }
+    /// The feed connected again, the delay counts from now
+    pub fn feed_live(&mut self, now: f64) {
+        self.last_price_at = self.last_price_at.max(now);
+    }
+    /// The market of the symbol opened, its staleness counts from now
+    pub fn market_opened(&mut self, symbol: &str, now: f64) {
+        self.last_tick_at.insert(symbol.to_owned(), now);
+    }
+    /// Updating the stale flags by the thresholds of the symbols in seconds,
+    /// symbols without a threshold are never stale. Returns true if any flag changed.
+    pub fn refresh<'a>(
+        &mut self,
+        thresholds: impl IntoIterator<Item = (&'a str, Option<u64>)>,
+        now: f64,
+    ) -> bool {
+        let mut changed = false;
+        for (symbol, threshold) in thresholds {
+            let last = self
+                .last_tick_at
+                .get(symbol)
+                .copied()
+                .unwrap_or(self.started_at);
+            let stale = threshold.is_some_and(|threshold| now - last > threshold as f64 * 1000.0);
+            if self.quotes.get(symbol).is_some_and(|quote| quote.stale) != stale {
+                Rc::make_mut(&mut self.quotes)
+                    .entry(symbol.to_owned())
+                    .or_default()
+                    .stale = stale;
+                changed = true;
+            }
+        }
+        changed
+    }
+    /// Whole seconds since the last price when they exceed the limit
+    pub fn feed_delay(&self, now: f64, limit_secs: u64) -> Option<u64> {
+        let seconds = ((now - self.last_price_at) / 1000.0).max(0.0) as u64;
+        (seconds >= limit_secs).then_some(seconds)
+    }
+
+    pub fn quotes(&self) -> &Rc<HashMap<String, QuoteFreshness>> {
+        &self.quotes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::app_config::StaleThresholds;
+    use crate::common::enums::QuoteType;
+
+    const START: f64 = 1_718_611_200_000.0;
+    const SECOND: f64 = 1000.0;
+
+    fn stale(tracker: &FreshnessTracker, symbol: &str) -> bool {
+        tracker
+            .quotes()
+            .get(symbol)
+            .is_some_and(|quote| quote.stale)
+    }
+
+    #[test]
+    fn quotes_go_stale_and_recover_on_the_next_tick() {
+        let mut tracker = FreshnessTracker::new(START);
+        tracker.record_tick("BTC/USD", PriceMove::Up, START);
+        assert!(!tracker.refresh([("BTC/USD", Some(60))], START + 60.0 * SECOND));
+        assert!(!stale(&tracker, "BTC/USD"));
+
+        assert!(tracker.refresh([("BTC/USD", Some(60))], START + 61.0 * SECOND));
+        assert!(stale(&tracker, "BTC/USD"));
+        // unchanged flags are not reported again
+        assert!(!tracker.refresh([("BTC/USD", Some(60))], START + 62.0 * SECOND));
+
+        tracker.record_tick("BTC/USD", PriceMove::Down, START + 63.0 * SECOND);
+        let quote = tracker.quotes()["BTC/USD"];
+        assert_eq!(
+            (quote.ticks, quote.last_move, quote.stale),
+            (2, PriceMove::Down, false)
+        );
+        assert!(!tracker.refresh([("BTC/USD", Some(60))], START + 64.0 * SECOND));
+    }
+
+    #[test]
+    fn closed_markets_are_never_stale() {
+        let mut tracker = FreshnessTracker::new(START);
+        tracker.record_tick("AAPL", PriceMove::Up, START);
+        assert!(tracker.refresh([("AAPL", Some(300))], START + 301.0 * SECOND));
+        // the market closed: no threshold clears the flag and keeps it clear
+        assert!(tracker.refresh([("AAPL", None)], START + 302.0 * SECOND));
+        assert!(!stale(&tracker, "AAPL"));
+        assert!(!tracker.refresh([("AAPL", None)], START + 86_400.0 * SECOND));
+
+        // after the opening the threshold counts from the opening, not from the last tick
+        tracker.market_opened("AAPL", START + 86_400.0 * SECOND);
+        assert!(!tracker.refresh([("AAPL", Some(300))], START + 86_500.0 * SECOND));
+        assert!(tracker.refresh([("AAPL", Some(300))], START + 86_701.0 * SECOND));
+    }
+
+    #[test]
+    fn thresholds_depend_on_the_quote_type() {
+        let thresholds = StaleThresholds::default();
+        let symbols = [
+            ("BTC/USD", QuoteType::CryptoCurrency),
+            ("EUR/USD", QuoteType::Currency),
+            ("SPX", QuoteType::Indices),
+            ("AAPL", QuoteType::USStocks),
+        ];
+        let mut tracker = FreshnessTracker::new(START);
+        let refresh = |tracker: &mut FreshnessTracker, seconds: f64| {
+            let thresholds = symbols
+                .map(|(symbol, quote_type)| (symbol, Some(thresholds.for_quote_type(quote_type))));
+            tracker.refresh(thresholds, START + seconds * SECOND);
+            symbols.map(|(symbol, _)| stale(tracker, symbol))
+        };
+        // symbols without ticks count from the start
+        assert_eq!(refresh(&mut tracker, 60.0), [false; 4]);
+        assert_eq!(refresh(&mut tracker, 61.0), [true, false, false, false]);
+        assert_eq!(refresh(&mut tracker, 121.0), [true, true, false, false]);
+        assert_eq!(refresh(&mut tracker, 301.0), [true; 4]);
+    }
+
+    #[test]
+    fn feed_delay_counts_from_the_last_price_or_reconnection() {
+        let mut tracker = FreshnessTracker::new(START);
+        tracker.record_tick("BTC/USD", PriceMove::Up, START);
+        assert_eq!(tracker.feed_delay(START + 29.0 * SECOND, 30), None);
+        assert_eq!(tracker.feed_delay(START + 45.5 * SECOND, 30), Some(45));
+        tracker.feed_live(START + 40.0 * SECOND);
+        assert_eq!(tracker.feed_delay(START + 45.5 * SECOND, 30), None);
+    }
+}
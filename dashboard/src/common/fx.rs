@@ -0,0 +1,241 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use rust_decimal::Decimal;
+
+/// Cross currency tried first, the most pairs are quoted against it
+const CROSS_CURRENCY: &str = "USD";
+
+/// Bid and ask of a currency pair, the units of the quote currency per unit of the base one
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct FxQuote {
+    /// Price of selling the base currency
+    pub bid: Decimal,
+    /// Price of buying the base currency
+    pub ask: Decimal,
+}
+
+impl FxQuote {
+    /// Quote by the bid and ask, or by the price when the spread is not quoted
+    pub fn new(price: Decimal, bid: Option<Decimal>, ask: Option<Decimal>) -> Option<Self> {
+        let quote = match (bid, ask) {
+            (Some(bid), Some(ask)) if bid > Decimal::ZERO && ask >= bid => Self { bid, ask },
+            _ => Self {
+                bid: price,
+                ask: price,
+            },
+        };
+        (quote.bid > Decimal::ZERO).then_some(quote)
+    }
+    pub fn mid(&self) -> Decimal {
+        (self.bid + self.ask) / Decimal::TWO
+    }
+    /// Quote of the reversed pair, selling the quote currency buys the base one at the ask
+    fn inverse(&self) -> Option<Self> {
+        Some(Self {
+            bid: Decimal::ONE.checked_div(self.ask)?,
+            ask: Decimal::ONE.checked_div(self.bid)?,
+        })
+    }
+    /// Quote of the pair through the quote currency of this one
+    fn cross(&self, next: &Self) -> Option<Self> {
+        Some(Self {
+            bid: self.bid.checked_mul(next.bid)?,
+            ask: self.ask.checked_mul(next.ask)?,
+        })
+    }
+}
+
+/// Rate between two currencies and the currencies it goes through
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct CrossRate {
+    pub quote: FxQuote,
+    /// Currencies from the source to the target, both included
+    pub path: Vec<String>,
+}
+
+/// Graph of the quoted currency pairs, each pair links its currencies both ways.
+/// The price of "EUR/USD" is the number of USD per EUR, crypto pairs like "BTC/USD" count too.
+#[derive(PartialEq, Clone, Default, Debug)]
+pub struct FxGraph {
+    edges: BTreeMap<String, BTreeMap<String, FxQuote>>,
+}
+
+impl FxGraph {
+    /// Adding the quote of a pair symbol, other symbols are ignored
+    pub fn insert(&mut self, symbol: &str, quote: FxQuote) {
+        let Some((base, counter)) = split_pair(symbol) else {
+            return;
+        };
+        if let Some(inverse) = quote.inverse() {
+            self.edges
+                .entry(base.to_owned())
+                .or_default()
+                .insert(counter.to_owned(), quote);
+            self.edges
+                .entry(counter.to_owned())
+                .or_default()
+                .insert(base.to_owned(), inverse);
+        }
+    }
+    /// Currencies of the quoted pairs in the alphabetical order
+    pub fn currencies(&self) -> Vec<String> {
+        self.edges.keys().cloned().collect()
+    }
+    /// Rate through the fewest pairs, e.g. GBP/JPY as GBP/USD and USD/JPY
+    pub fn cross_rate(&self, from: &str, to: &str) -> Option<CrossRate> {
+        if from == to {
+            return Some(CrossRate {
+                quote: FxQuote {
+                    bid: Decimal::ONE,
+                    ask: Decimal::ONE,
+                },
+                path: vec![from.to_owned()],
+            });
+        }
+        let path = self.find_path(from, to)?;
+        let quote = path.windows(2).try_fold(
+            FxQuote {
+                bid: Decimal::ONE,
+                ask: Decimal::ONE,
+            },
+            |quote, pair| quote.cross(self.edges.get(&pair[0])?.get(&pair[1])?),
+        )?;
+        Some(CrossRate { quote, path })
+    }
+    /// Units of `to` per unit of `from` at the mid price
+    pub fn rate(&self, from: &str, to: &str) -> Option<Decimal> {
+        self.cross_rate(from, to).map(|rate| rate.quote.mid())
+    }
+    /// The amount in `from` converted to `to` at the mid price
+    pub fn convert(&self, amount: Decimal, from: &str, to: &str) -> Option<Decimal> {
+        amount.checked_mul(self.rate(from, to)?)
+    }
+
+    /// Breadth first search, the cross currency and then the alphabetical order break the ties,
+    /// so the rate does not jump between equally short paths
+    fn find_path(&self, from: &str, to: &str) -> Option<Vec<String>> {
+        let mut previous: BTreeMap<&str, &str> = BTreeMap::new();
+        let mut queue = VecDeque::from([from]);
+        while let Some(currency) = queue.pop_front() {
+            let neighbours = self.edges.get(currency)?;
+            let ordered = neighbours
+                .get_key_value(CROSS_CURRENCY)
+                .into_iter()
+                .chain(neighbours.iter().filter(|(key, _)| *key != CROSS_CURRENCY));
+            for (next, _) in ordered {
+                if next == from || previous.contains_key(next.as_str()) {
+                    continue;
+                }
+                previous.insert(next, currency);
+                if next == to {
+                    let mut path = vec![to.to_owned()];
+                    let mut current = to;
+                    while let Some(&back) = previous.get(current) {
+                        path.push(back.to_owned());
+                        current = back;
+                    }
+                    path.reverse();
+                    return Some(path);
+                }
+                queue.push_back(next);
+            }
+        }
+        None
+    }
+}
+
+/// Base and quote currency of a pair symbol like "EUR/USD"
+pub fn split_pair(symbol: &str) -> Option<(&str, &str)> {
+    let (base, quote) = symbol.split_once('/')?;
+    (is_currency_code(base) && is_currency_code(quote)).then_some((base, quote))
+}
+
+/// ISO 4217 codes like "USD", and longer crypto tickers
+pub fn is_currency_code(code: &str) -> bool {
+    code.len() >= 3
+        && code
+            .chars()
+            .all(|char| char.is_ascii_uppercase() || char.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    fn quote(bid: &str, ask: &str) -> FxQuote {
+        FxQuote {
+            bid: dec(bid),
+            ask: dec(ask),
+        }
+    }
+
+    fn graph(pairs: &[(&str, FxQuote)]) -> FxGraph {
+        let mut graph = FxGraph::default();
+        for (symbol, quote) in pairs {
+            graph.insert(symbol, *quote);
+        }
+        graph
+    }
+
+    #[test]
+    fn reversed_pair_swaps_the_bid_and_ask() {
+        let graph = graph(&[("EUR/USD", quote("1.25", "1.6"))]);
+        let rate = graph.cross_rate("USD", "EUR").unwrap();
+        assert_eq!(rate.quote, quote("0.625", "0.8"));
+        assert_eq!(rate.path, ["USD", "EUR"]);
+        assert_eq!(graph.rate("EUR", "EUR"), Some(Decimal::ONE));
+        assert_eq!(graph.convert(dec("10"), "USD", "EUR"), Some(dec("7.125")));
+    }
+
+    #[test]
+    fn cross_rate_goes_through_the_dollar() {
+        let graph = graph(&[
+            ("GBP/USD", quote("1.25", "1.26")),
+            ("USD/JPY", quote("150", "151")),
+        ]);
+        let rate = graph.cross_rate("GBP", "JPY").unwrap();
+        assert_eq!(rate.quote, quote("187.5", "190.26"));
+        assert_eq!(rate.path, ["GBP", "USD", "JPY"]);
+        assert_eq!(graph.currencies(), ["GBP", "JPY", "USD"]);
+        assert_eq!(graph.cross_rate("GBP", "EUR"), None);
+    }
+
+    #[test]
+    fn equal_paths_prefer_the_dollar_then_the_alphabet() {
+        let mut pairs = vec![
+            ("EUR/CHF", quote("0.95", "0.95")),
+            ("GBP/CHF", quote("1.1", "1.1")),
+            ("EUR/AUD", quote("1.6", "1.6")),
+            ("GBP/AUD", quote("1.9", "1.9")),
+        ];
+        assert_eq!(
+            graph(&pairs).cross_rate("EUR", "GBP").unwrap().path,
+            ["EUR", "AUD", "GBP"]
+        );
+        pairs.extend([
+            ("EUR/USD", quote("1.08", "1.08")),
+            ("GBP/USD", quote("1.26", "1.26")),
+        ]);
+        assert_eq!(
+            graph(&pairs).cross_rate("EUR", "GBP").unwrap().path,
+            ["EUR", "USD", "GBP"]
+        );
+    }
+
+    #[test]
+    fn quote_falls_back_to_the_price() {
+        assert_eq!(
+            FxQuote::new(dec("1.1"), Some(dec("1.2")), Some(dec("1.0"))),
+            Some(quote("1.1", "1.1"))
+        );
+        assert_eq!(FxQuote::new(Decimal::ZERO, None, None), None);
+        let mut graph = FxGraph::default();
+        graph.insert("AAPL", quote("1", "1"));
+        graph.insert("eur/usd", quote("1", "1"));
+        assert!(graph.currencies().is_empty());
+    }
+}
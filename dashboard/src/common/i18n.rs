@@ -0,0 +1,387 @@
+use crate::common::alerts::AlertKind;
+use crate::common::enums::{
+    ChangeBaseline, ChartKind, ConnectionState, Language, MarketSession, QuoteType,
+    TimeSeriesInterval,
+};
+
+/// Message key of the user interface. Catalogs match on every key without a wildcard arm,
+/// so a key missing from any language does not compile.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Text {
+    /// Default watchlist title
+    QuoteType(QuoteType),
+    ColumnInstrument,
+    ColumnPrice,
+    ColumnBid,
+    ColumnAsk,
+    ColumnChange,
+    ColumnChangePercent,
+    ColumnChart,
+    ColumnTime,
+    Filter,
+    TopMovers,
+    MarketSession(MarketSession),
+    SymbolFailed,
+    SymbolFailedHint,
+    AddSymbol,
+    Delete,
+    MoveLeft,
+    MoveRight,
+    NewWatchlist,
+    Export,
+    Import,
+    Customize,
+    Done,
+    Language,
+    Baseline,
+    ChangeBaseline(ChangeBaseline),
+    ConnectionState(ConnectionState),
+    Retry,
+    /// Seconds since the last price
+    FeedDelayed(u64),
+    StartRecording,
+    StopRecording,
+    /// Number of recorded frames
+    RecordedFrames(usize),
+    /// Binance streams are left out of the recorded session
+    BinanceNotRecorded,
+    Download,
+    Interval(TimeSeriesInterval),
+    ChartKind(ChartKind),
+    Loading,
+    BarOpen,
+    BarHigh,
+    BarLow,
+    BarClose,
+    DayOpen,
+    PreviousClose,
+    DayRange,
+    FiftyTwoWeekRange,
+    /// Change from the 52-week low
+    FromLow,
+    /// Change from the 52-week high
+    FromHigh,
+    Volume,
+    AverageVolume,
+    ExtendedHours,
+    Rolling1d,
+    Rolling7d,
+    RollingPeriod,
+    Alerts,
+    /// Condition in the rule editor
+    AlertKind(AlertKind),
+    /// Condition in the fired alert message
+    AlertFired(AlertKind),
+    AlertThreshold,
+    AlertCooldown,
+    AddAlert,
+    InvalidThreshold,
+    AlertEnabled,
+    NoAlertRules,
+    AlertHistory,
+    ClearHistory,
+    Portfolio,
+    Quantity,
+    CostBasis,
+    /// Placeholder of the currency taken from the quote
+    CurrencyAuto,
+    MarketValue,
+    UnrealizedPnl,
+    DayPnl,
+    Allocation,
+    AddPosition,
+    InvalidPosition,
+    BaseCurrency,
+    Total,
+    NoPositions,
+    /// Totals without the positions missing a price or an exchange rate
+    IncompleteTotal,
+    Converter,
+    Amount,
+    /// Currencies the cross rate goes through
+    RatePath,
+    NoRate,
+}
+
+impl Language {
+    /// Message of the key in the language
+    pub fn text(self, key: Text) -> String {
+        match self {
+            Language::Ru => ru(key),
+            Language::En => en(key),
+        }
+    }
+    /// Name of the language in the language itself, for the switcher
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Language::Ru => "Русский",
+            Language::En => "English",
+        }
+    }
+}
+
+fn ru(key: Text) -> String {
+    let text = match key {
+        Text::QuoteType(quote_type) => match quote_type {
+            QuoteType::CryptoCurrency => "Крипто-валюты",
+            QuoteType::Currency => "Мировые валюты",
+            QuoteType::Indices => "Индексы",
+            QuoteType::USStocks => "Акции",
+        },
+        Text::ColumnInstrument => "Инструмент",
+        Text::ColumnPrice => "Цена",
+        Text::ColumnBid => "Покупка",
+        Text::ColumnAsk => "Продажа",
+        Text::ColumnChange => "Изм.",
+        Text::ColumnChangePercent => "Изм. %",
+        Text::ColumnChart => "График",
+        Text::ColumnTime => "Время",
+        Text::Filter => "Фильтр",
+        Text::TopMovers => "Лидеры изменения",
+        Text::MarketSession(session) => match session {
+            MarketSession::Open => "Открыт",
+            MarketSession::PreMarket => "Премаркет",
+            MarketSession::PostMarket => "Постмаркет",
+            MarketSession::Closed => "Закрыт",
+        },
+        Text::SymbolFailed => "нет потока",
+        Text::SymbolFailedHint => "Поставщик отклонил подписку на символ",
+        Text::AddSymbol => "Добавить символ",
+        Text::Delete => "Удалить",
+        Text::MoveLeft => "Переместить влево",
+        Text::MoveRight => "Переместить вправо",
+        Text::NewWatchlist => "Новый список",
+        Text::Export => "Экспорт",
+        Text::Import => "Импорт",
+        Text::Customize => "Настроить",
+        Text::Done => "Готово",
+        Text::Language => "Язык",
+        Text::Baseline => "База изменения",
+        Text::ChangeBaseline(baseline) => match baseline {
+            ChangeBaseline::Auto => "Изм.: авто",
+            ChangeBaseline::PreviousClose => "Изм.: от закрытия",
+            ChangeBaseline::SessionOpen => "Изм.: от открытия",
+            ChangeBaseline::Rolling24h => "Изм.: за 24 ч",
+        },
+        Text::ConnectionState(state) => match state {
+            ConnectionState::Connecting => "Подключение...",
+            ConnectionState::Live => "Онлайн",
+            ConnectionState::Reconnecting => "Переподключение...",
+            ConnectionState::Failed => "Нет соединения",
+        },
+        Text::Retry => "Повторить",
+        Text::FeedDelayed(seconds) => {
+            return format!("Поток задерживается: нет цен {seconds} с");
+        }
+        Text::StartRecording => "Записать сессию",
+        Text::StopRecording => "Остановить запись",
+        Text::RecordedFrames(frames) => return format!("Кадров: {frames}"),
+        Text::BinanceNotRecorded => "без потоков Binance",
+        Text::Download => "Скачать",
+        Text::Interval(interval) => match interval {
+            TimeSeriesInterval::OneMinute => "1м",
+            TimeSeriesInterval::FiveMinutes => "5м",
+            TimeSeriesInterval::FifteenMinutes => "15м",
+            TimeSeriesInterval::ThirtyMinutes => "30м",
+            TimeSeriesInterval::OneHour => "1ч",
+            TimeSeriesInterval::FourHours => "4ч",
+            TimeSeriesInterval::OneDay => "1д",
+            TimeSeriesInterval::OneWeek => "1н",
+            TimeSeriesInterval::OneMonth => "1мес",
+        },
+        Text::ChartKind(kind) => match kind {
+            ChartKind::Candles => "Свечи",
+            ChartKind::Line => "Линия",
+        },
+        Text::Loading => "Загрузка...",
+        Text::BarOpen => "Откр.",
+        Text::BarHigh => "Макс.",
+        Text::BarLow => "Мин.",
+        Text::BarClose => "Закр.",
+        Text::DayOpen => "Открытие",
+        Text::PreviousClose => "Пред. закрытие",
+        Text::DayRange => "Диапазон дня",
+        Text::FiftyTwoWeekRange => "Диапазон 52 недель",
+        Text::FromLow => "От минимума 52 недель",
+        Text::FromHigh => "От максимума 52 недель",
+        Text::Volume => "Объём",
+        Text::AverageVolume => "средний",
+        Text::ExtendedHours => "Вне основной сессии",
+        Text::Rolling1d => "Изм. за 24 ч",
+        Text::Rolling7d => "Изм. за 7 дней",
+        Text::RollingPeriod => "Изм. за период",
+        Text::Alerts => "Оповещения",
+        Text::AlertKind(kind) => match kind {
+            AlertKind::PriceCrosses => "Цена пересекает уровень",
+            AlertKind::ChangeBeyond => "Изменение больше, %",
+            AlertKind::SpreadWider => "Спред шире",
+            AlertKind::Stale => "Нет обновлений",
+        },
+        Text::AlertFired(kind) => match kind {
+            AlertKind::PriceCrosses => "цена пересекла уровень",
+            AlertKind::ChangeBeyond => "изменение превысило",
+            AlertKind::SpreadWider => "спред шире",
+            AlertKind::Stale => "котировка не обновляется",
+        },
+        Text::AlertThreshold => "Значение",
+        Text::AlertCooldown => "Пауза, с",
+        Text::AddAlert => "Добавить правило",
+        Text::InvalidThreshold => "Укажите положительное значение",
+        Text::AlertEnabled => "Включено",
+        Text::NoAlertRules => "Правил нет",
+        Text::AlertHistory => "История оповещений",
+        Text::ClearHistory => "Очистить",
+        Text::Portfolio => "Портфель",
+        Text::Quantity => "Кол-во",
+        Text::CostBasis => "Цена покупки",
+        Text::CurrencyAuto => "Валюта (авто)",
+        Text::MarketValue => "Стоимость",
+        Text::UnrealizedPnl => "Нереализ. P&L",
+        Text::DayPnl => "P&L за день",
+        Text::Allocation => "Доля",
+        Text::AddPosition => "Добавить позицию",
+        Text::InvalidPosition => "Укажите символ, ненулевое количество и цену покупки",
+        Text::BaseCurrency => "Базовая валюта",
+        Text::Total => "Итого",
+        Text::NoPositions => "Позиций нет",
+        Text::IncompleteTotal => "Итог без позиций, для которых нет цены или курса",
+        Text::Converter => "Конвертер",
+        Text::Amount => "Сумма",
+        Text::RatePath => "Через",
+        Text::NoRate => "Нет котировок для пересчёта этих валют",
+    };
+    text.to_owned()
+}
+
+fn en(key: Text) -> String {
+    let text = match key {
+        Text::QuoteType(quote_type) => match quote_type {
+            QuoteType::CryptoCurrency => "Cryptocurrencies",
+            QuoteType::Currency => "World currencies",
+            QuoteType::Indices => "Indices",
+            QuoteType::USStocks => "Stocks",
+        },
+        Text::ColumnInstrument => "Instrument",
+        Text::ColumnPrice => "Price",
+        Text::ColumnBid => "Bid",
+        Text::ColumnAsk => "Ask",
+        Text::ColumnChange => "Chg.",
+        Text::ColumnChangePercent => "Chg. %",
+        Text::ColumnChart => "Chart",
+        Text::ColumnTime => "Time",
+        Text::Filter => "Filter",
+        Text::TopMovers => "Top movers",
+        Text::MarketSession(session) => match session {
+            MarketSession::Open => "Open",
+            MarketSession::PreMarket => "Pre-market",
+            MarketSession::PostMarket => "Post-market",
+            MarketSession::Closed => "Closed",
+        },
+        Text::SymbolFailed => "no stream",
+        Text::SymbolFailedHint => "The provider rejected the symbol subscription",
+        Text::AddSymbol => "Add symbol",
+        Text::Delete => "Delete",
+        Text::MoveLeft => "Move left",
+        Text::MoveRight => "Move right",
+        Text::NewWatchlist => "New watchlist",
+        Text::Export => "Export",
+        Text::Import => "Import",
+        Text::Customize => "Customize",
+        Text::Done => "Done",
+        Text::Language => "Language",
+        Text::Baseline => "Change baseline",
+        Text::ChangeBaseline(baseline) => match baseline {
+            ChangeBaseline::Auto => "Chg.: auto",
+            ChangeBaseline::PreviousClose => "Chg.: previous close",
+            ChangeBaseline::SessionOpen => "Chg.: session open",
+            ChangeBaseline::Rolling24h => "Chg.: 24 hours",
+        },
+        Text::ConnectionState(state) => match state {
+            ConnectionState::Connecting => "Connecting...",
+            ConnectionState::Live => "Online",
+            ConnectionState::Reconnecting => "Reconnecting...",
+            ConnectionState::Failed => "No connection",
+        },
+        Text::Retry => "Retry",
+        Text::FeedDelayed(seconds) => {
+            return format!("Feed delayed: no prices for {seconds} s");
+        }
+        Text::StartRecording => "Record session",
+        Text::StopRecording => "Stop recording",
+        Text::RecordedFrames(frames) => return format!("Frames: {frames}"),
+        Text::BinanceNotRecorded => "without Binance streams",
+        Text::Download => "Download",
+        Text::Interval(interval) => match interval {
+            TimeSeriesInterval::OneMinute => "1m",
+            TimeSeriesInterval::FiveMinutes => "5m",
+            TimeSeriesInterval::FifteenMinutes => "15m",
+            TimeSeriesInterval::ThirtyMinutes => "30m",
+            TimeSeriesInterval::OneHour => "1h",
+            TimeSeriesInterval::FourHours => "4h",
+            TimeSeriesInterval::OneDay => "1D",
+            TimeSeriesInterval::OneWeek => "1W",
+            TimeSeriesInterval::OneMonth => "1M",
+        },
+        Text::ChartKind(kind) => match kind {
+            ChartKind::Candles => "Candles",
+            ChartKind::Line => "Line",
+        },
+        Text::Loading => "Loading...",
+        Text::BarOpen => "O",
+        Text::BarHigh => "H",
+        Text::BarLow => "L",
+        Text::BarClose => "C",
+        Text::DayOpen => "Open",
+        Text::PreviousClose => "Previous close",
+        Text::DayRange => "Day range",
+        Text::FiftyTwoWeekRange => "52-week range",
+        Text::FromLow => "From 52-week low",
+        Text::FromHigh => "From 52-week high",
+        Text::Volume => "Volume",
+        Text::AverageVolume => "average",
+        Text::ExtendedHours => "Extended hours",
+        Text::Rolling1d => "24h change",
+        Text::Rolling7d => "7d change",
+        Text::RollingPeriod => "Period change",
+        Text::Alerts => "Alerts",
+        Text::AlertKind(kind) => match kind {
+            AlertKind::PriceCrosses => "Price crosses level",
+            AlertKind::ChangeBeyond => "Change beyond, %",
+            AlertKind::SpreadWider => "Spread wider than",
+            AlertKind::Stale => "No updates",
+        },
+        Text::AlertFired(kind) => match kind {
+            AlertKind::PriceCrosses => "price crossed",
+            AlertKind::ChangeBeyond => "change beyond",
+            AlertKind::SpreadWider => "spread wider than",
+            AlertKind::Stale => "quote is stale",
+        },
+        Text::AlertThreshold => "Value",
+        Text::AlertCooldown => "Cooldown, s",
+        Text::AddAlert => "Add rule",
+        Text::InvalidThreshold => "Enter a positive value",
+        Text::AlertEnabled => "Enabled",
+        Text::NoAlertRules => "No rules",
+        Text::AlertHistory => "Alert history",
+        Text::ClearHistory => "Clear",
+        Text::Portfolio => "Portfolio",
+        Text::Quantity => "Quantity",
+        Text::CostBasis => "Cost basis",
+        Text::CurrencyAuto => "Currency (auto)",
+        Text::MarketValue => "Market value",
+        Text::UnrealizedPnl => "Unrealised P&L",
+        Text::DayPnl => "Day P&L",
+        Text::Allocation => "Allocation",
+        Text::AddPosition => "Add position",
+        Text::InvalidPosition => "Enter a symbol, a non-zero quantity and the cost basis",
+        Text::BaseCurrency => "Base currency",
+        Text::Total => "Total",
+        Text::NoPositions => "No positions",
+        Text::IncompleteTotal => "Totals exclude positions without a price or an exchange rate",
+        Text::Converter => "Converter",
+        Text::Amount => "Amount",
+        Text::RatePath => "Via",
+        Text::NoRate => "No quoted pairs link these currencies",
+    };
+    text.to_owned()
+}
@@ -0,0 +1,557 @@
+use chrono::{DateTime, Datelike, NaiveDateTime, Timelike, Weekday};
+use chrono_tz::Tz;
+
+use crate::common::entities::Quote;
+use crate::common::enums::{MarketSession, QuoteType};
+
+/// Calendar date (year, month, day)
+type Date = (i32, u32, u32);
+
+/// Trading hours of an exchange. Times are minutes since the local midnight.
+#[derive(PartialEq, Debug)]
+pub struct ExchangeHours {
+    /// Exchange timezone
+    pub timezone: Tz,
+    /// Regular session opening
+    pub open: u32,
+    /// Regular session closing
+    pub close: u32,
+    /// Pre-market opening
+    pub pre_open: Option<u32>,
+    /// Post-market closing
+    pub post_close: Option<u32>,
+    /// Midday break, the market is closed
+    pub lunch: Option<(u32, u32)>,
+    /// Closing of the shortened sessions
+    pub early_close: u32,
+    /// Post-market closing of the shortened sessions
+    pub early_post_close: Option<u32>,
+    /// Full day closures, listed up to the last known year
+    pub holidays: &'static [Date],
+    /// Sessions closing at `early_close`
+    pub early_closes: &'static [Date],
+}
+
+const fn minutes(hour: u32, minute: u32) -> u32 {
+    hour * 60 + minute
+}
+
+/// NYSE and NASDAQ
+const US_HOURS: ExchangeHours = ExchangeHours {
+    timezone: Tz::America__New_York,
+    open: minutes(9, 30),
+    close: minutes(16, 0),
+    pre_open: Some(minutes(4, 0)),
+    post_close: Some(minutes(20, 0)),
+    lunch: None,
+    early_close: minutes(13, 0),
+    early_post_close: Some(minutes(17, 0)),
+    holidays: &[
+        (2025, 1, 1),
+        (2025, 1, 9),
+        (2025, 1, 20),
+        (2025, 2, 17),
+        (2025, 4, 18),
+        (2025, 5, 26),
+        (2025, 6, 19),
+        (2025, 7, 4),
+        (2025, 9, 1),
+        (2025, 11, 27),
+        (2025, 12, 25),
+        (2026, 1, 1),
+        (2026, 1, 19),
+        (2026, 2, 16),
+        (2026, 4, 3),
+        (2026, 5, 25),
+        (2026, 6, 19),
+        (2026, 7, 3),
+        (2026, 9, 7),
+        (2026, 11, 26),
+        (2026, 12, 25),
+        (2027, 1, 1),
+        (2027, 1, 18),
+        (2027, 2, 15),
+        (2027, 3, 26),
+        (2027, 5, 31),
+        (2027, 6, 18),
+        (2027, 7, 5),
+        (2027, 9, 6),
+        (2027, 11, 25),
+        (2027, 12, 24),
+    ],
+    early_closes: &[
+        (2025, 7, 3),
+        (2025, 11, 28),
+        (2025, 12, 24),
+        (2026, 11, 27),
+        (2026, 12, 24),
+        (2027, 11, 26),
+    ],
+};
+
+/// London Stock Exchange
+const LSE_HOURS: ExchangeHours = ExchangeHours {
+    timezone: Tz::Europe__London,
+    open: minutes(8, 0),
+    close: minutes(16, 30),
+    pre_open: None,
+    post_close: None,
+    lunch: None,
+    early_close: minutes(12, 30),
+    early_post_close: None,
+    holidays: &[
+        (2025, 1, 1),
+        (2025, 4, 18),
+        (2025, 4, 21),
+        (2025, 5, 5),
+        (2025, 5, 26),
+        (2025, 8, 25),
+        (2025, 12, 25),
+        (2025, 12, 26),
+        (2026, 1, 1),
+        (2026, 4, 3),
+        (2026, 4, 6),
+        (2026, 5, 4),
+        (2026, 5, 25),
+        (2026, 8, 31),
+        (2026, 12, 25),
+        (2026, 12, 28),
+        (2027, 1, 1),
+        (2027, 3, 26),
+        (2027, 3, 29),
+        (2027, 5, 3),
+        (2027, 5, 31),
+        (2027, 8, 30),
+        (2027, 12, 27),
+        (2027, 12, 28),
+    ],
+    early_closes: &[
+        (2025, 12, 24),
+        (2025, 12, 31),
+        (2026, 12, 24),
+        (2026, 12, 31),
+        (2027, 12, 24),
+        (2027, 12, 31),
+    ],
+};
+
+/// Deutsche Börse Xetra
+const XETRA_HOURS: ExchangeHours = ExchangeHours {
+    timezone: Tz::Europe__Berlin,
+    open: minutes(9, 0),
+    close: minutes(17, 30),
+    pre_open: None,
+    post_close: None,
+    lunch: None,
+    early_close: minutes(14, 0),
+    early_post_close: None,
+    holidays: &[
+        (2025, 1, 1),
+        (2025, 4, 18),
+        (2025, 4, 21),
+        (2025, 5, 1),
+        (2025, 12, 24),
+        (2025, 12, 25),
+        (2025, 12, 26),
+        (2025, 12, 31),
+        (2026, 1, 1),
+        (2026, 4, 3),
+        (2026, 4, 6),
+        (2026, 5, 1),
+        (2026, 12, 24),
+        (2026, 12, 25),
+        (2026, 12, 31),
+        (2027, 1, 1),
+        (2027, 3, 26),
+        (2027, 3, 29),
+        (2027, 12, 24),
+        (2027, 12, 31),
+    ],
+    early_closes: &[],
+};
+
+/// Euronext Paris
+const EURONEXT_HOURS: ExchangeHours = ExchangeHours {
+    timezone: Tz::Europe__Paris,
+    open: minutes(9, 0),
+    close: minutes(17, 30),
+    pre_open: None,
+    post_close: None,
+    lunch: None,
+    early_close: minutes(14, 5),
+    early_post_close: None,
+    holidays: &[
+        (2025, 1, 1),
+        (2025, 4, 18),
+        (2025, 4, 21),
+        (2025, 5, 1),
+        (2025, 12, 25),
+        (2025, 12, 26),
+        (2026, 1, 1),
+        (2026, 4, 3),
+        (2026, 4, 6),
+        (2026, 5, 1),
+        (2026, 12, 25),
+        (2027, 1, 1),
+        (2027, 3, 26),
+        (2027, 3, 29),
+    ],
+    early_closes: &[
+        (2025, 12, 24),
+        (2025, 12, 31),
+        (2026, 12, 24),
+        (2026, 12, 31),
+        (2027, 12, 24),
+        (2027, 12, 31),
+    ],
+};
+
+/// Japan Exchange Group, Tokyo
+const JPX_HOURS: ExchangeHours = ExchangeHours {
+    timezone: Tz::Asia__Tokyo,
+    open: minutes(9, 0),
+    close: minutes(15, 30),
+    pre_open: None,
+    post_close: None,
+    lunch: Some((minutes(11, 30), minutes(12, 30))),
+    early_close: minutes(15, 30),
+    early_post_close: None,
+    holidays: &[
+        (2025, 1, 1),
+        (2025, 1, 2),
+        (2025, 1, 3),
+        (2025, 1, 13),
+        (2025, 2, 11),
+        (2025, 2, 24),
+        (2025, 3, 20),
+        (2025, 4, 29),
+        (2025, 5, 5),
+        (2025, 5, 6),
+        (2025, 7, 21),
+        (2025, 8, 11),
+        (2025, 9, 15),
+        (2025, 9, 23),
+        (2025, 10, 13),
+        (2025, 11, 3),
+        (2025, 11, 24),
+        (2025, 12, 31),
+        (2026, 1, 1),
+        (2026, 1, 2),
+        (2026, 1, 12),
+        (2026, 2, 11),
+        (2026, 2, 23),
+        (2026, 3, 20),
+        (2026, 4, 29),
+        (2026, 5, 4),
+        (2026, 5, 5),
+        (2026, 5, 6),
+        (2026, 7, 20),
+        (2026, 8, 11),
+        (2026, 9, 21),
+        (2026, 9, 22),
+        (2026, 9, 23),
+        (2026, 10, 12),
+        (2026, 11, 3),
+        (2026, 11, 23),
+        (2026, 12, 31),
+        (2027, 1, 1),
+        (2027, 1, 11),
+        (2027, 2, 11),
+        (2027, 2, 23),
+        (2027, 3, 22),
+        (2027, 4, 29),
+        (2027, 5, 3),
+        (2027, 5, 4),
+        (2027, 5, 5),
+        (2027, 7, 19),
+        (2027, 8, 11),
+        (2027, 9, 20),
+        (2027, 9, 23),
+        (2027, 10, 11),
+        (2027, 11, 3),
+        (2027, 11, 23),
+        (2027, 12, 31),
+    ],
+    early_closes: &[],
+};
+
+/// Forex week in New York time: from Sunday 17:00 to Friday 17:00
+const FOREX_TIMEZONE: Tz = Tz::America__New_York;
+const FOREX_ROLLOVER: u32 = minutes(17, 0);
+
+/// Trading hours of the exchange by its Twelve Data name or MIC code
+pub fn exchange_hours(exchange: &str) -> Option<&'static ExchangeHours> {
+    match exchange {
+        "NASDAQ" | "NYSE" | "XNGS" | "XNAS" | "XNYS" | "SNP" | "DJI" | "CBOE" => Some(&US_HOURS),
+        "LSE" | "XLON" => Some(&LSE_HOURS),
+        "XETR" | "FSX" => Some(&XETRA_HOURS),
+        "Euronext" | "XPAR" => Some(&EURONEXT_HOURS),
+        "JPX" | "XJPX" => Some(&JPX_HOURS),
+        _ => None,
+    }
+}
+
+/// Trading hours of the quote exchange by its MIC code, or by the exchange name
+pub fn quote_exchange_hours(quote: &Quote) -> Option<&'static ExchangeHours> {
+    exchange_hours(&quote.mic_code).or_else(|| exchange_hours(&quote.exchange))
+}
+
+/// When the market of an instrument is open
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum MarketCalendar {
+    /// Cryptocurrencies trade around the clock
+    AlwaysOpen,
+    /// Currencies trade 24 hours five days a week
+    Forex,
+    /// Exchange sessions and holidays, and the state reported by the last quote
+    /// for the dates past the holiday tables
+    Exchange(&'static ExchangeHours, bool),
+    /// Unknown exchange: the state reported by the last quote
+    Reported(bool),
+}
+
+impl MarketCalendar {
+    /// Calendar by the quote type and the exchange of the last quote
+    pub fn for_quote(quote_type: QuoteType, quote: Option<&Quote>) -> Self {
+        match quote_type {
+            QuoteType::CryptoCurrency => MarketCalendar::AlwaysOpen,
+            QuoteType::Currency => MarketCalendar::Forex,
+            QuoteType::Indices | QuoteType::USStocks => {
+                let Some(quote) = quote else {
+                    return MarketCalendar::Reported(true);
+                };
+                match quote_exchange_hours(quote) {
+                    Some(hours) => MarketCalendar::Exchange(hours, quote.is_market_open),
+                    None => MarketCalendar::Reported(quote.is_market_open),
+                }
+            }
+        }
+    }
+    /// Trading session at the unix timestamp
+    pub fn session(&self, timestamp: i64) -> MarketSession {
+        match self {
+            MarketCalendar::AlwaysOpen => MarketSession::Open,
+            MarketCalendar::Forex => {
+                let Some(local) = local_time(FOREX_TIMEZONE, timestamp) else {
+                    return MarketSession::Closed;
+                };
+                let minute = local.hour() * 60 + local.minute();
+                let open = match local.weekday() {
+                    Weekday::Sat => false,
+                    Weekday::Sun => minute >= FOREX_ROLLOVER,
+                    Weekday::Fri => minute < FOREX_ROLLOVER,
+                    _ => true,
+                };
+                if open {
+                    MarketSession::Open
+                } else {
+                    MarketSession::Closed
+                }
+            }
+            MarketCalendar::Exchange(hours, reported_open) => hours
+                .session(timestamp)
+                .unwrap_or(MarketCalendar::Reported(*reported_open).session(timestamp)),
+            MarketCalendar::Reported(true) => MarketSession::Open,
+            MarketCalendar::Reported(false) => MarketSession::Closed,
+        }
+    }
+}
+
+impl ExchangeHours {
+    /// Trading session at the unix timestamp, `None` past the last year of the holiday table
+    /// where the holidays are unknown
+    pub fn session(&self, timestamp: i64) -> Option<MarketSession> {
+        let local = local_time(self.timezone, timestamp)?;
+        if self
+            .holidays
+            .last()
+            .is_none_or(|(last_year, _, _)| local.year() > *last_year)
+        {
+            return None;
+        }
+        let date = (local.year(), local.month(), local.day());
+        if matches!(local.weekday(), Weekday::Sat | Weekday::Sun) || self.holidays.contains(&date) {
+            return Some(MarketSession::Closed);
+        }
+        let minute = local.hour() * 60 + local.minute();
+        let (close, post_close) = if self.early_closes.contains(&date) {
+            (self.early_close, self.early_post_close)
+        } else {
+            (self.close, self.post_close)
+        };
+        let in_lunch = self
+            .lunch
+            .is_some_and(|(start, end)| (start..end).contains(&minute));
+        let session = if (self.open..close).contains(&minute) && !in_lunch {
+            MarketSession::Open
+        } else if self
+            .pre_open
+            .is_some_and(|pre_open| (pre_open..self.open).contains(&minute))
+        {
+            MarketSession::PreMarket
+        } else if post_close.is_some_and(|post_close| (close..post_close).contains(&minute)) {
+            MarketSession::PostMarket
+        } else {
+            MarketSession::Closed
+        };
+        Some(session)
+    }
+}
+
+fn local_time(timezone: Tz, timestamp: i64) -> Option<NaiveDateTime> {
+    DateTime::from_timestamp(timestamp, 0).map(|utc| utc.with_timezone(&timezone).naive_local())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, TimeZone};
+
+    use super::*;
+
+    /// Unix timestamp of the local time in the timezone
+    fn local(timezone: Tz, date: Date, time: (u32, u32)) -> i64 {
+        let naive = NaiveDate::from_ymd_opt(date.0, date.1, date.2)
+            .and_then(|date| date.and_hms_opt(time.0, time.1, 0))
+            .unwrap();
+        timezone
+            .from_local_datetime(&naive)
+            .single()
+            .unwrap()
+            .timestamp()
+    }
+
+    fn quote(exchange: &str, mic_code: &str, is_market_open: bool) -> Quote {
+        serde_json::from_value(serde_json::json!({
+            "symbol": "TEST",
+            "name": "Test",
+            "exchange": exchange,
+            "mic_code": mic_code,
+            "datetime": "2025-06-02",
+            "timestamp": 0,
+            "is_market_open": is_market_open,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn us_sessions_follow_the_new_york_clock() {
+        let ny = Tz::America__New_York;
+        let monday = (2025, 6, 2);
+        let sessions = [
+            ((3, 59), MarketSession::Closed),
+            ((4, 0), MarketSession::PreMarket),
+            ((9, 29), MarketSession::PreMarket),
+            ((9, 30), MarketSession::Open),
+            ((15, 59), MarketSession::Open),
+            ((16, 0), MarketSession::PostMarket),
+            ((20, 0), MarketSession::Closed),
+        ];
+        for (time, session) in sessions {
+            assert_eq!(
+                US_HOURS.session(local(ny, monday, time)),
+                Some(session),
+                "{time:?}"
+            );
+        }
+        let saturday = local(ny, (2025, 6, 7), (10, 0));
+        assert_eq!(US_HOURS.session(saturday), Some(MarketSession::Closed));
+    }
+
+    #[test]
+    fn holidays_and_early_closes() {
+        let ny = Tz::America__New_York;
+        let independence_day = local(ny, (2025, 7, 4), (10, 0));
+        assert_eq!(
+            US_HOURS.session(independence_day),
+            Some(MarketSession::Closed)
+        );
+        // the day after Thanksgiving closes at 13:00, the after-hours end at 17:00
+        let sessions = [
+            ((8, 0), MarketSession::PreMarket),
+            ((12, 59), MarketSession::Open),
+            ((13, 0), MarketSession::PostMarket),
+            ((16, 59), MarketSession::PostMarket),
+            ((17, 0), MarketSession::Closed),
+        ];
+        for (time, session) in sessions {
+            assert_eq!(
+                US_HOURS.session(local(ny, (2027, 11, 26), time)),
+                Some(session),
+                "{time:?}"
+            );
+        }
+        // London has no extended hours
+        let london = local(Tz::Europe__London, (2025, 12, 24), (13, 0));
+        assert_eq!(LSE_HOURS.session(london), Some(MarketSession::Closed));
+    }
+
+    #[test]
+    fn sessions_past_the_holiday_tables_are_reported_by_the_quote() {
+        let ny = Tz::America__New_York;
+        let new_year = local(ny, (2028, 1, 3), (10, 0));
+        assert_eq!(US_HOURS.session(new_year), None);
+        assert_eq!(
+            MarketCalendar::Exchange(&US_HOURS, false).session(new_year),
+            MarketSession::Closed
+        );
+        assert_eq!(
+            MarketCalendar::Exchange(&US_HOURS, true).session(new_year),
+            MarketSession::Open
+        );
+        let last_day = local(ny, (2027, 12, 31), (10, 0));
+        assert_eq!(
+            MarketCalendar::Exchange(&US_HOURS, false).session(last_day),
+            MarketSession::Open
+        );
+    }
+
+    #[test]
+    fn tokyo_closes_for_lunch() {
+        let tokyo = Tz::Asia__Tokyo;
+        let monday = (2025, 6, 2);
+        let session = |time| JPX_HOURS.session(local(tokyo, monday, time)).unwrap();
+        assert_eq!(session((11, 29)), MarketSession::Open);
+        assert_eq!(session((11, 30)), MarketSession::Closed);
+        assert_eq!(session((12, 30)), MarketSession::Open);
+        assert_eq!(session((15, 30)), MarketSession::Closed);
+    }
+
+    #[test]
+    fn forex_trades_from_sunday_to_friday_evening() {
+        let ny = Tz::America__New_York;
+        let session = |date, time| MarketCalendar::Forex.session(local(ny, date, time));
+        assert_eq!(session((2025, 6, 1), (16, 59)), MarketSession::Closed);
+        assert_eq!(session((2025, 6, 1), (17, 0)), MarketSession::Open);
+        assert_eq!(session((2025, 6, 4), (3, 0)), MarketSession::Open);
+        assert_eq!(session((2025, 6, 6), (16, 59)), MarketSession::Open);
+        assert_eq!(session((2025, 6, 6), (17, 0)), MarketSession::Closed);
+        assert_eq!(session((2025, 6, 7), (12, 0)), MarketSession::Closed);
+    }
+
+    #[test]
+    fn calendar_by_the_quote_exchange() {
+        assert_eq!(exchange_hours("XLON"), Some(&LSE_HOURS));
+        assert_eq!(exchange_hours("Euronext"), Some(&EURONEXT_HOURS));
+        // an unknown MIC code falls back to the exchange name
+        assert_eq!(
+            quote_exchange_hours(&quote("NASDAQ", "XXXX", false)),
+            Some(&US_HOURS)
+        );
+        assert_eq!(
+            MarketCalendar::for_quote(QuoteType::USStocks, Some(&quote("NYSE", "XNYS", false))),
+            MarketCalendar::Exchange(&US_HOURS, false)
+        );
+        assert_eq!(
+            MarketCalendar::for_quote(QuoteType::Indices, Some(&quote("TSX", "", false))),
+            MarketCalendar::Reported(false)
+        );
+        assert_eq!(
+            MarketCalendar::for_quote(QuoteType::Indices, None),
+            MarketCalendar::Reported(true)
+        );
+        assert_eq!(
+            MarketCalendar::for_quote(QuoteType::CryptoCurrency, None).session(0),
+            MarketSession::Open
+        );
+    }
+}
@@ -1,14 +1,38 @@
 use crate::common::error::MarketError;
+/// Price alert rules
+pub mod alerts;
+/// Runtime application configuration
+pub mod app_config;
+/// Reconnection backoff of the price feeds
+pub mod backoff;
+/// Live candle aggregation
+pub mod candles;
+/// Price change by the selected baseline
+pub mod change;
 /// Dashboard Configuration
 pub mod config;
 /// Common entities
 pub mod entities;
 /// Common enums
 pub mod enums;
-/// Env variables
-pub mod env;
 /// Application Error
 pub mod error;
+/// Time and number formatting
+pub mod format;
+/// Quote and price feed freshness
+pub mod freshness;
+/// Currency exchange rates
+pub mod fx;
+/// User interface message catalogs
+pub mod i18n;
+/// Exchange trading sessions and holidays
+pub mod market_hours;
+/// Portfolio positions and P&L
+pub mod portfolio;
+/// Per-instrument price precision
+pub mod precision;
+/// Exact decimal prices
+pub mod price;
 /// Common util functions
 pub mod utils;
 
@@ -0,0 +1,224 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::common::fx::FxGraph;
+use crate::common::price::Price;
+
+/// Position entered by the user
+#[derive(Serialize, Deserialize, PartialEq, Eq, Clone, Debug)]
+pub struct Position {
+    /// Unique id of the position
+    pub id: u64,
+    pub symbol: String,
+    /// Negative for a short position
+    pub quantity: Decimal,
+    /// Average purchase price per unit
+    pub cost_basis: Decimal,
+    /// Currency of the instrument prices and of the cost basis
+    pub currency: String,
+}
+
+/// Current and previous session prices of a position symbol
+#[derive(PartialEq, Clone, Copy, Default, Debug)]
+pub struct PositionPrices {
+    /// Live price, or the last quote close before the first tick
+    pub price: Price,
+    pub previous_close: Price,
+}
+
+/// Position values in its own currency and in the base currency. Values are `None` without
+/// a price, base currency values also without an exchange rate.
+#[derive(PartialEq, Clone, Debug)]
+pub struct PositionValue {
+    pub position: Position,
+    pub price: Option<Decimal>,
+    pub market_value: Option<Decimal>,
+    pub unrealized: Option<Decimal>,
+    /// Percent of the cost
+    pub unrealized_percent: Option<Decimal>,
+    /// Change of the market value since the previous close
+    pub day_pnl: Option<Decimal>,
+    pub base_market_value: Option<Decimal>,
+    pub base_unrealized: Option<Decimal>,
+    pub base_day_pnl: Option<Decimal>,
+    /// Percent of the total base currency market value
+    pub allocation: Option<Decimal>,
+}
+
+/// Portfolio valued in the base currency
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct PortfolioValuation {
+    pub base_currency: String,
+    pub positions: Vec<PositionValue>,
+    pub market_value: Decimal,
+    pub unrealized: Decimal,
+    pub day_pnl: Decimal,
+    /// Some positions are left out of the totals for a missing price or exchange rate
+    pub incomplete: bool,
+}
+
+/// Valuing the positions at the current prices, converted by the exchange rates
+pub fn valuate(
+    positions: &[Position],
+    base_currency: &str,
+    prices: impl Fn(&str) -> PositionPrices,
+    rates: &FxGraph,
+) -> PortfolioValuation {
+    let mut valuation = PortfolioValuation {
+        base_currency: base_currency.to_owned(),
+        ..Default::default()
+    };
+    for position in positions {
+        let prices = prices(&position.symbol);
+        let quantity = position.quantity;
+        let price = prices.price.value();
+        let market_value = price.and_then(|price| quantity.checked_mul(price));
+        let cost = quantity.checked_mul(position.cost_basis);
+        let unrealized = market_value.zip(cost).map(|(value, cost)| value - cost);
+        let unrealized_percent = unrealized.zip(cost).and_then(|(unrealized, cost)| {
+            unrealized
+                .checked_mul(Decimal::ONE_HUNDRED)?
+                .checked_div(cost.abs())
+        });
+        let day_pnl = price
+            .zip(prices.previous_close.value())
+            .and_then(|(price, previous_close)| quantity.checked_mul(price - previous_close));
+        let to_base =
+            |amount: Option<Decimal>| rates.convert(amount?, &position.currency, base_currency);
+        let value = PositionValue {
+            position: position.clone(),
+            price,
+            market_value,
+            unrealized,
+            unrealized_percent,
+            day_pnl,
+            base_market_value: to_base(market_value),
+            base_unrealized: to_base(unrealized),
+            base_day_pnl: to_base(day_pnl),
+            allocation: None,
+        };
+        match (value.base_market_value, value.base_unrealized) {
+            (Some(market_value), Some(unrealized)) => {
+                valuation.market_value += market_value;
+                valuation.unrealized += unrealized;
+                valuation.day_pnl += value.base_day_pnl.unwrap_or_default();
+            }
+            _ => valuation.incomplete = true,
+        }
+        valuation.positions.push(value);
+    }
+    // allocation of the gross exposure, short positions count by their absolute value
+    let gross: Decimal = valuation
+        .positions
+        .iter()
+        .filter_map(|value| value.base_market_value)
+        .map(|value| value.abs())
+        .sum();
+    for value in &mut valuation.positions {
+        value.allocation = value.base_market_value.and_then(|market_value| {
+            market_value
+                .abs()
+                .checked_mul(Decimal::ONE_HUNDRED)?
+                .checked_div(gross)
+        });
+    }
+    valuation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fx::FxQuote;
+
+    fn dec(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    fn position(
+        id: u64,
+        symbol: &str,
+        quantity: &str,
+        cost_basis: &str,
+        currency: &str,
+    ) -> Position {
+        Position {
+            id,
+            symbol: symbol.to_owned(),
+            quantity: dec(quantity),
+            cost_basis: dec(cost_basis),
+            currency: currency.to_owned(),
+        }
+    }
+
+    fn prices(symbol: &str) -> PositionPrices {
+        let (price, previous_close) = match symbol {
+            "AAPL" => ("110", "105"),
+            "SAP" => ("200", "210"),
+            _ => return PositionPrices::default(),
+        };
+        PositionPrices {
+            price: Price::parse(price),
+            previous_close: Price::parse(previous_close),
+        }
+    }
+
+    fn rates() -> FxGraph {
+        let mut rates = FxGraph::default();
+        rates.insert(
+            "EUR/USD",
+            FxQuote {
+                bid: dec("1.1"),
+                ask: dec("1.1"),
+            },
+        );
+        rates
+    }
+
+    #[test]
+    fn short_position_gains_when_the_price_falls() {
+        let positions = [
+            position(1, "AAPL", "10", "100", "USD"),
+            position(2, "SAP", "-5", "220", "EUR"),
+        ];
+        let valuation = valuate(&positions, "USD", prices, &rates());
+        let long = &valuation.positions[0];
+        assert_eq!(long.market_value, Some(dec("1100")));
+        assert_eq!(long.unrealized, Some(dec("100")));
+        assert_eq!(long.unrealized_percent, Some(dec("10")));
+        assert_eq!(long.day_pnl, Some(dec("50")));
+        let short = &valuation.positions[1];
+        assert_eq!(short.market_value, Some(dec("-1000")));
+        assert_eq!(short.unrealized, Some(dec("100")));
+        assert_eq!(
+            short.unrealized_percent.map(|percent| percent.round_dp(4)),
+            Some(dec("9.0909"))
+        );
+        assert_eq!(short.day_pnl, Some(dec("50")));
+        assert_eq!(short.base_market_value, Some(dec("-1100")));
+        assert_eq!(valuation.market_value, dec("0"));
+        assert_eq!(valuation.unrealized, dec("210"));
+        assert_eq!(valuation.day_pnl, dec("105"));
+        assert_eq!(long.allocation, Some(dec("50")));
+        assert_eq!(short.allocation, Some(dec("50")));
+        assert!(!valuation.incomplete);
+    }
+
+    #[test]
+    fn positions_without_a_price_or_rate_are_left_out_of_the_totals() {
+        let positions = [
+            position(1, "AAPL", "10", "100", "USD"),
+            position(2, "MSFT", "1", "400", "USD"),
+            position(3, "AAPL", "1", "100", "CHF"),
+        ];
+        let valuation = valuate(&positions, "USD", prices, &rates());
+        assert!(valuation.incomplete);
+        assert_eq!(valuation.market_value, dec("1100"));
+        assert_eq!(valuation.unrealized, dec("100"));
+        let no_price = &valuation.positions[1];
+        assert_eq!((no_price.market_value, no_price.allocation), (None, None));
+        let no_rate = &valuation.positions[2];
+        assert_eq!(no_rate.market_value, Some(dec("110")));
+        assert_eq!(no_rate.base_market_value, None);
+        assert_eq!(valuation.positions[0].allocation, Some(dec("100")));
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+
+use crate::common::enums::QuoteType;
+
+/// Decimals of percentage changes
+pub const PERCENT_DECIMALS: u32 = 2;
+/// Prices are shown at least with cents
+pub const MIN_DECIMALS: u32 = 2;
+/// Longer fractions are float noise rather than tick size
+const MAX_DECIMALS: u32 = 8;
+
+/// Display precision of the instruments. The tick size is unknown up front, so the decimals
+/// are the longest fraction observed in the prices of the symbol, or a guess by the instrument
+/// type, quote currency and price level until the first price arrives.
+#[derive(Clone, Default, Debug)]
+pub struct PrecisionTracker {
+    observed: HashMap<String, u32>,
+}
+
+impl PrecisionTracker {
+    pub fn observe(&mut self, symbol: &str, price: f64) {
+        if !price.is_finite() || price == 0.0 {
+            return;
+        }
+        let decimals = fraction_len(price);
+        let observed = self.observed.entry(symbol.to_owned()).or_default();
+        *observed = (*observed).max(decimals);
+    }
+    /// Observing a live tick. The quoted bid and ask are on the instrument tick size, while
+    /// a price computed as their mid has a digit more, so the price counts only without them.
+    pub fn observe_tick(&mut self, symbol: &str, price: f64, bid: f64, ask: f64) {
+        let quoted: Vec<f64> = [bid, ask]
+            .into_iter()
+            .filter(|value| value.is_finite() && *value != 0.0)
+            .collect();
+        if quoted.is_empty() {
+            self.observe(symbol, price);
+        }
+        for value in quoted {
+            self.observe(symbol, value);
+        }
+    }
+    /// Decimals of the prices and price changes of the symbol
+    pub fn decimals(&self, symbol: &str, quote_type: QuoteType, price: Option<f64>) -> u32 {
+        match self.observed.get(symbol) {
+            Some(&observed) => observed.max(MIN_DECIMALS),
+            None => default_decimals(symbol, quote_type, price),
+        }
+    }
+}
+
+/// Number of digits after the decimal point, rounded to `MAX_DECIMALS` first so that
+/// float noise like 67353.48000000001 counts as 67353.48
+fn fraction_len(value: f64) -> u32 {
+    let text = format!("{:.*}", MAX_DECIMALS as usize, value);
+    text.split_once('.').map_or(0, |(_, fraction)| {
+        fraction.trim_end_matches('0').len() as u32
+    })
+}
+
+fn default_decimals(symbol: &str, quote_type: QuoteType, price: Option<f64>) -> u32 {
+    let price = price.map(f64::abs).unwrap_or_default();
+    match quote_type {
+        // pips with a fractional pip, yen pairs are quoted with two decimals less
+        QuoteType::Currency => match symbol.rsplit_once('/') {
+            Some((_, "JPY")) => 3,
+            _ => 5,
+        },
+        // about six significant digits, from bitcoin to sub cent coins
+        QuoteType::CryptoCurrency => match price {
+            price if price >= 1000.0 => 2,
+            price if price >= 10.0 => 3,
+            price if price >= 1.0 => 4,
+            _ => 6,
+        },
+        QuoteType::Indices => 2,
+        QuoteType::USStocks if price > 0.0 && price < 1.0 => 4,
+        QuoteType::USStocks => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimals_are_guessed_until_a_price_is_observed() {
+        let precision = PrecisionTracker::default();
+        let guess = |symbol, quote_type, price| precision.decimals(symbol, quote_type, price);
+        assert_eq!(guess("EUR/USD", QuoteType::Currency, None), 5);
+        assert_eq!(guess("USD/JPY", QuoteType::Currency, Some(150.0)), 3);
+        assert_eq!(
+            guess("BTC/USD", QuoteType::CryptoCurrency, Some(65000.0)),
+            2
+        );
+        assert_eq!(guess("ETH/BTC", QuoteType::CryptoCurrency, Some(0.05)), 6);
+        assert_eq!(guess("SPX", QuoteType::Indices, None), 2);
+        assert_eq!(guess("PENNY", QuoteType::USStocks, Some(0.5)), 4);
+        assert_eq!(guess("AAPL", QuoteType::USStocks, Some(190.0)), 2);
+    }
+
+    #[test]
+    fn the_longest_observed_fraction_wins() {
+        let mut precision = PrecisionTracker::default();
+        precision.observe("EUR/USD", 1.0854);
+        precision.observe("EUR/USD", 1.08);
+        assert_eq!(precision.decimals("EUR/USD", QuoteType::Currency, None), 4);
+        precision.observe("EUR/USD", 1.08545);
+        assert_eq!(precision.decimals("EUR/USD", QuoteType::Currency, None), 5);
+        // whole prices still show cents
+        precision.observe("SPX", 5000.0);
+        assert_eq!(
+            precision.decimals("SPX", QuoteType::Indices, None),
+            MIN_DECIMALS
+        );
+    }
+
+    #[test]
+    fn float_noise_and_missing_prices_are_ignored() {
+        assert_eq!(fraction_len(0.1 + 0.2), 1);
+        assert_eq!(fraction_len(67353.48000000001), 2);
+        assert_eq!(fraction_len((0.05205 + 0.05207) / 2.0), 5);
+        assert_eq!(fraction_len(1.5e-9), 0);
+        let mut precision = PrecisionTracker::default();
+        precision.observe("BTC/USD", 67353.48000000001);
+        assert_eq!(
+            precision.decimals("BTC/USD", QuoteType::CryptoCurrency, None),
+            MIN_DECIMALS
+        );
+        precision.observe("AAPL", 0.0);
+        precision.observe("AAPL", f64::NAN);
+        assert_eq!(
+            precision.decimals("AAPL", QuoteType::USStocks, Some(0.5)),
+            4
+        );
+    }
+
+    #[test]
+    fn mid_prices_do_not_add_a_digit() {
+        let mut precision = PrecisionTracker::default();
+        precision.observe_tick("ETH/BTC", (0.05205 + 0.05208) / 2.0, 0.05205, 0.05208);
+        assert_eq!(
+            precision.decimals("ETH/BTC", QuoteType::CryptoCurrency, None),
+            5
+        );
+        // without a quoted spread the price is all there is
+        precision.observe_tick("AAPL", 190.125, 0.0, 0.0);
+        assert_eq!(precision.decimals("AAPL", QuoteType::USStocks, None), 3);
+    }
+}
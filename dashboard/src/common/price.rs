@@ -0,0 +1,138 @@
+use std::str::FromStr;
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+/// Exact decimal price. Empty, missing and unparseable provider values are kept as `None`,
+/// so they render as a dash instead of a fake zero.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct Price(Option<Decimal>);
+
+impl Price {
+    pub fn new(value: Decimal) -> Self {
+        Self(Some(value))
+    }
+    /// Real-time price, by the shortest decimal representation of the float
+    pub fn from_f64(value: f64) -> Self {
+        if !value.is_finite() {
+            return Self(None);
+        }
+        Self::parse(&value.to_string())
+    }
+    /// Price text of the provider, plain or in scientific notation
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        Self(
+            Decimal::from_str(text)
+                .or_else(|_| Decimal::from_scientific(text))
+                .ok(),
+        )
+    }
+
+    pub fn value(self) -> Option<Decimal> {
+        self.0
+    }
+    /// Float value for charts and precision detection
+    pub fn to_f64(self) -> Option<f64> {
+        self.0.and_then(|value| value.to_f64())
+    }
+}
+
+/// Change of a price against a base price
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct PriceChange {
+    pub absolute: Decimal,
+    /// Percent of the base price, `None` for a zero base
+    pub percent: Option<Decimal>,
+}
+
+impl PriceChange {
+    /// Change between the prices, `None` if either is missing
+    pub fn between(price: Price, base: Price) -> Option<Self> {
+        let (price, base) = (price.value()?, base.value()?);
+        let absolute = price - base;
+        let percent = absolute
+            .checked_mul(Decimal::ONE_HUNDRED)
+            .and_then(|value| value.checked_div(base));
+        Some(Self { absolute, percent })
+    }
+}
+
+impl Serialize for Price {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Some(value) => serializer.serialize_str(&value.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Price {
+    /// Providers send prices as strings or as numbers, anything else is a missing price
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match Value::deserialize(deserializer)? {
+            Value::String(text) => Price::parse(&text),
+            Value::Number(number) => Price::parse(&number.to_string()),
+            _ => Price(None),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn dec(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn provider_values_are_parsed_exactly() {
+        assert_eq!(Price::parse(" 1.08545 ").value(), Some(dec("1.08545")));
+        assert_eq!(Price::parse("1.5e-7").value(), Some(dec("0.00000015")));
+        assert_eq!(Price::parse("").value(), None);
+        assert_eq!(Price::parse("N/A").value(), None);
+        assert_eq!(
+            Price::from_f64(0.1 + 0.2).value(),
+            Some(dec("0.30000000000000004"))
+        );
+        assert_eq!(Price::from_f64(f64::INFINITY).value(), None);
+    }
+
+    #[test]
+    fn strings_numbers_and_nulls_deserialize() {
+        let prices: Vec<Price> =
+            serde_json::from_value(json!(["190.12", 190.12, null, "", true])).unwrap();
+        assert_eq!(
+            prices,
+            [
+                Price::new(dec("190.12")),
+                Price::new(dec("190.12")),
+                Price::default(),
+                Price::default(),
+                Price::default(),
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value([Price::new(dec("1.50")), Price::default()]).unwrap(),
+            json!(["1.50", null])
+        );
+    }
+
+    #[test]
+    fn change_against_the_base_price() {
+        let change = PriceChange::between(Price::parse("99"), Price::parse("100")).unwrap();
+        assert_eq!(change.absolute, dec("-1"));
+        assert_eq!(change.percent, Some(dec("-1")));
+        let zero_base = PriceChange::between(Price::parse("1"), Price::parse("0")).unwrap();
+        assert_eq!(zero_base.percent, None);
+        assert_eq!(
+            PriceChange::between(Price::default(), Price::parse("1")),
+            None
+        );
+    }
+}
@@ -1,28 +1,39 @@
 use std::collections::HashSet;
-use std::time::{Duration, UNIX_EPOCH};
 
-use chrono::{DateTime, FixedOffset, Utc};
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, NaiveDate, Utc};
+
+use crate::common::enums::TimeSeriesInterval;
 
-/// Format timestamp(i64) to user friendly String
-pub fn format_time(timestamp: i64) -> String {
-    let secs = u64::try_from(timestamp).unwrap_or(0);
-    let d = UNIX_EPOCH + Duration::from_secs(secs);
-    let datetime = DateTime::<Utc>::from(d);
-    let datetime_offset = datetime.with_timezone(
-        &FixedOffset::east_opt(3 * 3600)
-            .unwrap_or_else(|| FixedOffset::east_opt(0).expect("UTC+0 is always valid")),
-    );
-    datetime_offset.format("%H:%M:%S").to_string()
-}
 /// Concat symbol names for server requests
 pub fn prepare_symbols_for_url(symbols: HashSet<String>) -> String {
     symbols.into_iter().collect::<Vec<_>>().join(",")
 }
-/// Round f64 value to 4 decimal places
-pub fn round_f64(before: f64) -> f64 {
-    (before * 10000.0).round() / 10000.0
-}
-/// Parse and round f64 value to 4 decimal places
-pub fn round_f64_str(str_f64: &str) -> f64 {
-    str_f64.parse::<f64>().map(round_f64).unwrap_or(0.0)
+/// Opening time of the bar containing the timestamp, in UTC
+pub fn bar_start(interval: TimeSeriesInterval, timestamp: i64) -> i64 {
+    let seconds = match interval {
+        TimeSeriesInterval::OneMinute => 60,
+        TimeSeriesInterval::FiveMinutes => 5 * 60,
+        TimeSeriesInterval::FifteenMinutes => 15 * 60,
+        TimeSeriesInterval::ThirtyMinutes => 30 * 60,
+        TimeSeriesInterval::OneHour => 3600,
+        TimeSeriesInterval::FourHours => 4 * 3600,
+        TimeSeriesInterval::OneDay => 24 * 3600,
+        TimeSeriesInterval::OneWeek | TimeSeriesInterval::OneMonth => {
+            let date = DateTime::<Utc>::from_timestamp(timestamp, 0)
+                .unwrap_or_default()
+                .date_naive();
+            let start = if interval == TimeSeriesInterval::OneWeek {
+                // weekly bars start on Monday
+                date - ChronoDuration::days(i64::from(date.weekday().num_days_from_monday()))
+            } else {
+                NaiveDate::from_ymd_opt(date.year(), date.month(), 1).unwrap_or(date)
+            };
+            return start
+                .and_hms_opt(0, 0, 0)
+                .unwrap_or_default()
+                .and_utc()
+                .timestamp();
+        }
+    };
+    timestamp - timestamp.rem_euclid(seconds)
 }
@@ -0,0 +1,250 @@
+use std::collections::VecDeque;
+
+use rust_decimal::Decimal;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::events::{Event, TargetCast};
+use yew::{
+    classes, function_component, html, use_state, Callback, Html, Properties, UseStateHandle,
+};
+
+use crate::common::alerts::{
+    AlertCondition, AlertEvent, AlertKind, AlertRule, DEFAULT_COOLDOWN_SECS,
+};
+use crate::common::enums::Language;
+use crate::common::format::DisplayFormat;
+use crate::common::i18n::Text;
+use crate::common::precision::PERCENT_DECIMALS;
+use crate::common::price::Price;
+use crate::services::alerts::AlertAction;
+
+/// Alert Panel Properties
+#[derive(Properties, PartialEq, Clone)]
+pub struct AlertPanelProps {
+    pub rules: Vec<AlertRule>,
+    /// Fired alerts, the newest first
+    pub history: VecDeque<AlertEvent>,
+    /// Symbols of all watchlists
+    pub symbols: Vec<String>,
+    /// Time and number formatting
+    pub format: DisplayFormat,
+    /// User interface language
+    pub language: Language,
+    /// Alert change
+    pub on_action: Callback<AlertAction>,
+}
+
+/// Creating, enabling and deleting alert rules, and the log of the fired alerts
+#[function_component]
+pub fn AlertPanel(props: &AlertPanelProps) -> Html {
+    let symbol = use_state(String::new);
+    let kind = use_state(|| AlertKind::PriceCrosses);
+    let threshold = use_state(String::new);
+    let cooldown = use_state(|| DEFAULT_COOLDOWN_SECS.to_string());
+    let invalid = use_state(|| false);
+    let language = props.language;
+
+    // the first symbol until another one is selected
+    let selected_symbol = props
+        .symbols
+        .iter()
+        .find(|value| **value == *symbol)
+        .or(props.symbols.first())
+        .cloned()
+        .unwrap_or_default();
+    let on_symbol = {
+        let symbol = symbol.clone();
+        Callback::from(move |event: Event| {
+            let select: HtmlSelectElement = event.target_unchecked_into();
+            symbol.set(select.value());
+        })
+    };
+    let on_kind = {
+        let kind = kind.clone();
+        Callback::from(move |event: Event| {
+            let select: HtmlSelectElement = event.target_unchecked_into();
+            if let Some(&selected) = usize::try_from(select.selected_index())
+                .ok()
+                .and_then(|index| AlertKind::ALL.get(index))
+            {
+                kind.set(selected);
+            }
+        })
+    };
+    let on_threshold = input_setter(&threshold);
+    let on_cooldown = input_setter(&cooldown);
+    let on_create = {
+        let on_action = props.on_action.clone();
+        let (kind, threshold, cooldown, invalid) = (
+            kind.clone(),
+            threshold.clone(),
+            cooldown.clone(),
+            invalid.clone(),
+        );
+        let symbol = selected_symbol.clone();
+        Callback::from(move |_| {
+            let value = match *kind {
+                AlertKind::Stale => Some(Decimal::ZERO),
+                _ => Price::parse(&threshold)
+                    .value()
+                    .filter(|value| *value > Decimal::ZERO),
+            };
+            let Some(value) = value else {
+                invalid.set(true);
+                return;
+            };
+            invalid.set(false);
+            threshold.set(String::new());
+            on_action.emit(AlertAction::Create {
+                symbol: symbol.clone(),
+                condition: AlertCondition::new(*kind, value),
+                cooldown_secs: cooldown.trim().parse().unwrap_or(DEFAULT_COOLDOWN_SECS),
+            });
+        })
+    };
+    let on_clear = props.on_action.reform(|_| AlertAction::ClearHistory);
+
+    html! {
+        <div class="alert-panel">
+            <div class="alert-create">
+                <select onchange={on_symbol}>
+                {
+                    props.symbols.iter().map(|value| html! {
+                        <option selected={*value == selected_symbol}>{value}</option>
+                    }).collect::<Html>()
+                }
+                </select>
+                <select onchange={on_kind}>
+                {
+                    AlertKind::ALL.iter().map(|&value| html! {
+                        <option selected={value == *kind}>{language.text(Text::AlertKind(value))}</option>
+                    }).collect::<Html>()
+                }
+                </select>
+                if *kind != AlertKind::Stale {
+                    <input class="alert-threshold" placeholder={language.text(Text::AlertThreshold)}
+                           value={(*threshold).clone()} onchange={on_threshold} />
+                }
+                <label>
+                    {language.text(Text::AlertCooldown)}{" "}
+                    <input class="alert-cooldown" type="number" min="0" value={(*cooldown).clone()}
+                           onchange={on_cooldown} />
+                </label>
+                <button onclick={on_create}>{language.text(Text::AddAlert)}</button>
+                if *invalid {
+                    <span class="color-red">{language.text(Text::InvalidThreshold)}</span>
+                }
+            </div>
+            <ul class="alert-rules">
+            if props.rules.is_empty() {
+                <li class="alert-empty">{language.text(Text::NoAlertRules)}</li>
+            }
+            {
+                props.rules.iter().map(|rule| {
+                    let id = rule.id;
+                    let on_toggle = props.on_action.reform(move |_| AlertAction::Toggle { id });
+                    let on_delete = props.on_action.reform(move |_| AlertAction::Delete { id });
+                    html! {
+                        <li class={classes!((!rule.enabled).then_some("disabled"))}>
+                            <input type="checkbox" title={language.text(Text::AlertEnabled)}
+                                   checked={rule.enabled} onchange={on_toggle} />
+                            <span>{rule_text(rule, language, props.format)}</span>
+                            <span class="alert-cooldown-value">{format!("{} s", rule.cooldown_secs)}</span>
+                            <button title={language.text(Text::Delete)} onclick={on_delete}>{"✕"}</button>
+                        </li>
+                    }
+                }).collect::<Html>()
+            }
+            </ul>
+            <div class="alert-history-title">
+                <span>{language.text(Text::AlertHistory)}</span>
+                <button disabled={props.history.is_empty()} onclick={on_clear}>{language.text(Text::ClearHistory)}</button>
+            </div>
+            <ul class="alert-history">
+            {
+                props.history.iter().map(|event| html! {
+                    <li>
+                        <span class="alert-time">{props.format.date_time((event.fired_at / 1000.0) as i64)}</span>
+                        {alert_message(event, language, props.format)}
+                    </li>
+                }).collect::<Html>()
+            }
+            </ul>
+        </div>
+    }
+}
+
+/// Alert Toasts Properties
+#[derive(Properties, PartialEq, Clone)]
+pub struct AlertToastsProps {
+    /// Recently fired alerts, the oldest first
+    pub toasts: Vec<AlertEvent>,
+    /// Time and number formatting
+    pub format: DisplayFormat,
+    /// User interface language
+    pub language: Language,
+    /// Closing the toast by index
+    pub on_dismiss: Callback<usize>,
+}
+
+/// In-page notifications of the fired alerts
+#[function_component]
+pub fn AlertToasts(props: &AlertToastsProps) -> Html {
+    html! {
+        <div class="alert-toasts">
+        {
+            props.toasts.iter().enumerate().map(|(index, event)| {
+                let on_dismiss = props.on_dismiss.reform(move |_| index);
+                html! {
+                    <div class="alert-toast" onclick={on_dismiss}>
+                        {alert_message(event, props.language, props.format)}
+                    </div>
+                }
+            }).collect::<Html>()
+        }
+        </div>
+    }
+}
+
+/// Text of the fired alert, e.g. "BTC/USD: price crossed 65,000 (65,012.5)"
+pub fn alert_message(event: &AlertEvent, language: Language, format: DisplayFormat) -> String {
+    let kind = event.condition.kind();
+    let mut message = format!(
+        "{}: {}",
+        event.symbol,
+        language.text(Text::AlertFired(kind))
+    );
+    if let Some(threshold) = event.condition.threshold() {
+        message.push(' ');
+        message.push_str(&alert_value(kind, threshold, format));
+    }
+    if let Some(value) = event.value {
+        message.push_str(&format!(" ({})", alert_value(kind, value, format)));
+    }
+    message
+}
+
+fn rule_text(rule: &AlertRule, language: Language, format: DisplayFormat) -> String {
+    let kind = rule.condition.kind();
+    let mut text = format!("{}: {}", rule.symbol, language.text(Text::AlertKind(kind)));
+    if let Some(threshold) = rule.condition.threshold() {
+        text.push(' ');
+        text.push_str(&alert_value(kind, threshold, format));
+    }
+    text
+}
+
+/// Thresholds and values keep their own decimals, percents are rounded
+fn alert_value(kind: AlertKind, value: Decimal, format: DisplayFormat) -> String {
+    match kind {
+        AlertKind::ChangeBeyond => format!("{}%", format.decimal(Some(value), PERCENT_DECIMALS)),
+        _ => format.decimal(Some(value), value.normalize().scale()),
+    }
+}
+
+fn input_setter(state: &UseStateHandle<String>) -> Callback<Event> {
+    let state = state.clone();
+    Callback::from(move |event: Event| {
+        let input: HtmlInputElement = event.target_unchecked_into();
+        state.set(input.value());
+    })
+}
@@ -0,0 +1,43 @@
+use web_sys::HtmlSelectElement;
+use yew::events::{Event, TargetCast};
+use yew::{function_component, html, Callback, Html, Properties};
+
+use crate::common::enums::{ChangeBaseline, Language};
+use crate::common::i18n::Text;
+
+/// Baseline Switcher Properties
+#[derive(Properties, PartialEq, Clone)]
+pub struct BaselineSwitcherProps {
+    /// Current reference price of the changes
+    pub baseline: ChangeBaseline,
+    /// User interface language
+    pub language: Language,
+    /// Baseline selected
+    pub on_change: Callback<ChangeBaseline>,
+}
+
+#[function_component]
+pub fn BaselineSwitcher(props: &BaselineSwitcherProps) -> Html {
+    let on_change = {
+        let on_change = props.on_change.clone();
+        Callback::from(move |event: Event| {
+            let select: HtmlSelectElement = event.target_unchecked_into();
+            if let Some(&baseline) = usize::try_from(select.selected_index())
+                .ok()
+                .and_then(|index| ChangeBaseline::ALL.get(index))
+            {
+                on_change.emit(baseline);
+            }
+        })
+    };
+
+    html! {
+        <select class="baseline-switcher" title={props.language.text(Text::Baseline)} onchange={on_change}>
+        {
+            ChangeBaseline::ALL.iter().map(|&value| html! {
+                <option selected={value == props.baseline}>{props.language.text(Text::ChangeBaseline(value))}</option>
+            }).collect::<Html>()
+        }
+        </select>
+    }
+}
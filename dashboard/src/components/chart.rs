@@ -0,0 +1,149 @@
+use std::rc::Rc;
+
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use yew::{function_component, html, use_effect_with_deps, use_node_ref, Html, Properties};
+
+use crate::common::entities::Bar;
+use crate::common::enums::ChartKind;
+use crate::common::format::DisplayFormat;
+
+const WIDTH: u32 = 600;
+const HEIGHT: u32 = 280;
+/// Space for the price axis on the right
+const AXIS_WIDTH: f64 = 70.0;
+/// Space above and below the plot
+const PADDING: f64 = 10.0;
+/// Space for the time labels at the bottom
+const TIME_AXIS_HEIGHT: f64 = 16.0;
+const PRICE_LINES: u32 = 4;
+const GROWTH_COLOR: &str = "#008000";
+const DECLINE_COLOR: &str = "#FF0000";
+const LINE_COLOR: &str = "#20207C";
+const GRID_COLOR: &str = "#E0E0E0";
+const LABEL_COLOR: &str = "#6C7A89";
+
+/// Price Chart Component Properties
+#[derive(Properties, PartialEq, Clone)]
+pub struct PriceChartProps {
+    /// Bars in ascending time order
+    pub bars: Rc<Vec<Bar>>,
+    /// Candlesticks or close price line
+    pub kind: ChartKind,
+    /// Time and price label formatting
+    pub format: DisplayFormat,
+    /// Price label decimals
+    pub decimals: u32,
+}
+
+#[function_component]
+pub fn PriceChart(props: &PriceChartProps) -> Html {
+    let canvas_ref = use_node_ref();
+    {
+        let canvas_ref = canvas_ref.clone();
+        use_effect_with_deps(
+            move |(bars, kind, format, decimals)| {
+                if let Some(canvas) = canvas_ref.cast::<HtmlCanvasElement>() {
+                    draw(&canvas, bars, *kind, format, *decimals);
+                }
+                || ()
+            },
+            (props.bars.clone(), props.kind, props.format, props.decimals),
+        );
+    }
+
+    html! {
+        <canvas class="price-chart" ref={canvas_ref} width={WIDTH.to_string()} height={HEIGHT.to_string()} />
+    }
+}
+
+fn draw(
+    canvas: &HtmlCanvasElement,
+    bars: &[Bar],
+    kind: ChartKind,
+    format: &DisplayFormat,
+    decimals: u32,
+) {
+    let Some(context) = canvas
+        .get_context("2d")
+        .ok()
+        .flatten()
+        .and_then(|context| context.dyn_into::<CanvasRenderingContext2d>().ok())
+    else {
+        return;
+    };
+    let (width, height) = (f64::from(canvas.width()), f64::from(canvas.height()));
+    context.clear_rect(0.0, 0.0, width, height);
+    if bars.is_empty() {
+        return;
+    }
+
+    let low = bars.iter().map(|bar| bar.low).fold(f64::INFINITY, f64::min);
+    let high = bars
+        .iter()
+        .map(|bar| bar.high)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let span = (high - low).max(f64::EPSILON);
+    let plot_width = width - AXIS_WIDTH;
+    let plot_height = height - 2.0 * PADDING - TIME_AXIS_HEIGHT;
+    let y = |price: f64| PADDING + (high - price) / span * plot_height;
+    let step = plot_width / bars.len() as f64;
+    let x = |index: usize| (index as f64 + 0.5) * step;
+
+    context.set_font("11px sans-serif");
+    context.set_line_width(1.0);
+    for line in 0..=PRICE_LINES {
+        let price = low + span * f64::from(line) / f64::from(PRICE_LINES);
+        context.set_stroke_style(&JsValue::from_str(GRID_COLOR));
+        context.begin_path();
+        context.move_to(0.0, y(price));
+        context.line_to(plot_width, y(price));
+        context.stroke();
+        context.set_fill_style(&JsValue::from_str(LABEL_COLOR));
+        let _ = context.fill_text(
+            &format.number(price, decimals),
+            plot_width + 4.0,
+            y(price) + 4.0,
+        );
+    }
+    let label_y = height - 4.0;
+    if let (Some(first), Some(last)) = (bars.first(), bars.last()) {
+        let _ = context.fill_text(&format.date_time(first.time), 0.0, label_y);
+        let _ = context.fill_text(&format.date_time(last.time), plot_width - 80.0, label_y);
+    }
+
+    match kind {
+        ChartKind::Candles => {
+            let body_width = (step * 0.7).max(1.0);
+            for (index, bar) in bars.iter().enumerate() {
+                let color = if bar.close >= bar.open {
+                    GROWTH_COLOR
+                } else {
+                    DECLINE_COLOR
+                };
+                context.set_stroke_style(&JsValue::from_str(color));
+                context.set_fill_style(&JsValue::from_str(color));
+                context.begin_path();
+                context.move_to(x(index), y(bar.high));
+                context.line_to(x(index), y(bar.low));
+                context.stroke();
+                let top = y(bar.open.max(bar.close));
+                let body_height = (y(bar.open.min(bar.close)) - top).max(1.0);
+                context.fill_rect(x(index) - body_width / 2.0, top, body_width, body_height);
+            }
+        }
+        ChartKind::Line => {
+            context.set_stroke_style(&JsValue::from_str(LINE_COLOR));
+            context.set_line_width(1.5);
+            context.begin_path();
+            for (index, bar) in bars.iter().enumerate() {
+                if index == 0 {
+                    context.move_to(x(index), y(bar.close));
+                } else {
+                    context.line_to(x(index), y(bar.close));
+                }
+            }
+            context.stroke();
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use std::rc::Rc;
+
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::events::{Event, TargetCast};
+use yew::{function_component, html, use_state, Callback, Html, Properties, UseStateHandle};
+
+use crate::common::enums::Language;
+use crate::common::format::DisplayFormat;
+use crate::common::fx::FxGraph;
+use crate::common::i18n::Text;
+use crate::common::precision::MIN_DECIMALS;
+use crate::common::price::Price;
+
+/// Decimals of the cross rates, enough for the pairs quoted in pips
+const RATE_DECIMALS: u32 = 5;
+
+/// Currency Converter Properties
+#[derive(Properties, PartialEq, Clone)]
+pub struct CurrencyConverterProps {
+    /// Live quotes of the currency pairs
+    pub graph: Rc<FxGraph>,
+    /// Target currency until another one is selected
+    pub base_currency: String,
+    /// Time and number formatting
+    pub format: DisplayFormat,
+    /// User interface language
+    pub language: Language,
+}
+
+/// Converting an amount between any currencies linked by the quoted pairs
+#[function_component]
+pub fn CurrencyConverter(props: &CurrencyConverterProps) -> Html {
+    let amount = use_state(|| "1".to_owned());
+    let from = use_state(String::new);
+    let to = use_state(String::new);
+    let language = props.language;
+    let format = props.format;
+    let currencies = props.graph.currencies();
+
+    // the first other currency and the base currency until selected
+    let selected = |state: &UseStateHandle<String>, default: Option<&String>| {
+        currencies
+            .iter()
+            .find(|currency| **currency == **state)
+            .or(default)
+            .cloned()
+            .unwrap_or_default()
+    };
+    let selected_to = selected(
+        &to,
+        currencies
+            .iter()
+            .find(|currency| **currency == props.base_currency)
+            .or(currencies.first()),
+    );
+    let selected_from = selected(
+        &from,
+        currencies.iter().find(|currency| **currency != selected_to),
+    );
+    let currency_options = |selected: &str| {
+        currencies
+            .iter()
+            .map(|currency| {
+                html! {
+                    <option value={currency.clone()} selected={currency == selected}>{currency}</option>
+                }
+            })
+            .collect::<Html>()
+    };
+    let on_swap = {
+        let (from, to) = (from.clone(), to.clone());
+        let (selected_from, selected_to) = (selected_from.clone(), selected_to.clone());
+        Callback::from(move |_| {
+            from.set(selected_to.clone());
+            to.set(selected_from.clone());
+        })
+    };
+
+    let value = Price::parse(&amount).value();
+    let rate = props
+        .graph
+        .cross_rate(&selected_from, &selected_to)
+        .filter(|_| !currencies.is_empty());
+    let result = match rate {
+        Some(rate) => {
+            let converted = value.and_then(|value| value.checked_mul(rate.quote.mid()));
+            html! {
+                <>
+                    <span class="converter-result">
+                        {format!("= {} {}", format.decimal(converted, MIN_DECIMALS), selected_to)}
+                    </span>
+                    <span class="converter-rate">
+                        {format!(
+                            "1 {} = {} {}, {} {} / {} {}",
+                            selected_from,
+                            format.decimal(Some(rate.quote.mid()), RATE_DECIMALS),
+                            selected_to,
+                            language.text(Text::ColumnBid),
+                            format.decimal(Some(rate.quote.bid), RATE_DECIMALS),
+                            language.text(Text::ColumnAsk),
+                            format.decimal(Some(rate.quote.ask), RATE_DECIMALS),
+                        )}
+                    </span>
+                    if rate.path.len() > 2 {
+                        <span class="converter-rate">
+                            {format!("{}: {}", language.text(Text::RatePath), rate.path.join(" → "))}
+                        </span>
+                    }
+                </>
+            }
+        }
+        None => html! { <span class="converter-rate">{language.text(Text::NoRate)}</span> },
+    };
+
+    html! {
+        <div class="currency-converter">
+            <input class="converter-amount" placeholder={language.text(Text::Amount)}
+                   value={(*amount).clone()} onchange={input_setter(&amount)} />
+            <select onchange={select_setter(&from)}>{currency_options(&selected_from)}</select>
+            <button class="converter-swap" onclick={on_swap}>{"⇄"}</button>
+            <select onchange={select_setter(&to)}>{currency_options(&selected_to)}</select>
+            {result}
+        </div>
+    }
+}
+
+fn input_setter(state: &UseStateHandle<String>) -> Callback<Event> {
+    let state = state.clone();
+    Callback::from(move |event: Event| {
+        let input: HtmlInputElement = event.target_unchecked_into();
+        state.set(input.value());
+    })
+}
+
+fn select_setter(state: &UseStateHandle<String>) -> Callback<Event> {
+    let state = state.clone();
+    Callback::from(move |event: Event| {
+        let select: HtmlSelectElement = event.target_unchecked_into();
+        state.set(select.value());
+    })
+}
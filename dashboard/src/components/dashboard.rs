@@ -1,20 +1,54 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::rc::Rc;
 use std::sync::Arc;
 
-use linked_hash_set::LinkedHashSet;
-use log::{error, info};
+use futures::join;
+use gloo::timers::callback::Interval;
+use js_sys::Date;
+use log::{error, info, warn};
 use yew::{
-    function_component, html, BaseComponent, Component, Context, Html, HtmlResult, Properties,
+    classes, function_component, html, BaseComponent, Callback, Component, Context, Html,
+    HtmlResult, Properties,
 };
 
-use crate::common::config::DashboardConfiguration;
-use crate::common::entities::{PriceMessage, RealTimePriceData, ReferenceData, WSResponseEvent};
-use crate::common::enums::{QuoteType, QuotesComponentType, WSResponseEventType};
+use crate::common::alerts::{AlertEngine, AlertEvent, AlertInput};
+use crate::common::app_config::AppConfig;
+use crate::common::candles::{CandleAggregator, CandleInterval, ExchangeClock};
+use crate::common::change::price_change;
+use crate::common::entities::{
+    EndOfDay, PriceTick, Quote, RealTimePriceData, ReferenceData, Watchlist,
+};
+use crate::common::enums::{
+    ChangeBaseline, ConnectionState, FeedEvent, Language, MarketSession, PriceMove, ProviderKind,
+    QuoteType,
+};
 use crate::common::error::MarketError;
-use crate::common::utils::format_time;
+use crate::common::freshness::FreshnessTracker;
+use crate::common::fx::{is_currency_code, split_pair, FxGraph, FxQuote};
+use crate::common::i18n::Text;
+use crate::common::market_hours::MarketCalendar;
+use crate::common::portfolio::{valuate, PortfolioValuation, Position, PositionPrices};
+use crate::common::precision::{PrecisionTracker, MIN_DECIMALS};
+use crate::common::price::Price;
+use crate::common::MarketResult;
+use crate::components::alerts::{alert_message, AlertPanel, AlertToasts};
+use crate::components::baseline::BaselineSwitcher;
+use crate::components::converter::CurrencyConverter;
+use crate::components::detail::InstrumentDetail;
+use crate::components::language::LanguageSwitcher;
+use crate::components::portfolio::PortfolioPanel;
 use crate::components::quotes::{QuotesComponent, QuotesProps};
-use crate::components::suspense::use_load_data;
-use crate::services::websocket::WebSocketService;
+use crate::components::recorder::SessionRecorderControls;
+use crate::components::status::{ConnectionStatus, FeedDelayWarning};
+use crate::components::suspense::{use_load_data, LoadedData};
+use crate::components::watchlists::WatchlistEditor;
+use crate::services::alerts::{AlertAction, AlertNotifier, AlertService};
+use crate::services::config::ConfigService;
+use crate::services::portfolio::{PortfolioAction, PortfolioService};
+use crate::services::providers::ProviderRef;
+use crate::services::recorder::SessionRecorder;
+use crate::services::supervisor::{ConnectionSupervisor, SupervisorCallbacks};
+use crate::services::watchlists::{WatchlistAction, WatchlistService};
 
 pub type AppContent = WithLoadingData<DashboardComponent>;
 
@@ -24,35 +58,136 @@ pub fn WithLoadingData<Comp>() -> HtmlResult
 where
     Comp: BaseComponent<Properties = DashboardComponentProps>,
 {
-    let reference_data = use_load_data()?;
+    let LoadedData {
+        config,
+        provider,
+        watchlists,
+        reference_data,
+    } = use_load_data()?;
 
-    Ok(
-        yew::virtual_dom::VChild::<Comp>::new(DashboardComponentProps { reference_data }, None)
-            .into(),
+    Ok(yew::virtual_dom::VChild::<Comp>::new(
+        DashboardComponentProps {
+            config,
+            provider,
+            watchlists,
+            reference_data,
+        },
+        None,
     )
+    .into())
 }
 
 /// Dashboard Component Properties
-#[derive(Debug, PartialEq, Properties)]
+#[derive(PartialEq, Properties)]
 pub struct DashboardComponentProps {
+    config: Rc<AppConfig>,
+    provider: ProviderRef,
+    watchlists: Vec<Watchlist>,
     reference_data: ReferenceData,
 }
 
 /// Dashboard struct Component
 pub struct DashboardComponent {
-    crypto_currencies_symbols: Arc<LinkedHashSet<String>>,
-    currencies_symbols: Arc<LinkedHashSet<String>>,
-    indices_symbols: Arc<LinkedHashSet<String>>,
-    us_stocks_symbols: Arc<LinkedHashSet<String>>,
-    prices: HashMap<String, RealTimePriceData>,
+    watchlists: Vec<Watchlist>,
+    /// Watchlist editing mode
+    editing: bool,
+    /// User interface language
+    language: Language,
+    /// Reference price of the changes
+    baseline: ChangeBaseline,
+    /// Last prices by symbol. The maps passed to every watchlist are shared and copied on change.
+    prices: Rc<HashMap<String, RealTimePriceData>>,
+    /// Recent prices by symbol, the oldest first
+    price_history: Rc<HashMap<String, VecDeque<f64>>>,
     reference_data: Arc<ReferenceData>,
+    connection_state: ConnectionState,
+    supervisor: ConnectionSupervisor,
+    recorder: SessionRecorder,
+    selected_symbol: Option<String>,
+    candles: CandleAggregator,
+    /// Price decimals observed by symbol
+    precision: PrecisionTracker,
+    /// Price decimals of the watchlist symbols, updated with the prices and watchlists
+    decimals: Rc<HashMap<String, u32>>,
+    /// Symbols rejected by the price feed subscription
+    failed_symbols: Rc<HashSet<String>>,
+    freshness: FreshnessTracker,
+    /// Seconds since the last price while the feed is delayed
+    feed_delay: Option<u64>,
+    /// Current trading session by symbol
+    sessions: Rc<HashMap<String, MarketSession>>,
+    /// Alert rules, their state and the fired alerts
+    alerts: AlertEngine,
+    notifier: AlertNotifier,
+    /// Recently fired alerts shown in the page, the oldest first
+    toasts: Vec<AlertEvent>,
+    /// Alert panel is open
+    alerts_open: bool,
+    positions: Vec<Position>,
+    /// Currency of the portfolio totals
+    base_currency: String,
+    /// Currency pairs at the current prices, rebuilt when a pair price changes
+    fx_graph: Rc<FxGraph>,
+    /// Portfolio at the current prices, revalued when a position price or rate changes
+    valuation: Rc<PortfolioValuation>,
+    /// Portfolio panel is open
+    portfolio_open: bool,
+    /// Currency converter is open
+    converter_open: bool,
+    /// Periodic freshness check, stopped when dropped
+    _freshness_timer: Interval,
 }
 /// Dashboard Component Messages
 pub enum DashboardMessage {
-    /// Web socket response message
-    WebSocketResponse(String),
+    /// Price feed event
+    FeedEvent(FeedEvent),
     /// Market error message
     MarketError(MarketError),
+    /// Web socket connection state change
+    ConnectionState(ConnectionState),
+    /// Manual reconnection request
+    Reconnect,
+    /// Starting or stopping the session recording
+    ToggleRecording,
+    /// The recording stopped at the frame limit
+    RecordingStopped,
+    /// Saving the recorded session
+    DownloadSession,
+    /// Opening the detail panel of the instrument
+    SelectSymbol(String),
+    /// Closing the detail panel
+    CloseDetail,
+    /// Switching the watchlist editing mode
+    ToggleEditing,
+    /// Switching the user interface language
+    SetLanguage(Language),
+    /// Switching the reference price of the changes
+    SetBaseline(ChangeBaseline),
+    /// Watchlist change
+    Watchlist(WatchlistAction),
+    /// Opening or closing the alert panel
+    ToggleAlerts,
+    /// Alert rule change
+    Alert(AlertAction),
+    /// Closing the in-page alert by index
+    DismissToast(usize),
+    /// Opening or closing the portfolio panel
+    TogglePortfolio,
+    /// Portfolio position change
+    Portfolio(PortfolioAction),
+    /// Switching the currency of the portfolio totals
+    SetBaseCurrency(String),
+    /// Opening or closing the currency converter
+    ToggleConverter,
+    /// Saving the watchlists to a file
+    ExportWatchlists,
+    /// Periodic check of the stale quotes and the feed delay
+    CheckFreshness,
+    /// Reference data of the added symbols or of the opened markets
+    ReferenceLoaded {
+        end_of_day: MarketResult<HashMap<String, EndOfDay>>,
+        last_quote: MarketResult<HashMap<String, Quote>>,
+    },
 }
 
 impl Component for DashboardComponent {
@@ -60,155 +195,812 @@ impl Component for DashboardComponent {
     type Properties = DashboardComponentProps;
 
     fn create(ctx: &Context<Self>) -> Self {
-        let ws_soket_connection_result = WebSocketService::open_ws_connection();
-        match ws_soket_connection_result {
-            Ok(mut web_socket) => {
-                let success_response_callback =
-                    ctx.link().callback(DashboardMessage::WebSocketResponse);
-                let error_response_callback = ctx.link().callback(DashboardMessage::MarketError);
-                let subscribe_result = web_socket.subscribe_real_time_rates(
-                    DashboardConfiguration::get_all_quote_symbols(),
-                    success_response_callback,
-                    error_response_callback.clone(),
-                );
-                match subscribe_result {
-                    Ok(_) => {
-                        info!("Web socket subscribe Success");
-                    }
-                    Err(error) => {
-                        error!("Web socket subscribe Error: {:?}", error);
+        let recorder = SessionRecorder::default();
+        if ctx.props().config.record_session {
+            recorder.start();
+        }
+        let frame_recorder = recorder.clone();
+        let recorder_link = ctx.link().clone();
+        let supervisor = ConnectionSupervisor::new(
+            &ctx.props().provider,
+            WatchlistService::symbols_by_type(&ctx.props().watchlists),
+            SupervisorCallbacks {
+                frame: Callback::from(move |frame: String| {
+                    if frame_recorder.record(&frame) {
+                        recorder_link.send_message(DashboardMessage::RecordingStopped);
                     }
-                }
+                }),
+                event: ctx.link().callback(DashboardMessage::FeedEvent),
+                error: ctx.link().callback(DashboardMessage::MarketError),
+                state: ctx.link().callback(DashboardMessage::ConnectionState),
+            },
+        );
+        supervisor.start();
+        let link = ctx.link().clone();
+        let freshness_timer = Interval::new(FRESHNESS_CHECK_MS, move || {
+            link.send_message(DashboardMessage::CheckFreshness)
+        });
 
-                let heartbeat_result = web_socket.heartbeat(error_response_callback.clone());
-                match heartbeat_result {
-                    Ok(()) => {
-                        info!("Web socket heartbeat Success");
-                    }
-                    Err(error) => {
-                        error!("Web socket heartbeat Error: {:?}", error);
-                    }
-                }
-            }
-            Err(error) => {
-                error!("Web socket connection Error: {:?}", error);
-            }
-        }
-        Self {
-            crypto_currencies_symbols: Arc::new(
-                DashboardConfiguration::get_crypto_currencies_symbols(),
-            ),
-            currencies_symbols: Arc::new(DashboardConfiguration::get_currencies_symbols()),
-            indices_symbols: Arc::new(DashboardConfiguration::get_indices_symbols()),
-            us_stocks_symbols: Arc::new(DashboardConfiguration::get_us_stocks()),
-            prices: HashMap::new(),
+        let sessions = market_sessions(
+            &ctx.props().watchlists,
+            &ctx.props().reference_data,
+            Date::now(),
+        );
+
+        let mut dashboard = Self {
+            watchlists: ctx.props().watchlists.clone(),
+            editing: false,
+            language: ctx.props().config.language,
+            baseline: ctx.props().config.change_baseline,
+            prices: Rc::default(),
+            price_history: Rc::default(),
             reference_data: Arc::new(ctx.props().reference_data.clone()),
-        }
+            connection_state: ConnectionState::default(),
+            supervisor,
+            recorder,
+            selected_symbol: None,
+            candles: Self::create_candle_aggregator(&ctx.props().reference_data),
+            precision: Self::create_precision_tracker(&ctx.props().reference_data),
+            decimals: Rc::default(),
+            failed_symbols: Rc::default(),
+            freshness: FreshnessTracker::new(Date::now()),
+            feed_delay: None,
+            sessions: Rc::new(sessions),
+            alerts: AlertService::load(),
+            notifier: AlertNotifier::default(),
+            toasts: Vec::new(),
+            alerts_open: false,
+            positions: PortfolioService::load(),
+            base_currency: ctx.props().config.base_currency.clone(),
+            fx_graph: Rc::default(),
+            valuation: Rc::default(),
+            portfolio_open: false,
+            converter_open: false,
+            _freshness_timer: freshness_timer,
+        };
+        dashboard.update_fx_graph();
+        dashboard.update_decimals();
+        dashboard
     }
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
-            DashboardMessage::WebSocketResponse(data) => {
-                let response_event: WSResponseEvent =
-                    serde_json::from_str(data.as_str()).unwrap_or_default();
-
-                match WSResponseEventType::from(response_event.event) {
-                    WSResponseEventType::SubscribeStatus => {
-                        info!("subscribe status {:?}", data);
+            DashboardMessage::FeedEvent(event) => match event {
+                FeedEvent::SubscribeStatus(status) => {
+                    info!("subscribe status {:?}", status);
+                    if !status.fails.is_empty() {
+                        warn!("Price feed rejected symbols {:?}", status.fails);
                     }
-                    WSResponseEventType::Price => {
-                        match serde_json::from_str::<PriceMessage>(data.as_str()) {
-                            Ok(price_message) => {
-                                info!("price_message {:?}", price_message);
-                                self.prices.insert(
-                                    price_message.symbol.clone(),
-                                    RealTimePriceData {
-                                        symbol: price_message.symbol,
-                                        price: price_message.price,
-                                        bid: price_message.bid,
-                                        ask: price_message.ask,
-                                        time: format_time(price_message.timestamp),
-                                        ..Default::default()
-                                    },
-                                );
-                            }
-                            Err(e) => {
-                                error!("Failed to parse price message: {}", e);
-                            }
-                        }
+                    let failed = Rc::make_mut(&mut self.failed_symbols);
+                    for symbol in &status.success {
+                        failed.remove(symbol);
                     }
-                    WSResponseEventType::Heartbeat => {
-                        info!("heart beat status {:?}", data);
+                    failed.extend(status.fails);
+                }
+                FeedEvent::Price(tick) => {
+                    self.candles.push(&tick);
+                    let history = Rc::make_mut(&mut self.price_history)
+                        .entry(tick.symbol.clone())
+                        .or_default();
+                    if history.len() == PRICE_HISTORY_CAPACITY {
+                        history.pop_front();
+                    }
+                    history.push_back(tick.price);
+                    let last_move = match self.prices.get(&tick.symbol) {
+                        Some(last) if tick.price > last.price => PriceMove::Up,
+                        Some(last) if tick.price < last.price => PriceMove::Down,
+                        _ => PriceMove::Unchanged,
+                    };
+                    self.freshness
+                        .record_tick(&tick.symbol, last_move, Date::now());
+                    self.precision
+                        .observe_tick(&tick.symbol, tick.price, tick.bid, tick.ask);
+                    let alerts = self.check_alerts(&tick);
+                    // not every tick carries the volume
+                    let day_volume = match self.prices.get(&tick.symbol) {
+                        Some(last) if tick.day_volume == 0.0 => last.day_volume,
+                        _ => tick.day_volume,
+                    };
+                    let pair = split_pair(&tick.symbol).is_some();
+                    let position = self
+                        .positions
+                        .iter()
+                        .any(|position| position.symbol == tick.symbol);
+                    let symbol = tick.symbol.clone();
+                    Rc::make_mut(&mut self.prices).insert(
+                        tick.symbol.clone(),
+                        RealTimePriceData {
+                            symbol: tick.symbol,
+                            price: tick.price,
+                            bid: tick.bid,
+                            ask: tick.ask,
+                            day_volume,
+                            timestamp: tick.timestamp,
+                            ..Default::default()
+                        },
+                    );
+                    let decimals = self.get_symbol_decimals(&symbol);
+                    if self.decimals.get(&symbol) != Some(&decimals) {
+                        Rc::make_mut(&mut self.decimals).insert(symbol, decimals);
                     }
-                    WSResponseEventType::Unknown => {
-                        info!("unknown message {:?}", data);
+                    if pair {
+                        self.update_fx_graph();
+                    } else if position {
+                        self.update_valuation();
                     }
+                    self.raise_alerts(ctx, alerts);
                 }
-            }
+                FeedEvent::Heartbeat => {
+                    info!("heart beat status");
+                }
+                FeedEvent::Unknown(data) => {
+                    info!("unknown message {:?}", data);
+                }
+            },
             DashboardMessage::MarketError(error) => {
                 error!("MarketErrorResponse: {}", error);
             }
+            DashboardMessage::ConnectionState(state) => {
+                info!("Web socket connection state: {:?}", state);
+                if state == ConnectionState::Live {
+                    self.freshness.feed_live(Date::now());
+                }
+                self.connection_state = state;
+            }
+            DashboardMessage::Reconnect => {
+                self.supervisor.restart_failed();
+            }
+            DashboardMessage::ToggleRecording => {
+                if self.recorder.is_recording() {
+                    self.recorder.stop();
+                } else {
+                    self.recorder.start();
+                }
+            }
+            // only the recorder controls change
+            DashboardMessage::RecordingStopped => {}
+            DashboardMessage::DownloadSession => {
+                if let Err(error) = self.recorder.download() {
+                    error!("Failed to save the recorded session: {}", error);
+                }
+                return false;
+            }
+            DashboardMessage::SelectSymbol(symbol) => {
+                self.selected_symbol = Some(symbol);
+            }
+            DashboardMessage::CloseDetail => {
+                self.selected_symbol = None;
+            }
+            DashboardMessage::ToggleEditing => {
+                self.editing = !self.editing;
+            }
+            DashboardMessage::SetLanguage(language) => {
+                if language == self.language {
+                    return false;
+                }
+                self.language = language;
+                let value = serde_json::to_value(language).unwrap_or_default();
+                if let Err(error) = ConfigService::save_override("language", value) {
+                    error!("Failed to save language: {}", error);
+                }
+            }
+            DashboardMessage::SetBaseline(baseline) => {
+                if baseline == self.baseline {
+                    return false;
+                }
+                self.baseline = baseline;
+                let value = serde_json::to_value(baseline).unwrap_or_default();
+                if let Err(error) = ConfigService::save_override("change_baseline", value) {
+                    error!("Failed to save change baseline: {}", error);
+                }
+            }
+            DashboardMessage::Watchlist(action) => {
+                return self.update_watchlists(ctx, action);
+            }
+            DashboardMessage::ExportWatchlists => {
+                if let Err(error) = WatchlistService::export(&self.watchlists) {
+                    error!("Failed to export watchlists: {}", error);
+                }
+                return false;
+            }
+            DashboardMessage::CheckFreshness => {
+                let changed = self.check_freshness(ctx);
+                let now = Date::now();
+                let alerts = self.alerts.on_freshness(self.freshness.quotes(), now);
+                let toasts = self.toasts.len();
+                self.toasts
+                    .retain(|toast| now - toast.fired_at < TOAST_DURATION_MS);
+                let alerted = self.raise_alerts(ctx, alerts);
+                return changed || alerted || toasts != self.toasts.len();
+            }
+            DashboardMessage::ToggleAlerts => {
+                self.alerts_open = !self.alerts_open;
+            }
+            DashboardMessage::Alert(action) => {
+                if let AlertAction::Create { .. } = action {
+                    // a user action, browsers ask for the permission and start audio only on one
+                    let config = &ctx.props().config;
+                    self.notifier
+                        .prepare(config.alert_notifications, config.alert_sound);
+                }
+                if !AlertService::apply(&mut self.alerts, action) {
+                    return false;
+                }
+                if let Err(error) = AlertService::save(&self.alerts) {
+                    error!("Failed to save alerts: {}", error);
+                }
+            }
+            DashboardMessage::DismissToast(index) => {
+                if index >= self.toasts.len() {
+                    return false;
+                }
+                self.toasts.remove(index);
+            }
+            DashboardMessage::TogglePortfolio => {
+                self.portfolio_open = !self.portfolio_open;
+            }
+            DashboardMessage::ToggleConverter => {
+                self.converter_open = !self.converter_open;
+            }
+            DashboardMessage::Portfolio(action) => {
+                return self.update_portfolio(ctx, action);
+            }
+            DashboardMessage::SetBaseCurrency(currency) => {
+                let currency = currency.trim().to_uppercase();
+                if currency == self.base_currency || !is_currency_code(&currency) {
+                    // the input shows the current base currency again
+                    return true;
+                }
+                self.base_currency = currency;
+                self.update_valuation();
+                let value = serde_json::Value::String(self.base_currency.clone());
+                if let Err(error) = ConfigService::save_override("base_currency", value) {
+                    error!("Failed to save base currency: {}", error);
+                }
+            }
+            DashboardMessage::ReferenceLoaded {
+                end_of_day,
+                last_quote,
+            } => {
+                let reference_data = Arc::make_mut(&mut self.reference_data);
+                match end_of_day {
+                    Ok(end_of_day) => reference_data.end_of_day.extend(end_of_day),
+                    Err(error) => error!("Failed to load end of day prices: {}", error),
+                }
+                match last_quote {
+                    Ok(last_quote) => {
+                        for (symbol, quote) in &last_quote {
+                            self.candles
+                                .set_clock(symbol, ExchangeClock::for_quote(quote));
+                            observe_close(&mut self.precision, symbol, quote);
+                        }
+                        reference_data.last_quote.extend(last_quote);
+                    }
+                    Err(error) => error!("Failed to load last quotes: {}", error),
+                }
+                self.update_fx_graph();
+                self.update_decimals();
+            }
         }
         true
     }
 
-    fn view(&self, _ctx: &Context<Self>) -> Html {
-        let crypto_currencies_props = QuotesProps {
-            title: "Крипто-валюты".to_owned(),
-            component_type: QuotesComponentType::BidAsk,
-            quote_type: QuoteType::CryptoCurrency,
-            symbols: self.crypto_currencies_symbols.clone(),
-            prices: self.get_quote_data(QuoteType::CryptoCurrency),
-            reference_data: self.reference_data.clone(),
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let on_select = ctx.link().callback(DashboardMessage::SelectSymbol);
+        let format = ctx.props().config.display_format();
+        let watchlists = self
+            .watchlists
+            .iter()
+            .enumerate()
+            .map(|(index, watchlist)| {
+                let props = QuotesProps {
+                    title: watchlist.title(self.language),
+                    component_type: watchlist.quote_type.component_type(),
+                    quote_type: watchlist.quote_type,
+                    symbols: watchlist.symbols.clone(),
+                    prices: self.prices.clone(),
+                    price_history: self.price_history.clone(),
+                    reference_data: self.reference_data.clone(),
+                    selected: self.selected_symbol.clone(),
+                    on_select: on_select.clone(),
+                    failed: self.failed_symbols.clone(),
+                    freshness: self.freshness.quotes().clone(),
+                    format,
+                    language: self.language,
+                    precision: self.decimals.clone(),
+                    baseline: self.baseline,
+                    sessions: self.sessions.clone(),
+                    sort: watchlist.sort,
+                    on_sort: ctx.link().callback(move |sort| {
+                        DashboardMessage::Watchlist(WatchlistAction::Sort { index, sort })
+                    }),
+                    editing: self.editing,
+                    provider: ctx.props().provider.clone(),
+                    on_add_symbol: ctx.link().callback(move |symbol| {
+                        DashboardMessage::Watchlist(WatchlistAction::AddSymbol { index, symbol })
+                    }),
+                    on_remove_symbol: ctx.link().callback(move |symbol| {
+                        DashboardMessage::Watchlist(WatchlistAction::RemoveSymbol { index, symbol })
+                    }),
+                };
+                html! { <div class="watchlist"><QuotesComponent ..props /></div> }
+            })
+            .collect::<Html>();
+        let on_retry = ctx.link().callback(|_| DashboardMessage::Reconnect);
+        let on_toggle = ctx.link().callback(|_| DashboardMessage::ToggleRecording);
+        let on_download = ctx.link().callback(|_| DashboardMessage::DownloadSession);
+        let config = &ctx.props().config;
+        let binance_excluded =
+            [Some(config.provider), config.crypto_provider].contains(&Some(ProviderKind::Binance));
+        let on_edit = ctx.link().callback(|_| DashboardMessage::ToggleEditing);
+        let edit_label = self.language.text(if self.editing {
+            Text::Done
+        } else {
+            Text::Customize
+        });
+        html! {
+            <>
+              <div class="toolbar">
+                  <ConnectionStatus state={self.connection_state} language={self.language} {on_retry} />
+                  if let Some(seconds) = self.feed_delay {
+                      <FeedDelayWarning {seconds} language={self.language} />
+                  }
+                  <SessionRecorderControls
+                      recording={self.recorder.is_recording()}
+                      frames={self.recorder.frame_count()}
+                      binance_excluded={binance_excluded}
+                      language={self.language}
+                      {on_toggle}
+                      {on_download} />
+                  <button class="watchlist-toggle" onclick={on_edit}>{edit_label}</button>
+                  <button class={classes!("portfolio-toggle", self.portfolio_open.then_some("selected"))}
+                          onclick={ctx.link().callback(|_| DashboardMessage::TogglePortfolio)}>
+                      {self.language.text(Text::Portfolio)}
+                  </button>
+                  <button class={classes!("converter-toggle", self.converter_open.then_some("selected"))}
+                          onclick={ctx.link().callback(|_| DashboardMessage::ToggleConverter)}>
+                      {self.language.text(Text::Converter)}
+                  </button>
+                  <button class={classes!("alerts-toggle", self.alerts_open.then_some("selected"))}
+                          onclick={ctx.link().callback(|_| DashboardMessage::ToggleAlerts)}>
+                      {self.language.text(Text::Alerts)}
+                  </button>
+                  <BaselineSwitcher
+                      baseline={self.baseline}
+                      language={self.language}
+                      on_change={ctx.link().callback(DashboardMessage::SetBaseline)} />
+                  <LanguageSwitcher
+                      language={self.language}
+                      on_change={ctx.link().callback(DashboardMessage::SetLanguage)} />
+              </div>
+              if self.editing {
+                  <WatchlistEditor
+                      watchlists={self.watchlists.clone()}
+                      language={self.language}
+                      on_action={ctx.link().callback(DashboardMessage::Watchlist)}
+                      on_export={ctx.link().callback(|_| DashboardMessage::ExportWatchlists)} />
+              }
+              if self.alerts_open {
+                  <AlertPanel
+                      rules={self.alerts.rules().to_vec()}
+                      history={self.alerts.history().clone()}
+                      symbols={self.get_alert_symbols()}
+                      {format}
+                      language={self.language}
+                      on_action={ctx.link().callback(DashboardMessage::Alert)} />
+              }
+              if self.portfolio_open {
+                  <PortfolioPanel
+                      valuation={self.valuation.clone()}
+                      precision={self.get_position_precision()}
+                      {format}
+                      language={self.language}
+                      on_action={ctx.link().callback(DashboardMessage::Portfolio)}
+                      on_base_currency={ctx.link().callback(DashboardMessage::SetBaseCurrency)} />
+              }
+              if self.converter_open {
+                  <CurrencyConverter
+                      graph={self.fx_graph.clone()}
+                      base_currency={self.base_currency.clone()}
+                      {format}
+                      language={self.language} />
+              }
+              <AlertToasts
+                  toasts={self.toasts.clone()}
+                  {format}
+                  language={self.language}
+                  on_dismiss={ctx.link().callback(DashboardMessage::DismissToast)} />
+              if let Some(symbol) = &self.selected_symbol {
+                  <InstrumentDetail
+                      provider={ctx.props().provider.clone()}
+                      symbol={symbol.clone()}
+                      name={self.get_symbol_name(symbol)}
+                      quote={self.reference_data.last_quote.get(symbol).cloned()}
+                      price={self.prices.get(symbol).cloned()}
+                      live_candles={self.candles.last_candles(symbol)}
+                      format={format.for_quote(self.reference_data.last_quote.get(symbol))}
+                      language={self.language}
+                      decimals={self.get_symbol_decimals(symbol)}
+                      on_close={ctx.link().callback(|_| DashboardMessage::CloseDetail)} />
+              }
+              <div class="watchlists">{watchlists}</div>
+            </>
+        }
+    }
+}
+
+/// Number of live candles kept per symbol and interval
+const CANDLE_CAPACITY: usize = 120;
+/// Number of recent prices kept per symbol for the sparklines
+const PRICE_HISTORY_CAPACITY: usize = 120;
+/// Period of the stale quotes and feed delay check, milliseconds
+const FRESHNESS_CHECK_MS: u32 = 1000;
+/// How long a fired alert stays in the page, milliseconds
+const TOAST_DURATION_MS: f64 = 10_000.0;
+/// Number of fired alerts shown in the page at once
+const TOAST_CAPACITY: usize = 5;
+
+impl DashboardComponent {
+    fn create_candle_aggregator(reference_data: &ReferenceData) -> CandleAggregator {
+        let mut candles = CandleAggregator::new(&CandleInterval::ALL, CANDLE_CAPACITY);
+        for (symbol, quote) in &reference_data.last_quote {
+            candles.set_clock(symbol, ExchangeClock::for_quote(quote));
+        }
+        candles
+    }
+    fn create_precision_tracker(reference_data: &ReferenceData) -> PrecisionTracker {
+        let mut precision = PrecisionTracker::default();
+        for (symbol, quote) in &reference_data.last_quote {
+            observe_close(&mut precision, symbol, quote);
+        }
+        precision
+    }
+    /// Updating the market sessions, the stale quotes and the feed delay, true if the view changed.
+    /// Quotes of closed markets are never stale and the feed delay counts only while
+    /// some market is trading. Reference data of the opened markets is reloaded.
+    fn check_freshness(&mut self, ctx: &Context<Self>) -> bool {
+        let config = &ctx.props().config;
+        let now = Date::now();
+        let sessions = market_sessions(&self.watchlists, &self.reference_data, now);
+        let opened: HashSet<String> = sessions
+            .iter()
+            .filter(|(symbol, session)| {
+                **session == MarketSession::Open
+                    && self
+                        .sessions
+                        .get(*symbol)
+                        .is_some_and(|previous| *previous != MarketSession::Open)
+            })
+            .map(|(symbol, _)| symbol.clone())
+            .collect();
+        let trading_resumed = sessions.values().any(MarketSession::is_trading)
+            && !self.sessions.values().any(MarketSession::is_trading);
+        if trading_resumed {
+            self.freshness.feed_live(now);
+        }
+        for symbol in &opened {
+            self.freshness.market_opened(symbol, now);
+        }
+        let sessions_changed = sessions != *self.sessions;
+        if sessions_changed {
+            self.sessions = Rc::new(sessions);
+        }
+        if !opened.is_empty() {
+            info!("Markets opened for {:?}", opened);
+            self.load_reference_data(ctx, opened);
+        }
+
+        let sessions = &self.sessions;
+        let thresholds = self.watchlists.iter().flat_map(|watchlist| {
+            let threshold = config.stale_after_secs.for_quote_type(watchlist.quote_type);
+            watchlist.symbols.iter().map(move |symbol| {
+                let trading = sessions.get(symbol).is_some_and(MarketSession::is_trading);
+                (symbol.as_str(), trading.then_some(threshold))
+            })
+        });
+        let changed = self.freshness.refresh(thresholds, now);
+        let trading = self.sessions.values().any(MarketSession::is_trading);
+        let feed_delay = match self.connection_state {
+            ConnectionState::Live if trading => {
+                self.freshness.feed_delay(now, config.feed_delay_secs)
+            }
+            _ => None,
         };
-        let currencies_props = QuotesProps {
-            title: "Мировые валюты".to_owned(),
-            component_type: QuotesComponentType::BidAsk,
-            quote_type: QuoteType::Currency,
-            symbols: self.currencies_symbols.clone(),
-            prices: self.get_quote_data(QuoteType::Currency),
-            reference_data: self.reference_data.clone(),
+        let delay_changed = feed_delay != self.feed_delay;
+        self.feed_delay = feed_delay;
+        sessions_changed || changed || delay_changed
+    }
+    /// Price, change and spread alerts of the tick
+    fn check_alerts(&mut self, tick: &PriceTick) -> Vec<AlertEvent> {
+        let Some(price) = Price::from_f64(tick.price).value() else {
+            return Vec::new();
         };
-        let indices_stock_props = QuotesProps {
-            title: "Индексы".to_owned(),
-            component_type: QuotesComponentType::OnlyPrice,
-            quote_type: QuoteType::Indices,
-            symbols: self.indices_symbols.clone(),
-            prices: self.get_quote_data(QuoteType::Indices),
-            reference_data: self.reference_data.clone(),
+        // 0 if the provider does not send the bid or ask
+        let quoted = |value: f64| {
+            Price::from_f64(value)
+                .value()
+                .filter(|value| !value.is_zero())
         };
-        let us_stock_props = QuotesProps {
-            title: "Акции".to_owned(),
-            component_type: QuotesComponentType::OnlyPrice,
-            quote_type: QuoteType::USStocks,
-            symbols: self.us_stocks_symbols.clone(),
-            prices: self.get_quote_data(QuoteType::USStocks),
-            reference_data: self.reference_data.clone(),
+        let change_percent = self.find_quote_type(&tick.symbol).and_then(|quote_type| {
+            price_change(
+                self.baseline,
+                quote_type,
+                self.reference_data.last_quote.get(&tick.symbol),
+                Some(Price::new(price)),
+            )
+            .and_then(|change| change.percent)
+        });
+        let input = AlertInput {
+            price,
+            bid: quoted(tick.bid),
+            ask: quoted(tick.ask),
+            change_percent,
         };
-        html! {
-              <div class="row">
-                  <div class="column1">
-                        <div><QuotesComponent ..crypto_currencies_props.clone() /></div>
-                        <div><QuotesComponent ..currencies_props.clone() /></div>
-                  </div>
-                 <div class="column2">
-                        <div><QuotesComponent ..indices_stock_props.clone() /></div>
-                  </div>
-                  <div class="column3">
-                        <div><QuotesComponent ..us_stock_props.clone() /></div>
-                  </div>
-            </div>
+        self.alerts.on_tick(&tick.symbol, input, Date::now())
+    }
+    /// Notifying about the fired alerts and saving the history, true if any fired
+    fn raise_alerts(&mut self, ctx: &Context<Self>, alerts: Vec<AlertEvent>) -> bool {
+        if alerts.is_empty() {
+            return false;
         }
+        let config = &ctx.props().config;
+        let format = config.display_format();
+        let title = self.language.text(Text::Alerts);
+        for alert in alerts {
+            let message = alert_message(&alert, self.language, format);
+            info!("Alert: {}", message);
+            self.notifier.notify(
+                &title,
+                &message,
+                config.alert_notifications,
+                config.alert_sound,
+            );
+            self.toasts.push(alert);
+        }
+        let overflow = self.toasts.len().saturating_sub(TOAST_CAPACITY);
+        self.toasts.drain(..overflow);
+        if let Err(error) = AlertService::save(&self.alerts) {
+            error!("Failed to save alert history: {}", error);
+        }
+        true
     }
-}
+    /// Saving the changed watchlists, updating the subscription and loading the reference data
+    /// of the added symbols
+    fn update_watchlists(&mut self, ctx: &Context<Self>, action: WatchlistAction) -> bool {
+        let old_symbols = WatchlistService::symbols_by_type(&self.watchlists);
+        if !WatchlistService::apply(&mut self.watchlists, action) {
+            return false;
+        }
+        if let Err(error) = WatchlistService::save(&self.watchlists) {
+            error!("Failed to save watchlists: {}", error);
+        }
+        self.update_decimals();
+        let symbols = WatchlistService::symbols_by_type(&self.watchlists);
+        if symbols == old_symbols {
+            return true;
+        }
+        self.supervisor.set_symbols(symbols);
+        let all_symbols = WatchlistService::all_symbols(&self.watchlists);
+        Rc::make_mut(&mut self.failed_symbols).retain(|symbol| all_symbols.contains(symbol));
 
-impl DashboardComponent {
-    fn get_quote_data(&self, quote_type: QuoteType) -> HashMap<String, RealTimePriceData> {
-        let symbols = DashboardConfiguration::get_quote_symbols(quote_type);
-        self.prices
+        let added: HashSet<String> = all_symbols
+            .into_iter()
+            .filter(|symbol| !self.reference_data.last_quote.contains_key(symbol))
+            .collect();
+        if !added.is_empty() {
+            self.load_reference_data(ctx, added);
+        }
+        true
+    }
+    /// Saving the changed positions. The currency of an added position is taken from its quote
+    /// or pair when not entered, and the reference data of a new symbol is loaded.
+    fn update_portfolio(&mut self, ctx: &Context<Self>, action: PortfolioAction) -> bool {
+        let action = match action {
+            PortfolioAction::Add {
+                symbol,
+                quantity,
+                cost_basis,
+                currency,
+            } => {
+                let symbol = symbol.trim().to_uppercase();
+                let quote = self.reference_data.last_quote.get(&symbol);
+                let currency = Some(currency.trim().to_uppercase())
+                    .filter(|currency| !currency.is_empty())
+                    .or_else(|| {
+                        quote
+                            .map(|quote| quote.currency.to_uppercase())
+                            .filter(|currency| !currency.is_empty())
+                    })
+                    .or_else(|| split_pair(&symbol).map(|(_, quote)| quote.to_owned()))
+                    .unwrap_or_else(|| self.base_currency.clone());
+                if quote.is_none() && !symbol.is_empty() {
+                    self.load_reference_data(ctx, HashSet::from([symbol.clone()]));
+                }
+                PortfolioAction::Add {
+                    symbol,
+                    quantity,
+                    cost_basis,
+                    currency,
+                }
+            }
+            action => action,
+        };
+        if !PortfolioService::apply(&mut self.positions, action) {
+            return false;
+        }
+        if let Err(error) = PortfolioService::save(&self.positions) {
+            error!("Failed to save portfolio: {}", error);
+        }
+        self.update_valuation();
+        true
+    }
+    /// Loading the end of day prices and the last quotes of the symbols
+    fn load_reference_data(&self, ctx: &Context<Self>, symbols: HashSet<String>) {
+        let provider = ctx.props().provider.clone();
+        ctx.link().send_future(async move {
+            let (end_of_day, last_quote) = join!(
+                provider.get_end_of_day_data(symbols.clone()),
+                provider.get_last_quote(symbols)
+            );
+            DashboardMessage::ReferenceLoaded {
+                end_of_day,
+                last_quote,
+            }
+        });
+    }
+    fn get_symbol_name(&self, symbol: &str) -> String {
+        let reference_data = &self.reference_data;
+        reference_data
+            .indices
+            .get(symbol)
+            .map(|indices| indices.name.clone())
+            .or_else(|| {
+                reference_data
+                    .us_stocks
+                    .get(symbol)
+                    .map(|stock| stock.name.clone())
+            })
+            .or_else(|| {
+                reference_data
+                    .last_quote
+                    .get(symbol)
+                    .map(|quote| quote.name.clone())
+            })
+            .unwrap_or_else(|| symbol.to_owned())
+    }
+    /// Price decimals of the symbol, guessed from the current or the last price until observed
+    fn get_decimals(&self, symbol: &str, quote_type: QuoteType) -> u32 {
+        let price = self
+            .prices
+            .get(symbol)
+            .map(|price| price.price)
+            .or_else(|| {
+                self.reference_data
+                    .last_quote
+                    .get(symbol)
+                    .and_then(|quote| quote.close.to_f64())
+            });
+        self.precision.decimals(symbol, quote_type, price)
+    }
+    /// Price decimals of the symbol in any watchlist
+    fn get_symbol_decimals(&self, symbol: &str) -> u32 {
+        self.find_quote_type(symbol)
+            .map_or(MIN_DECIMALS, |quote_type| {
+                self.get_decimals(symbol, quote_type)
+            })
+    }
+    /// Quote type of the first watchlist with the symbol
+    fn find_quote_type(&self, symbol: &str) -> Option<QuoteType> {
+        self.watchlists
+            .iter()
+            .find(|watchlist| watchlist.symbols.iter().any(|value| value == symbol))
+            .map(|watchlist| watchlist.quote_type)
+    }
+    /// Price decimals of all watchlist symbols, once per watchlist or reference data change
+    /// rather than for every watchlist on every render
+    fn update_decimals(&mut self) {
+        let decimals = self
+            .watchlists
+            .iter()
+            .flat_map(|watchlist| watchlist.symbols.iter())
+            .map(|symbol| (symbol.clone(), self.get_symbol_decimals(symbol)))
+            .collect();
+        self.decimals = Rc::new(decimals);
+    }
+    /// Symbols of all watchlists in the display order, without repeats
+    fn get_alert_symbols(&self) -> Vec<String> {
+        let mut unique = HashSet::new();
+        self.watchlists
+            .iter()
+            .flat_map(|watchlist| watchlist.symbols.iter())
+            .filter(|symbol| unique.insert(symbol.as_str()))
+            .cloned()
+            .collect()
+    }
+    /// Rebuilding the currency pairs and revaluing the portfolio, once per pair price change
+    /// rather than on every render
+    fn update_fx_graph(&mut self) {
+        self.fx_graph = Rc::new(self.get_fx_graph());
+        self.update_valuation();
+    }
+
+    fn update_valuation(&mut self) {
+        self.valuation = Rc::new(self.get_valuation(&self.fx_graph));
+    }
+    /// Portfolio valued at the live prices, or at the last closes before the first tick
+    fn get_valuation(&self, fx_graph: &FxGraph) -> PortfolioValuation {
+        let prices = |symbol: &str| {
+            let quote = self.reference_data.last_quote.get(symbol);
+            PositionPrices {
+                price: match self.prices.get(symbol) {
+                    Some(price) => Price::from_f64(price.price),
+                    None => quote.map(|quote| quote.close).unwrap_or_default(),
+                },
+                previous_close: quote.map(|quote| quote.previous_close).unwrap_or_default(),
+            }
+        };
+        valuate(&self.positions, &self.base_currency, prices, fx_graph)
+    }
+    /// Currency pairs quoted by the live bid and ask, or by the last close before the first tick
+    fn get_fx_graph(&self) -> FxGraph {
+        let mut graph = FxGraph::default();
+        for (symbol, quote) in &self.reference_data.last_quote {
+            if let Some(quote) = quote
+                .close
+                .value()
+                .and_then(|close| FxQuote::new(close, None, None))
+            {
+                graph.insert(symbol, quote);
+            }
+        }
+        let decimal = |value: f64| Price::from_f64(value).value();
+        for (symbol, price) in self.prices.iter() {
+            if let Some(quote) = decimal(price.price)
+                .and_then(|value| FxQuote::new(value, decimal(price.bid), decimal(price.ask)))
+            {
+                graph.insert(symbol, quote);
+            }
+        }
+        graph
+    }
+    /// Price decimals of the position symbols
+    fn get_position_precision(&self) -> HashMap<String, u32> {
+        self.positions
             .iter()
-            .filter(|(k, _)| symbols.contains(k.as_str()))
-            .map(|(k, v)| (k.clone(), v.clone()))
+            .map(|position| {
+                (
+                    position.symbol.clone(),
+                    self.get_symbol_decimals(&position.symbol),
+                )
+            })
             .collect()
     }
 }
+
+/// Trading session of every watchlist symbol at the browser time in milliseconds
+fn market_sessions(
+    watchlists: &[Watchlist],
+    reference_data: &ReferenceData,
+    now: f64,
+) -> HashMap<String, MarketSession> {
+    let timestamp = (now / 1000.0) as i64;
+    watchlists
+        .iter()
+        .flat_map(|watchlist| {
+            watchlist.symbols.iter().map(|symbol| {
+                let calendar = MarketCalendar::for_quote(
+                    watchlist.quote_type,
+                    reference_data.last_quote.get(symbol),
+                );
+                (symbol.clone(), calendar.session(timestamp))
+            })
+        })
+        .collect()
+}
+
+/// Decimals of the last quote close price
+fn observe_close(precision: &mut PrecisionTracker, symbol: &str, quote: &Quote) {
+    if let Some(close) = quote.close.to_f64() {
+        precision.observe(symbol, close);
+    }
+}
@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use yew::{classes, html, Callback, Component, Context, Html, Properties};
+
+use crate::common::candles::CandleInterval;
+use crate::common::entities::{Bar, Quote, RealTimePriceData};
+use crate::common::enums::{ChartKind, Language, TimeSeriesInterval};
+use crate::common::format::DisplayFormat;
+use crate::common::i18n::Text;
+use crate::common::utils::bar_start;
+use crate::common::MarketResult;
+use crate::components::chart::PriceChart;
+use crate::components::metrics::QuoteMetrics;
+use crate::services::providers::ProviderRef;
+
+/// Number of bars requested and kept on the chart
+const OUTPUT_SIZE: u32 = 120;
+
+/// Instrument Detail Component Properties
+#[derive(Properties, PartialEq, Clone)]
+pub struct InstrumentDetailProps {
+    /// Market data provider of the time series
+    pub provider: ProviderRef,
+    /// Symbol ticker of the instrument
+    pub symbol: String,
+    /// Display name of the instrument
+    pub name: String,
+    /// The last quote of the instrument
+    pub quote: Option<Quote>,
+    /// The latest real-time price of the instrument
+    pub price: Option<RealTimePriceData>,
+    /// The current live candle of the instrument at every interval
+    pub live_candles: HashMap<CandleInterval, Bar>,
+    /// Time and number formatting, resolved for the instrument exchange
+    pub format: DisplayFormat,
+    /// User interface language
+    pub language: Language,
+    /// Price decimals of the instrument
+    pub decimals: u32,
+    /// Closing the panel
+    pub on_close: Callback<()>,
+}
+
+/// Instrument detail drawer: historical bars with the live price appended to the last bar,
+/// and the metrics of the last quote
+pub struct InstrumentDetail {
+    interval: TimeSeriesInterval,
+    kind: ChartKind,
+    bars: Rc<Vec<Bar>>,
+    loading: bool,
+    error: Option<String>,
+}
+
+/// Instrument Detail Component Messages
+pub enum InstrumentDetailMessage {
+    /// Interval selected
+    SetInterval(TimeSeriesInterval),
+    /// Chart type selected
+    SetChartKind(ChartKind),
+    /// Time series loaded
+    Loaded {
+        symbol: String,
+        interval: TimeSeriesInterval,
+        bars: MarketResult<Vec<Bar>>,
+    },
+}
+
+impl Component for InstrumentDetail {
+    type Message = InstrumentDetailMessage;
+    type Properties = InstrumentDetailProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let mut detail = Self {
+            interval: TimeSeriesInterval::default(),
+            kind: ChartKind::default(),
+            bars: Rc::new(Vec::new()),
+            loading: false,
+            error: None,
+        };
+        detail.load(ctx);
+        detail
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            InstrumentDetailMessage::SetInterval(interval) => {
+                if interval == self.interval {
+                    return false;
+                }
+                self.interval = interval;
+                self.load(ctx);
+            }
+            InstrumentDetailMessage::SetChartKind(kind) => {
+                self.kind = kind;
+            }
+            InstrumentDetailMessage::Loaded {
+                symbol,
+                interval,
+                bars,
+            } => {
+                // a response to an outdated request
+                if symbol != ctx.props().symbol || interval != self.interval {
+                    return false;
+                }
+                self.loading = false;
+                match bars {
+                    Ok(bars) => self.bars = Rc::new(bars),
+                    Err(error) => {
+                        log::error!("Failed to load time series: {}", error);
+                        self.error = Some(error.to_string());
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, old_props: &Self::Properties) -> bool {
+        let props = ctx.props();
+        if props.symbol != old_props.symbol {
+            self.load(ctx);
+        } else if let Some(candle) = CandleInterval::from_time_series(self.interval)
+            .and_then(|interval| props.live_candles.get(&interval))
+        {
+            self.merge_candle(candle);
+        } else if let (Some(price), true) = (&props.price, props.price != old_props.price) {
+            self.append_price(price);
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let on_close = props.on_close.reform(|_| ());
+        let interval_buttons = TimeSeriesInterval::ALL
+            .iter()
+            .map(|&interval| {
+                let onclick = ctx
+                    .link()
+                    .callback(move |_| InstrumentDetailMessage::SetInterval(interval));
+                html! {
+                    <button class={classes!((interval == self.interval).then_some("selected"))} {onclick}>
+                        {props.language.text(Text::Interval(interval))}
+                    </button>
+                }
+            })
+            .collect::<Html>();
+        let kind_buttons = [ChartKind::Candles, ChartKind::Line]
+            .into_iter()
+            .map(|kind| {
+                let onclick = ctx
+                    .link()
+                    .callback(move |_| InstrumentDetailMessage::SetChartKind(kind));
+                html! {
+                    <button class={classes!((kind == self.kind).then_some("selected"))} {onclick}>
+                        {props.language.text(Text::ChartKind(kind))}
+                    </button>
+                }
+            })
+            .collect::<Html>();
+
+        html! {
+            <div class="detail-panel">
+                <div class="title">
+                    <div>{format!("{} ({})", props.name, props.symbol)}</div>
+                    <button class="detail-close" onclick={on_close}>{"✕"}</button>
+                </div>
+                <div class="detail-toolbar">
+                    <div>{interval_buttons}</div>
+                    <div>{kind_buttons}</div>
+                </div>
+                if self.loading {
+                    <div class="detail-message">{props.language.text(Text::Loading)}</div>
+                }
+                if let Some(error) = &self.error {
+                    <div class="detail-message color-red">{error}</div>
+                }
+                <PriceChart bars={self.bars.clone()} kind={self.kind} format={props.format} decimals={props.decimals} />
+                if let Some(bar) = self.bars.last() {
+                    <div class="detail-summary">
+                        <span>{props.format.date_time(bar.time)}</span>
+                        {
+                            [(Text::BarOpen, bar.open), (Text::BarHigh, bar.high), (Text::BarLow, bar.low), (Text::BarClose, bar.close)]
+                                .into_iter()
+                                .map(|(label, price)| html! {
+                                    <span>{format!("{} {}", props.language.text(label), props.format.number(price, props.decimals))}</span>
+                                })
+                                .collect::<Html>()
+                        }
+                    </div>
+                }
+                if let Some(quote) = &props.quote {
+                    <QuoteMetrics quote={quote.clone()} price={props.price.clone()} format={props.format}
+                                  language={props.language} decimals={props.decimals} />
+                }
+            </div>
+        }
+    }
+}
+
+impl InstrumentDetail {
+    fn load(&mut self, ctx: &Context<Self>) {
+        self.loading = true;
+        self.error = None;
+        self.bars = Rc::new(Vec::new());
+        let provider = ctx.props().provider.clone();
+        let symbol = ctx.props().symbol.clone();
+        let interval = self.interval;
+        ctx.link().send_future(async move {
+            let bars = provider
+                .get_time_series(symbol.clone(), interval, OUTPUT_SIZE)
+                .await
+                .and_then(|time_series| time_series.bars());
+            InstrumentDetailMessage::Loaded {
+                symbol,
+                interval,
+                bars,
+            }
+        });
+    }
+    /// Merging the live candle of the same interval into the last bar or appending it
+    fn merge_candle(&mut self, candle: &Bar) {
+        if self.bars.is_empty() {
+            return;
+        }
+        let bars = Rc::make_mut(&mut self.bars);
+        match bars.last_mut() {
+            // the live candle covers only the part of the bar since the page was loaded
+            Some(bar) if bar.time == candle.time => {
+                bar.high = bar.high.max(candle.high);
+                bar.low = bar.low.min(candle.low);
+                bar.close = candle.close;
+                bar.volume = bar.volume.max(candle.volume);
+            }
+            Some(bar) if bar.time > candle.time => {}
+            _ => Self::push_bar(bars, *candle),
+        }
+    }
+    /// Updating the last bar with the live price or opening a new bar
+    fn append_price(&mut self, price: &RealTimePriceData) {
+        if self.bars.is_empty() || price.timestamp == 0 {
+            return;
+        }
+        let start = bar_start(self.interval, price.timestamp);
+        let bars = Rc::make_mut(&mut self.bars);
+        match bars.last_mut() {
+            Some(bar) if bar.time == start => {
+                bar.high = bar.high.max(price.price);
+                bar.low = bar.low.min(price.price);
+                bar.close = price.price;
+            }
+            // the price is older than the last bar
+            Some(bar) if bar.time > start => {}
+            _ => Self::push_bar(
+                bars,
+                Bar {
+                    time: start,
+                    open: price.price,
+                    high: price.price,
+                    low: price.price,
+                    close: price.price,
+                    volume: 0.0,
+                },
+            ),
+        }
+    }
+
+    fn push_bar(bars: &mut Vec<Bar>, bar: Bar) {
+        bars.push(bar);
+        if bars.len() > OUTPUT_SIZE as usize {
+            bars.remove(0);
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use web_sys::HtmlSelectElement;
+use yew::events::{Event, TargetCast};
+use yew::{function_component, html, Callback, Html, Properties};
+
+use crate::common::enums::Language;
+use crate::common::i18n::Text;
+
+/// Language Switcher Properties
+#[derive(Properties, PartialEq, Clone)]
+pub struct LanguageSwitcherProps {
+    /// Current user interface language
+    pub language: Language,
+    /// Language selected
+    pub on_change: Callback<Language>,
+}
+
+#[function_component]
+pub fn LanguageSwitcher(props: &LanguageSwitcherProps) -> Html {
+    let on_change = {
+        let on_change = props.on_change.clone();
+        Callback::from(move |event: Event| {
+            let select: HtmlSelectElement = event.target_unchecked_into();
+            if let Some(&language) = usize::try_from(select.selected_index())
+                .ok()
+                .and_then(|index| Language::ALL.get(index))
+            {
+                on_change.emit(language);
+            }
+        })
+    };
+
+    html! {
+        <select class="language-switcher" title={props.language.text(Text::Language)} onchange={on_change}>
+        {
+            Language::ALL.iter().map(|&value| html! {
+                <option selected={value == props.language}>{value.native_name()}</option>
+            }).collect::<Html>()
+        }
+        </select>
+    }
+}
@@ -0,0 +1,181 @@
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use yew::{classes, function_component, html, Html, Properties};
+
+use crate::common::entities::{Quote, RealTimePriceData};
+use crate::common::enums::Language;
+use crate::common::format::{DisplayFormat, MISSING};
+use crate::common::i18n::Text;
+use crate::common::precision::PERCENT_DECIMALS;
+use crate::common::price::Price;
+
+/// Quote Metrics Component Properties
+#[derive(Properties, PartialEq, Clone)]
+pub struct QuoteMetricsProps {
+    /// The last quote of the instrument
+    pub quote: Quote,
+    /// The latest real-time price of the instrument
+    pub price: Option<RealTimePriceData>,
+    /// Time and number formatting, resolved for the instrument exchange
+    pub format: DisplayFormat,
+    /// User interface language
+    pub language: Language,
+    /// Price decimals of the instrument
+    pub decimals: u32,
+}
+
+/// Session, 52-week, volume and extended hours metrics of the last quote. The day range,
+/// the range positions and the volume follow the live ticks.
+#[function_component]
+pub fn QuoteMetrics(props: &QuoteMetricsProps) -> Html {
+    let quote = &props.quote;
+    let format = props.format;
+    let text = |key| props.language.text(key);
+    let price = |price: Price| format.price(price, props.decimals);
+    let percent = |value: Price| match value.value() {
+        Some(value) => format!("{}%", format.change(Some(value), PERCENT_DECIMALS)),
+        None => MISSING.to_owned(),
+    };
+
+    let live_price = props
+        .price
+        .as_ref()
+        .and_then(|price_data| Price::from_f64(price_data.price).value());
+    let last = live_price.or(quote.close.value());
+    // the quote range is as of the last REST request, the live price may be outside it
+    let day_low = extreme(quote.low.value(), live_price, Decimal::min);
+    let day_high = extreme(quote.high.value(), live_price, Decimal::max);
+    let year_low = extreme(quote.fifty_two_week.low.value(), live_price, Decimal::min);
+    let year_high = extreme(quote.fifty_two_week.high.value(), live_price, Decimal::max);
+
+    let live_volume = props
+        .price
+        .as_ref()
+        .map(|price_data| price_data.day_volume)
+        .filter(|&volume| volume > 0.0);
+    let volume = live_volume.or_else(|| parse_volume(&quote.volume));
+    let average_volume = parse_volume(&quote.average_volume);
+    let extended_time = quote.extended_timestamp.trim().parse::<i64>().ok();
+
+    let range = |label: Text, low: Option<Decimal>, high: Option<Decimal>| {
+        html! {
+            <div class="metric-range">
+              <div class="metric-label">{text(label)}</div>
+              <div class="range-bar">
+                <span>{format.decimal(low, props.decimals)}</span>
+                <div class="range-track">
+                  if let Some(position) = range_position(low, high, last) {
+                    <div class="range-marker" style={format!("left: {position:.1}%")}></div>
+                  }
+                </div>
+                <span>{format.decimal(high, props.decimals)}</span>
+              </div>
+            </div>
+        }
+    };
+    let row = |label: Text, value: String| {
+        html! {
+            <div class="metric-row">
+              <span class="metric-label">{text(label)}</span>
+              <span>{value}</span>
+            </div>
+        }
+    };
+
+    html! {
+        <div class="quote-metrics">
+          {row(Text::DayOpen, price(quote.open))}
+          {row(Text::PreviousClose, price(quote.previous_close))}
+          {range(Text::DayRange, day_low, day_high)}
+          {range(Text::FiftyTwoWeekRange, year_low, year_high)}
+          {row(Text::FromLow, percent(quote.fifty_two_week.low_change_percent))}
+          {row(Text::FromHigh, percent(quote.fifty_two_week.high_change_percent))}
+          <div class="metric-range">
+            <div class="metric-label">
+              {format!("{} / {}", text(Text::Volume), text(Text::AverageVolume))}
+            </div>
+            <div class="range-bar">
+              <span>{volume.map_or_else(|| MISSING.to_owned(), |volume| format.number(volume, 0))}</span>
+              <div class="range-track">
+                if let Some((filled, average)) = volume_scale(volume, average_volume) {
+                  <div class="volume-fill" style={format!("width: {filled:.1}%")}></div>
+                  <div class="range-marker" style={format!("left: {average:.1}%")}></div>
+                }
+              </div>
+              <span>{average_volume.map_or_else(|| MISSING.to_owned(), |volume| format.number(volume, 0))}</span>
+            </div>
+          </div>
+          if quote.extended_price.value().is_some() {
+            <div class="metric-row">
+              <span class="metric-label">{text(Text::ExtendedHours)}</span>
+              <span>
+                {price(quote.extended_price)}
+                <span class={classes!("metric-change", change_color(quote.extended_change))}>
+                  {format!(" {} ({})", format.change(quote.extended_change.value(), props.decimals),
+                           percent(quote.extended_percent_change))}
+                </span>
+                if let Some(timestamp) = extended_time {
+                  <span class="metric-time">{format!(" {}", format.date_time(timestamp))}</span>
+                }
+              </span>
+            </div>
+          }
+          {
+              [
+                  (Text::Rolling1d, quote.rolling_1d_change),
+                  (Text::Rolling7d, quote.rolling_7d_change),
+                  (Text::RollingPeriod, quote.rolling_period_change),
+              ]
+              .into_iter()
+              .filter(|(_, change)| change.value().is_some())
+              .map(|(label, change)| row(label, percent(change)))
+              .collect::<Html>()
+          }
+        </div>
+    }
+}
+
+/// The lower or higher of the quote value and the live price
+fn extreme(
+    value: Option<Decimal>,
+    live_price: Option<Decimal>,
+    pick: fn(Decimal, Decimal) -> Decimal,
+) -> Option<Decimal> {
+    match (value, live_price) {
+        (Some(value), Some(live_price)) => Some(pick(value, live_price)),
+        (value, live_price) => value.or(live_price),
+    }
+}
+
+/// Position of the price in the range, percent of the range width
+fn range_position(
+    low: Option<Decimal>,
+    high: Option<Decimal>,
+    price: Option<Decimal>,
+) -> Option<f64> {
+    let (low, high, price) = (low?.to_f64()?, high?.to_f64()?, price?.to_f64()?);
+    if high <= low {
+        return None;
+    }
+    Some(((price - low) / (high - low) * 100.0).clamp(0.0, 100.0))
+}
+
+/// Widths of the day volume bar and of the average volume mark, percent of the larger one
+fn volume_scale(volume: Option<f64>, average_volume: Option<f64>) -> Option<(f64, f64)> {
+    let (volume, average_volume) = (volume?, average_volume?);
+    let scale = volume.max(average_volume);
+    (scale > 0.0).then(|| (volume / scale * 100.0, average_volume / scale * 100.0))
+}
+
+/// Volumes come as decimal strings, empty if unknown
+fn parse_volume(text: &str) -> Option<f64> {
+    Price::parse(text).to_f64()
+}
+
+fn change_color(change: Price) -> Option<&'static str> {
+    match change.value() {
+        Some(change) if change.is_sign_positive() && !change.is_zero() => Some("color-green"),
+        Some(change) if change.is_sign_negative() && !change.is_zero() => Some("color-red"),
+        _ => None,
+    }
+}
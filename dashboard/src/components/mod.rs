@@ -1,6 +1,32 @@
+/// Alert Rules and Notifications Component
+pub mod alerts;
+/// Change Baseline Switcher Component
+pub mod baseline;
+/// Price Chart Component
+pub mod chart;
+/// Currency Converter Component
+pub mod converter;
 /// Dashboard Component
 pub mod dashboard;
+/// Instrument Detail Component
+pub mod detail;
+/// Language Switcher Component
+pub mod language;
+/// Quote Metrics Component
+pub mod metrics;
+/// Portfolio Component
+pub mod portfolio;
 /// Quote Component
 pub mod quotes;
+/// Session Recorder Component
+pub mod recorder;
+/// Symbol Search Component
+pub mod search;
+/// Sparkline Component
+pub mod sparkline;
+/// Connection Status Component
+pub mod status;
 /// Suspense Component
 pub mod suspense;
+/// Watchlist Editor Component
+pub mod watchlists;
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use rust_decimal::Decimal;
+use web_sys::HtmlInputElement;
+use yew::events::{Event, TargetCast};
+use yew::{
+    classes, function_component, html, use_state, Callback, Html, Properties, UseStateHandle,
+};
+
+use crate::common::enums::Language;
+use crate::common::format::DisplayFormat;
+use crate::common::i18n::Text;
+use crate::common::portfolio::PortfolioValuation;
+use crate::common::precision::{MIN_DECIMALS, PERCENT_DECIMALS};
+use crate::common::price::Price;
+use crate::services::portfolio::PortfolioAction;
+
+/// Portfolio Panel Properties
+#[derive(Properties, PartialEq, Clone)]
+pub struct PortfolioPanelProps {
+    /// Positions valued at the current prices
+    pub valuation: Rc<PortfolioValuation>,
+    /// Price decimals by symbol
+    pub precision: HashMap<String, u32>,
+    /// Time and number formatting
+    pub format: DisplayFormat,
+    /// User interface language
+    pub language: Language,
+    /// Position change, an empty currency is taken from the quote
+    pub on_action: Callback<PortfolioAction>,
+    /// Base currency change
+    pub on_base_currency: Callback<String>,
+}
+
+/// Entering positions, their live market value, P&L and allocation in the base currency
+#[function_component]
+pub fn PortfolioPanel(props: &PortfolioPanelProps) -> Html {
+    let symbol = use_state(String::new);
+    let quantity = use_state(String::new);
+    let cost_basis = use_state(String::new);
+    let currency = use_state(String::new);
+    let invalid = use_state(|| false);
+    let language = props.language;
+    let format = props.format;
+    let valuation = &props.valuation;
+    let text = |key| language.text(key);
+    let money = |value: Option<Decimal>| format.decimal(value, MIN_DECIMALS);
+    let pnl = |value: Option<Decimal>| {
+        html! {
+            <span class={classes!(pnl_color(value))}>{format.change(value, MIN_DECIMALS)}</span>
+        }
+    };
+    let percent = |value: Option<Decimal>| match value {
+        Some(_) => format!("{}%", format.decimal(value, PERCENT_DECIMALS)),
+        None => format.decimal(None, PERCENT_DECIMALS),
+    };
+
+    let on_add = {
+        let on_action = props.on_action.clone();
+        let (symbol, quantity, cost_basis, currency, invalid) = (
+            symbol.clone(),
+            quantity.clone(),
+            cost_basis.clone(),
+            currency.clone(),
+            invalid.clone(),
+        );
+        Callback::from(move |_| {
+            let parsed = (
+                Price::parse(&quantity)
+                    .value()
+                    .filter(|value| !value.is_zero()),
+                Price::parse(&cost_basis)
+                    .value()
+                    .filter(|value| !value.is_sign_negative()),
+            );
+            let (false, (Some(quantity_value), Some(cost_value))) =
+                (symbol.trim().is_empty(), parsed)
+            else {
+                invalid.set(true);
+                return;
+            };
+            invalid.set(false);
+            on_action.emit(PortfolioAction::Add {
+                symbol: (*symbol).clone(),
+                quantity: quantity_value,
+                cost_basis: cost_value,
+                currency: (*currency).clone(),
+            });
+            for field in [&symbol, &quantity, &cost_basis, &currency] {
+                field.set(String::new());
+            }
+        })
+    };
+    let on_base_currency = props.on_base_currency.reform(|event: Event| {
+        let input: HtmlInputElement = event.target_unchecked_into();
+        input.value()
+    });
+
+    html! {
+        <div class="portfolio-panel">
+            <div class="portfolio-create">
+                <input placeholder={text(Text::ColumnInstrument)} value={(*symbol).clone()}
+                       onchange={input_setter(&symbol)} />
+                <input class="portfolio-number" placeholder={text(Text::Quantity)} value={(*quantity).clone()}
+                       onchange={input_setter(&quantity)} />
+                <input class="portfolio-number" placeholder={text(Text::CostBasis)} value={(*cost_basis).clone()}
+                       onchange={input_setter(&cost_basis)} />
+                <input class="portfolio-currency" placeholder={text(Text::CurrencyAuto)} value={(*currency).clone()}
+                       onchange={input_setter(&currency)} />
+                <button onclick={on_add}>{text(Text::AddPosition)}</button>
+                if *invalid {
+                    <span class="color-red">{text(Text::InvalidPosition)}</span>
+                }
+                <label class="portfolio-base">
+                    {text(Text::BaseCurrency)}{" "}
+                    <input class="portfolio-currency" value={valuation.base_currency.clone()}
+                           onchange={on_base_currency} />
+                </label>
+            </div>
+            <table class="portfolio-table">
+                <thead>
+                    <tr>
+                        <th>{text(Text::ColumnInstrument)}</th>
+                        <th>{text(Text::Quantity)}</th>
+                        <th>{text(Text::CostBasis)}</th>
+                        <th>{text(Text::ColumnPrice)}</th>
+                        <th>{text(Text::MarketValue)}</th>
+                        <th>{text(Text::UnrealizedPnl)}</th>
+                        <th>{text(Text::DayPnl)}</th>
+                        <th>{format!("{}, {}", text(Text::MarketValue), valuation.base_currency)}</th>
+                        <th>{text(Text::Allocation)}</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                if valuation.positions.is_empty() {
+                    <tr><td class="portfolio-empty" colspan="10">{text(Text::NoPositions)}</td></tr>
+                }
+                {
+                    valuation.positions.iter().map(|value| {
+                        let position = &value.position;
+                        let decimals = props.precision.get(&position.symbol).copied().unwrap_or(MIN_DECIMALS);
+                        let id = position.id;
+                        let on_remove = props.on_action.reform(move |_| PortfolioAction::Remove { id });
+                        html! {
+                            <tr>
+                                <td>{&position.symbol}</td>
+                                <td>{format.decimal(Some(position.quantity), position.quantity.normalize().scale())}</td>
+                                <td>{format!("{} {}", format.decimal(Some(position.cost_basis), decimals), position.currency)}</td>
+                                <td>{format.decimal(value.price, decimals)}</td>
+                                <td>{money(value.market_value)}</td>
+                                <td>
+                                    {pnl(value.unrealized)}
+                                    <span class="portfolio-percent">{format!(" ({})", percent(value.unrealized_percent))}</span>
+                                </td>
+                                <td>{pnl(value.day_pnl)}</td>
+                                <td>{money(value.base_market_value)}</td>
+                                <td>{percent(value.allocation)}</td>
+                                <td><button title={text(Text::Delete)} onclick={on_remove}>{"✕"}</button></td>
+                            </tr>
+                        }
+                    }).collect::<Html>()
+                }
+                </tbody>
+                <tfoot>
+                    <tr>
+                        <td colspan="4">{format!("{}, {}", text(Text::Total), valuation.base_currency)}</td>
+                        <td>{money(Some(valuation.market_value))}</td>
+                        <td>{pnl(Some(valuation.unrealized))}</td>
+                        <td>{pnl(Some(valuation.day_pnl))}</td>
+                        <td colspan="3"></td>
+                    </tr>
+                </tfoot>
+            </table>
+            if valuation.incomplete {
+                <div class="portfolio-note">{text(Text::IncompleteTotal)}</div>
+            }
+        </div>
+    }
+}
+
+fn pnl_color(value: Option<Decimal>) -> Option<&'static str> {
+    match value {
+        Some(value) if value.is_zero() => None,
+        Some(value) if value.is_sign_positive() => Some("color-green"),
+        Some(_) => Some("color-red"),
+        None => None,
+    }
+}
+
+fn input_setter(state: &UseStateHandle<String>) -> Callback<Event> {
+    let state = state.clone();
+    Callback::from(move |event: Event| {
+        let input: HtmlInputElement = event.target_unchecked_into();
+        state.set(input.value());
+    })
+}
@@ -1,12 +1,30 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::rc::Rc;
 use std::sync::Arc;
 
-use linked_hash_set::LinkedHashSet;
-use yew::{classes, function_component, html, Html, Properties};
+use js_sys::Date;
+use rust_decimal::Decimal;
+use web_sys::HtmlInputElement;
+use yew::events::{InputEvent, MouseEvent, TargetCast};
+use yew::{classes, function_component, html, use_mut_ref, use_state, Callback, Html, Properties};
 
+use crate::common::change::{change_base, price_change};
 use crate::common::entities::{RealTimePriceData, ReferenceData};
-use crate::common::enums::{QuoteType, QuotesComponentType};
-use crate::common::utils::{format_time, round_f64, round_f64_str};
+use crate::common::enums::{
+    ChangeBaseline, Language, MarketSession, PriceMove, QuoteSort, QuoteType, QuotesComponentType,
+    SortColumn,
+};
+use crate::common::format::{DisplayFormat, MISSING};
+use crate::common::freshness::QuoteFreshness;
+use crate::common::i18n::Text;
+use crate::common::precision::{MIN_DECIMALS, PERCENT_DECIMALS};
+use crate::common::price::{Price, PriceChange};
+use crate::components::search::SymbolSearch;
+use crate::components::sparkline::Sparkline;
+use crate::services::providers::ProviderRef;
+
+/// Shortest interval between two sorts of the rows by the moving prices
+const RESORT_INTERVAL_MS: f64 = 2000.0;
 
 /// Quotes Component Properties
 #[derive(Properties, PartialEq, Clone)]
@@ -18,51 +36,205 @@ pub struct QuotesProps {
     /// Type of quote
     pub quote_type: QuoteType,
     /// List of symbols
-    pub symbols: Arc<LinkedHashSet<String>>,
+    pub symbols: Vec<String>,
     /// Real Time prices
-    pub prices: HashMap<String, RealTimePriceData>,
+    pub prices: Rc<HashMap<String, RealTimePriceData>>,
+    /// Recent prices, the oldest first
+    pub price_history: Rc<HashMap<String, VecDeque<f64>>>,
     /// Reference Data
     pub reference_data: Arc<ReferenceData>,
+    /// Symbol of the instrument shown in the detail panel
+    pub selected: Option<String>,
+    /// Instrument row click
+    pub on_select: Callback<String>,
+    /// Symbols rejected by the price feed subscription
+    pub failed: Rc<HashSet<String>>,
+    /// Last update state by symbol
+    pub freshness: Rc<HashMap<String, QuoteFreshness>>,
+    /// Time and number formatting
+    pub format: DisplayFormat,
+    /// User interface language
+    pub language: Language,
+    /// Price decimals by symbol
+    pub precision: Rc<HashMap<String, u32>>,
+    /// Reference price of the changes
+    pub baseline: ChangeBaseline,
+    /// Current trading session by symbol
+    pub sessions: Rc<HashMap<String, MarketSession>>,
+    /// Row order of the block
+    pub sort: QuoteSort,
+    /// Row order change
+    pub on_sort: Callback<QuoteSort>,
+    /// Watchlist editing mode: symbols can be added and removed
+    pub editing: bool,
+    /// Market data provider of the symbol search
+    pub provider: ProviderRef,
+    /// Adding a symbol to the watchlist
+    pub on_add_symbol: Callback<String>,
+    /// Removing a symbol from the watchlist
+    pub on_remove_symbol: Callback<String>,
 }
 
 #[function_component]
 pub fn QuotesComponent(props: &QuotesProps) -> Html {
     let css_props = get_css_props(props);
+    let text = |key| props.language.text(key);
+    let filter = use_state(String::new);
+    let sorted = use_mut_ref(|| None::<SortedRows>);
+    let order = {
+        let mut sorted = sorted.borrow_mut();
+        let key = SortedKey {
+            sort: props.sort,
+            baseline: props.baseline,
+            symbols: props.symbols.clone(),
+            priced: priced_symbols(props),
+        };
+        let now = Date::now();
+        match &*sorted {
+            Some(rows) if rows.key == key && !resort_due(props.sort, rows.sorted_at, now) => {
+                rows.order.clone()
+            }
+            _ => {
+                let order = sort_symbols(props);
+                *sorted = Some(SortedRows {
+                    key,
+                    sorted_at: now,
+                    order: order.clone(),
+                });
+                order
+            }
+        }
+    };
+    let filter_text = filter.trim().to_lowercase();
+    let on_filter = {
+        let filter = filter.clone();
+        Callback::from(move |event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            filter.set(input.value());
+        })
+    };
+    let on_top_movers = {
+        let on_sort = props.on_sort.clone();
+        let sort = props.sort;
+        Callback::from(move |_| {
+            on_sort.emit(if sort == QuoteSort::TopMovers {
+                QuoteSort::Manual
+            } else {
+                QuoteSort::TopMovers
+            })
+        })
+    };
+    let header = |column: SortColumn, class: &'static str, key: Text| {
+        let arrow = match props.sort {
+            QuoteSort::Column {
+                column: current,
+                descending,
+            } if current == column => Some(if descending { "▼" } else { "▲" }),
+            _ => None,
+        };
+        let on_click = {
+            let on_sort = props.on_sort.clone();
+            let sort = props.sort;
+            Callback::from(move |_| on_sort.emit(sort.toggle(column)))
+        };
+        html! {
+            <div class={classes!("col", class, "sortable")} onclick={on_click}>
+              {text(key)}
+              if let Some(arrow) = arrow {
+                <span class="sort-arrow">{arrow}</span>
+              }
+            </div>
+        }
+    };
 
     html! {
            <div class={classes!(css_props.container_classes)}>
              <ul class="responsive-table">
                <li class={classes!(css_props.title_classes)}>
                  <div>{props.title.clone()}</div>
+                 <div class="quote-tools">
+                   <input class="quote-filter" type="search" placeholder={text(Text::Filter)}
+                          value={(*filter).clone()} oninput={on_filter} />
+                   <button class={classes!("top-movers", (props.sort == QuoteSort::TopMovers).then_some("active"))}
+                           onclick={on_top_movers}>{text(Text::TopMovers)}</button>
+                 </div>
+                 if props.editing {
+                   <SymbolSearch provider={props.provider.clone()} on_select={props.on_add_symbol.clone()}
+                                 language={props.language} />
+                 }
                </li>
                <li class="table-header">
-                 <div class="col col-1">{"Инструмент"}</div>
-                 <div class="col col-2">{"Цена"}</div>
+                 {header(SortColumn::Instrument, "col-1", Text::ColumnInstrument)}
+                 {header(SortColumn::Price, "col-2", Text::ColumnPrice)}
                     if props.component_type == QuotesComponentType::BidAsk {
-                       <div class="col col-3">{"Покупка"}</div>
-                       <div class="col col-4">{"Продажа"}</div>
+                       <div class="col col-3">{text(Text::ColumnBid)}</div>
+                       <div class="col col-4">{text(Text::ColumnAsk)}</div>
                     }
-                 <div class="col col-5">{"Изм."}</div>
-                 <div class="col col-6">{"Изм. %"}</div>
-                 <div class="col col-7">{"Время"}</div>
+                 {header(SortColumn::Change, "col-5", Text::ColumnChange)}
+                 {header(SortColumn::ChangePercent, "col-6", Text::ColumnChangePercent)}
+                 <div class="col col-8">{text(Text::ColumnChart)}</div>
+                 {header(SortColumn::Time, "col-7", Text::ColumnTime)}
                </li>
            {
-               props.symbols.iter().map(|symbol| {
+               order.iter().filter(|symbol| matches_filter(symbol, &filter_text, props)).map(|symbol| {
                    let symbol_display = get_symbol_name(symbol, &props.quote_type, &props.reference_data);
                    let mut price_data = DisplayPriceData::default();
                    fill_last_quote(&mut price_data, symbol, props);
                    fill_current_quote(&mut price_data, symbol, props);
+                   fill_change(&mut price_data, symbol, props);
+                   let selected = props.selected.as_ref() == Some(symbol);
+                   let freshness = props.freshness.get(symbol).copied().unwrap_or_default();
+                   let flash = match (freshness.ticks, freshness.last_move) {
+                       (0, _) => None,
+                       (_, PriceMove::Up) => Some("flash-up"),
+                       (_, PriceMove::Down) => Some("flash-down"),
+                       (_, PriceMove::Unchanged) => Some("flash"),
+                   };
+                   let on_select = {
+                       let on_select = props.on_select.clone();
+                       let symbol = symbol.clone();
+                       Callback::from(move |_| on_select.emit(symbol.clone()))
+                   };
+                   let on_remove = {
+                       let on_remove_symbol = props.on_remove_symbol.clone();
+                       let symbol = symbol.clone();
+                       Callback::from(move |event: MouseEvent| {
+                           // the row click opens the detail panel
+                           event.stop_propagation();
+                           on_remove_symbol.emit(symbol.clone())
+                       })
+                   };
                    html!{
-                       <li class="table-row">
-                         <div class="col col-1" data-label="Инструмент">{symbol_display}</div>
-                         <div class="col col-2" data-label="Цена">{price_data.price_value}</div>
+                       // keyed rows move with their symbol when the order changes
+                       <li key={symbol.clone()} class={classes!("table-row", selected.then_some("selected"), freshness.stale.then_some("stale"))}
+                           onclick={on_select}>
+                         <div class="col col-1" data-label={text(Text::ColumnInstrument)}>
+                           if props.editing {
+                             <button class="symbol-remove" title={text(Text::Delete)} onclick={on_remove}>{"✕"}</button>
+                           }
+                           {symbol_display}
+                           if let Some(session) = props.sessions.get(symbol) {
+                             <span class={classes!("market-session", session_class(*session))}>{text(Text::MarketSession(*session))}</span>
+                           }
+                           if props.failed.contains(symbol) {
+                             <span class="symbol-failed" title={text(Text::SymbolFailedHint)}>{text(Text::SymbolFailed)}</span>
+                           }
+                         </div>
+                         <div class="col col-2" data-label={text(Text::ColumnPrice)}>
+                           // the key recreates the span, so the animation restarts on every tick
+                           <span key={freshness.ticks} class={classes!(flash)}>{price_data.price_value}</span>
+                         </div>
                           if props.component_type == QuotesComponentType::BidAsk {
-                            <div class="col col-3" data-label="Покупка">{price_data.bid_value}</div>
-                            <div class="col col-4" data-label="Продажа">{price_data.ask_value}</div>
+                            <div class="col col-3" data-label={text(Text::ColumnBid)}>{price_data.bid_value}</div>
+                            <div class="col col-4" data-label={text(Text::ColumnAsk)}>{price_data.ask_value}</div>
                           }
-                         <div class={classes!(price_data.change_classes)} data-label="Изм.">{price_data.change_value}</div>
-                         <div class={classes!(price_data.change_percent_classes)} data-label="Изм. %">{price_data.percentage_value}</div>
-                         <div class="col col-7" data-label="Время">{price_data.time_value}</div>
+                         <div class={classes!(price_data.change_classes)} data-label={text(Text::ColumnChange)}>{price_data.change_value}</div>
+                         <div class={classes!(price_data.change_percent_classes)} data-label={text(Text::ColumnChangePercent)}>{price_data.percentage_value}</div>
+                         <div class="col col-8" data-label={text(Text::ColumnChart)}>
+                           <Sparkline points={props.price_history.get(symbol).map(|history| Vec::from(history.clone())).unwrap_or_default()}
+                                      baseline={get_change_base(symbol, props)} />
+                         </div>
+                         <div class="col col-7" data-label={text(Text::ColumnTime)}>{price_data.time_value}</div>
                        </li>
                    }
                }).collect::<Html>()
@@ -89,81 +261,215 @@ fn fill_current_quote(
     symbol: &String,
     props: &QuotesProps,
 ) {
-    let eod_price = get_eod_price(symbol, props);
     if let Some(price_data) = props.prices.get(symbol).cloned() {
-        display_price_data.price_value = round_f64(price_data.price).to_string();
-        display_price_data.time_value.clone_from(&price_data.time);
+        let format = props
+            .format
+            .for_quote(props.reference_data.last_quote.get(symbol));
+        let decimals = get_decimals(symbol, props);
+        let price = Price::from_f64(price_data.price);
+        display_price_data.price_value = format.price(price, decimals);
+        display_price_data.time_value = format.time(price_data.timestamp);
 
         if price_data.bid == 0.00 {
-            display_price_data.bid_value = round_f64(price_data.price).to_string();
+            display_price_data.bid_value = format.price(price, decimals);
         } else {
-            display_price_data.bid_value = round_f64(price_data.bid).to_string();
+            display_price_data.bid_value = format.price(Price::from_f64(price_data.bid), decimals);
         }
         if price_data.ask == 0.00 {
-            display_price_data.ask_value = round_f64(price_data.price).to_string();
+            display_price_data.ask_value = format.price(price, decimals);
         } else {
-            display_price_data.ask_value = round_f64(price_data.ask).to_string();
-        }
-        if let Some(eod_price) = eod_price {
-            let change = price_data.price - eod_price;
-            if change == 0.00 {
-                "0.00".clone_into(&mut display_price_data.change_value);
-                "0.00".clone_into(&mut display_price_data.percentage_value);
-            } else if change > 0.00 {
-                display_price_data.change_value = format!("+{}", round_f64(change));
-                let perc = (change / eod_price) * 100.00;
-                display_price_data.percentage_value = format!("+{}", round_f64(perc));
-                display_price_data.change_classes.push("color-green");
-                display_price_data
-                    .change_percent_classes
-                    .push("color-green");
-            } else if change < 0.00 {
-                display_price_data.change_value = format!("{}", round_f64(change));
-                let perc = (change / eod_price) * 100.00;
-                display_price_data.percentage_value = format!("{}", round_f64(perc));
-                display_price_data.change_classes.push("color-red");
-                display_price_data.change_percent_classes.push("color-red");
-            }
+            display_price_data.ask_value = format.price(Price::from_f64(price_data.ask), decimals);
         }
     }
 }
 
 fn fill_last_quote(price_data: &mut DisplayPriceData, symbol: &String, props: &QuotesProps) {
+    for value in [
+        &mut price_data.price_value,
+        &mut price_data.bid_value,
+        &mut price_data.ask_value,
+    ] {
+        MISSING.clone_into(value);
+    }
+    if let Some(last_quote) = props.reference_data.last_quote.get(symbol) {
+        let format = props.format.for_quote(Some(last_quote));
+        let decimals = get_decimals(symbol, props);
+        price_data.price_value = format.price(last_quote.close, decimals);
+        price_data.bid_value = format.price(last_quote.close, decimals);
+        price_data.ask_value = format.price(last_quote.close, decimals);
+        price_data.time_value = format.time(last_quote.timestamp);
+    }
+}
+
+/// Change of the live price, or of the last quote before the first tick, against the baseline
+fn fill_change(price_data: &mut DisplayPriceData, symbol: &String, props: &QuotesProps) {
     let mut change_classes = vec!["col", "col-5"];
     let mut change_percent_classes = vec!["col", "col-6"];
-    if let Some(last_quote) = props.reference_data.last_quote.get(symbol) {
-        price_data.price_value = round_f64_str(&last_quote.close).to_string();
-        price_data.bid_value = round_f64_str(&last_quote.close).to_string();
-        price_data.ask_value = round_f64_str(&last_quote.close).to_string();
-        price_data.time_value = format_time(last_quote.timestamp);
-        let change = round_f64_str(&last_quote.change);
-        let percent_change = round_f64_str(&last_quote.percent_change);
-        if change == 0.00 {
-            "0.00".clone_into(&mut price_data.change_value);
-            "0.00".clone_into(&mut price_data.percentage_value);
-        } else if change > 0.00 {
-            price_data.change_value = format!("+{change}");
-            price_data.percentage_value = format!("+{percent_change}");
-            change_classes.push("color-green");
-            change_percent_classes.push("color-green");
-        } else {
-            price_data.change_value = format!("{change}");
-            price_data.percentage_value = format!("{percent_change}");
-            change_classes.push("color-red");
-            change_percent_classes.push("color-red");
-        }
+    let change = get_change(symbol, props);
+    let decimals = get_decimals(symbol, props);
+    price_data.change_value = props
+        .format
+        .change(change.map(|change| change.absolute), decimals);
+    price_data.percentage_value = props
+        .format
+        .change(change.and_then(|change| change.percent), PERCENT_DECIMALS);
+    if let Some(color) = change.and_then(|change| change_color(change.absolute)) {
+        change_classes.push(color);
+        change_percent_classes.push(color);
     }
     price_data.change_classes = change_classes;
     price_data.change_percent_classes = change_percent_classes;
 }
 
-fn get_eod_price(symbol: &String, props: &QuotesProps) -> Option<f64> {
-    let mut eod_price: Option<f64> = None;
-    if let Some(end_of_day) = props.reference_data.end_of_day.get(symbol) {
-        let val = round_f64_str(&end_of_day.close);
-        eod_price = Some(val);
+/// Change of the live price, or of the last quote before the first tick
+fn get_change(symbol: &String, props: &QuotesProps) -> Option<PriceChange> {
+    let live_price = props
+        .prices
+        .get(symbol)
+        .map(|price_data| Price::from_f64(price_data.price));
+    price_change(
+        props.baseline,
+        props.quote_type,
+        props.reference_data.last_quote.get(symbol),
+        live_price,
+    )
+}
+
+fn change_color(change: Decimal) -> Option<&'static str> {
+    if change.is_zero() {
+        None
+    } else if change.is_sign_positive() {
+        Some("color-green")
+    } else {
+        Some("color-red")
+    }
+}
+
+/// Baseline price of the sparkline
+fn get_change_base(symbol: &String, props: &QuotesProps) -> Option<f64> {
+    props
+        .reference_data
+        .last_quote
+        .get(symbol)
+        .and_then(|quote| change_base(props.baseline, props.quote_type, quote).to_f64())
+}
+
+/// Value of the row for the sort, `None` if not known yet
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum SortKey {
+    Text(String),
+    Number(Decimal),
+    Time(i64),
+}
+
+fn sort_key(symbol: &String, props: &QuotesProps) -> Option<SortKey> {
+    let column = match props.sort {
+        QuoteSort::Manual => return None,
+        QuoteSort::TopMovers => {
+            return get_change(symbol, props)
+                .and_then(|change| change.percent)
+                .map(|percent| SortKey::Number(percent.abs()));
+        }
+        QuoteSort::Column { column, .. } => column,
+    };
+    let last_quote = props.reference_data.last_quote.get(symbol);
+    let live_price = props.prices.get(symbol);
+    match column {
+        SortColumn::Instrument => Some(SortKey::Text(
+            get_symbol_name(symbol, &props.quote_type, &props.reference_data).to_lowercase(),
+        )),
+        SortColumn::Price => live_price
+            .map(|price_data| Price::from_f64(price_data.price))
+            .or(last_quote.map(|quote| quote.close))
+            .and_then(Price::value)
+            .map(SortKey::Number),
+        SortColumn::Change => {
+            get_change(symbol, props).map(|change| SortKey::Number(change.absolute))
+        }
+        SortColumn::ChangePercent => get_change(symbol, props)
+            .and_then(|change| change.percent)
+            .map(SortKey::Number),
+        SortColumn::Time => live_price
+            .map(|price_data| price_data.timestamp)
+            .or(last_quote.map(|quote| quote.timestamp))
+            .map(SortKey::Time),
+    }
+}
+
+/// Symbols in the row order. The sort is stable, so equal values keep the watchlist order,
+/// and rows without a value go last in both directions.
+fn sort_symbols(props: &QuotesProps) -> Vec<String> {
+    let descending = match props.sort {
+        QuoteSort::Manual => return props.symbols.clone(),
+        QuoteSort::TopMovers => true,
+        QuoteSort::Column { descending, .. } => descending,
+    };
+    let mut rows: Vec<(Option<SortKey>, &String)> = props
+        .symbols
+        .iter()
+        .map(|symbol| (sort_key(symbol, props), symbol))
+        .collect();
+    rows.sort_by(|(left, _), (right, _)| match (left, right) {
+        (Some(left), Some(right)) if descending => right.cmp(left),
+        (Some(left), Some(right)) => left.cmp(right),
+        _ => right.is_some().cmp(&left.is_some()),
+    });
+    rows.into_iter().map(|(_, symbol)| symbol.clone()).collect()
+}
+
+/// Row order of the last sort and what it was sorted by
+struct SortedRows {
+    key: SortedKey,
+    /// Browser time of the sort in milliseconds
+    sorted_at: f64,
+    order: Vec<String>,
+}
+
+#[derive(PartialEq)]
+struct SortedKey {
+    sort: QuoteSort,
+    baseline: ChangeBaseline,
+    symbols: Vec<String>,
+    priced: usize,
+}
+
+/// Sorts by prices and changes, top movers included, follow the ticks at most every
+/// `RESORT_INTERVAL_MS` so that rows do not jump on every tick, the rest are sorted on every render
+fn resort_due(sort: QuoteSort, sorted_at: f64, now: f64) -> bool {
+    match sort {
+        QuoteSort::TopMovers
+        | QuoteSort::Column {
+            column:
+                SortColumn::Price | SortColumn::Change | SortColumn::ChangePercent | SortColumn::Time,
+            ..
+        } => now - sorted_at >= RESORT_INTERVAL_MS,
+        _ => true,
     }
-    eod_price
+}
+
+/// Number of rows with a live or a last price, cheap enough for every render
+fn priced_symbols(props: &QuotesProps) -> usize {
+    props
+        .symbols
+        .iter()
+        .filter(|symbol| {
+            props.prices.contains_key(*symbol)
+                || props.reference_data.last_quote.contains_key(*symbol)
+        })
+        .count()
+}
+
+/// Symbols and instrument names containing the lower case filter text
+fn matches_filter(symbol: &String, filter: &str, props: &QuotesProps) -> bool {
+    filter.is_empty()
+        || symbol.to_lowercase().contains(filter)
+        || get_symbol_name(symbol, &props.quote_type, &props.reference_data)
+            .to_lowercase()
+            .contains(filter)
+}
+
+fn get_decimals(symbol: &str, props: &QuotesProps) -> u32 {
+    props.precision.get(symbol).copied().unwrap_or(MIN_DECIMALS)
 }
 
 fn get_symbol_name(
@@ -171,23 +477,25 @@ fn get_symbol_name(
     quote_type: &QuoteType,
     reference_data: &ReferenceData,
 ) -> String {
-    match quote_type {
-        QuoteType::Indices => {
-            if let Some(indice) = reference_data.indices.get(symbol) {
-                indice.name.clone()
-            } else {
-                symbol.clone()
-            }
-        }
-        QuoteType::USStocks => {
-            if let Some(indice) = reference_data.us_stocks.get(symbol) {
-                indice.name.clone()
-            } else {
-                symbol.clone()
-            }
-        }
-        _ => symbol.clone(),
-    }
+    let name = match quote_type {
+        QuoteType::Indices => reference_data
+            .indices
+            .get(symbol)
+            .map(|indice| indice.name.clone()),
+        QuoteType::USStocks => reference_data
+            .us_stocks
+            .get(symbol)
+            .map(|stock| stock.name.clone()),
+        _ => return symbol.clone(),
+    };
+    // symbols found by the search may be missing in the reference lists
+    name.or_else(|| {
+        reference_data
+            .last_quote
+            .get(symbol)
+            .map(|quote| quote.name.clone())
+    })
+    .unwrap_or_else(|| symbol.clone())
 }
 
 struct CssProps {
@@ -213,3 +521,11 @@ fn get_css_props(props: &QuotesProps) -> CssProps {
         title_classes,
     }
 }
+
+fn session_class(session: MarketSession) -> &'static str {
+    match session {
+        MarketSession::Open => "session-open",
+        MarketSession::PreMarket | MarketSession::PostMarket => "session-extended",
+        MarketSession::Closed => "session-closed",
+    }
+}
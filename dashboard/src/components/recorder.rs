@@ -0,0 +1,49 @@
+use yew::{classes, function_component, html, Callback, Html, Properties};
+
+use crate::common::enums::Language;
+use crate::common::i18n::Text;
+
+/// Session Recorder Controls Properties
+#[derive(Properties, PartialEq, Clone)]
+pub struct SessionRecorderProps {
+    /// Recording is in progress
+    pub recording: bool,
+    /// Number of recorded frames
+    pub frames: usize,
+    /// Binance streams are open, the mock provider cannot replay them so they are not recorded
+    pub binance_excluded: bool,
+    /// User interface language
+    pub language: Language,
+    /// Starting or stopping the recording
+    pub on_toggle: Callback<()>,
+    /// Saving the recorded session
+    pub on_download: Callback<()>,
+}
+
+#[function_component]
+pub fn SessionRecorderControls(props: &SessionRecorderProps) -> Html {
+    let toggle_label = props.language.text(if props.recording {
+        Text::StopRecording
+    } else {
+        Text::StartRecording
+    });
+    let on_toggle = props.on_toggle.reform(|_| ());
+    let on_download = props.on_download.reform(|_| ());
+
+    html! {
+        <div class={classes!("session-recorder", props.recording.then_some("recording"))}>
+            <button onclick={on_toggle}>{toggle_label}</button>
+            if props.frames > 0 {
+                <span>{props.language.text(Text::RecordedFrames(props.frames))}</span>
+            }
+            if props.binance_excluded && (props.recording || props.frames > 0) {
+                <span class="excluded-feeds">{props.language.text(Text::BinanceNotRecorded)}</span>
+            }
+            if props.frames > 0 {
+                if !props.recording {
+                    <button onclick={on_download}>{props.language.text(Text::Download)}</button>
+                }
+            }
+        </div>
+    }
+}
@@ -0,0 +1,208 @@
+use gloo::timers::callback::Timeout;
+use web_sys::HtmlInputElement;
+use yew::events::{InputEvent, KeyboardEvent, TargetCast};
+use yew::{classes, html, Callback, Component, Context, Html, Properties};
+
+use crate::common::entities::SymbolMatch;
+use crate::common::enums::Language;
+use crate::common::i18n::Text;
+use crate::common::MarketResult;
+use crate::services::providers::ProviderRef;
+
+/// Pause after the last keystroke before the search request, milliseconds
+const DEBOUNCE_MS: u32 = 300;
+/// Number of suggestions requested
+const OUTPUT_SIZE: u32 = 10;
+
+/// Symbol Search Component Properties
+#[derive(Properties, PartialEq, Clone)]
+pub struct SymbolSearchProps {
+    /// Market data provider of the search
+    pub provider: ProviderRef,
+    /// Chosen symbol: a suggestion or the typed text
+    pub on_select: Callback<String>,
+    /// User interface language
+    pub language: Language,
+}
+
+/// Symbol input with debounced autocomplete: arrows move over the suggestions,
+/// Enter chooses the highlighted one or the typed symbol, Escape closes the list
+pub struct SymbolSearch {
+    query: String,
+    results: Vec<SymbolMatch>,
+    highlighted: Option<usize>,
+    error: Option<String>,
+    debounce: Option<Timeout>,
+}
+
+/// Symbol Search Component Messages
+pub enum SymbolSearchMessage {
+    /// Query typed
+    Input(String),
+    /// Debounce pause elapsed
+    Search(String),
+    /// Search response
+    Loaded {
+        query: String,
+        results: MarketResult<Vec<SymbolMatch>>,
+    },
+    /// Moving the highlight by the offset
+    Highlight(isize),
+    /// Choosing the highlighted suggestion or the typed symbol
+    Choose(Option<usize>),
+    /// Closing the suggestions
+    Close,
+}
+
+impl Component for SymbolSearch {
+    type Message = SymbolSearchMessage;
+    type Properties = SymbolSearchProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            query: String::new(),
+            results: Vec::new(),
+            highlighted: None,
+            error: None,
+            debounce: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            SymbolSearchMessage::Input(query) => {
+                self.query = query;
+                let search = self.query.trim().to_owned();
+                // dropping the previous timeout cancels it
+                self.debounce = if search.is_empty() {
+                    self.close();
+                    None
+                } else {
+                    let link = ctx.link().clone();
+                    Some(Timeout::new(DEBOUNCE_MS, move || {
+                        link.send_message(SymbolSearchMessage::Search(search))
+                    }))
+                };
+            }
+            SymbolSearchMessage::Search(query) => {
+                self.debounce = None;
+                let provider = ctx.props().provider.clone();
+                ctx.link().send_future(async move {
+                    let results = provider.search_symbols(query.clone(), OUTPUT_SIZE).await;
+                    SymbolSearchMessage::Loaded { query, results }
+                });
+                return false;
+            }
+            SymbolSearchMessage::Loaded { query, results } => {
+                // a response to an outdated query
+                if query != self.query.trim() {
+                    return false;
+                }
+                self.highlighted = None;
+                match results {
+                    Ok(results) => {
+                        self.results = results;
+                        self.error = None;
+                    }
+                    Err(error) => {
+                        log::error!("Symbol search failed: {}", error);
+                        self.results.clear();
+                        self.error = Some(error.to_string());
+                    }
+                }
+            }
+            SymbolSearchMessage::Highlight(offset) => {
+                if self.results.is_empty() {
+                    return false;
+                }
+                let last = self.results.len() - 1;
+                self.highlighted = match (self.highlighted, offset.is_negative()) {
+                    (None, false) => Some(0),
+                    (None, true) => Some(last),
+                    (Some(index), _) => Some(index.saturating_add_signed(offset).min(last)),
+                };
+            }
+            SymbolSearchMessage::Choose(index) => {
+                let symbol = match index.and_then(|index| self.results.get(index)) {
+                    Some(result) => result.symbol.clone(),
+                    None => self.query.trim().to_owned(),
+                };
+                if !symbol.is_empty() {
+                    ctx.props().on_select.emit(symbol);
+                }
+                self.query.clear();
+                self.debounce = None;
+                self.close();
+            }
+            SymbolSearchMessage::Close => {
+                self.close();
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let oninput = ctx.link().callback(|event: InputEvent| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            SymbolSearchMessage::Input(input.value())
+        });
+        let highlighted = self.highlighted;
+        let onkeydown = ctx.link().batch_callback(move |event: KeyboardEvent| {
+            let message = match event.key().as_str() {
+                "ArrowDown" => SymbolSearchMessage::Highlight(1),
+                "ArrowUp" => SymbolSearchMessage::Highlight(-1),
+                "Enter" => SymbolSearchMessage::Choose(highlighted),
+                "Escape" => SymbolSearchMessage::Close,
+                _ => return None,
+            };
+            event.prevent_default();
+            Some(message)
+        });
+
+        html! {
+            <div class="symbol-search">
+                <input class="symbol-input" placeholder={ctx.props().language.text(Text::AddSymbol)} value={self.query.clone()}
+                       {oninput} {onkeydown} />
+                if !self.results.is_empty() || self.error.is_some() {
+                    <ul class="symbol-suggestions">
+                        if let Some(error) = &self.error {
+                            <li class="color-red">{error}</li>
+                        }
+                        {
+                            self.results.iter().enumerate().map(|(index, result)| {
+                                let onclick = ctx.link().callback(move |_| SymbolSearchMessage::Choose(Some(index)));
+                                let exchange = if result.mic_code.is_empty() || result.mic_code == result.exchange {
+                                    result.exchange.clone()
+                                } else {
+                                    format!("{} ({})", result.exchange, result.mic_code)
+                                };
+                                let details = [exchange.as_str(), &result.instrument_type, &result.country]
+                                    .into_iter()
+                                    .filter(|value| !value.is_empty())
+                                    .collect::<Vec<_>>()
+                                    .join(" · ");
+                                html! {
+                                    <li class={classes!((highlighted == Some(index)).then_some("selected"))} {onclick}>
+                                        <div>
+                                            <span class="symbol-suggestion-symbol">{&result.symbol}</span>
+                                            {&result.name}
+                                        </div>
+                                        <div class="symbol-suggestion-details">{details}</div>
+                                    </li>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </ul>
+                }
+            </div>
+        }
+    }
+}
+
+impl SymbolSearch {
+    fn close(&mut self) {
+        self.results.clear();
+        self.highlighted = None;
+        self.error = None;
+    }
+}
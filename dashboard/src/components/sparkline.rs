@@ -0,0 +1,57 @@
+use yew::{classes, function_component, html, Html, Properties};
+
+const WIDTH: f64 = 80.0;
+const HEIGHT: f64 = 20.0;
+/// Space above and below the line so that it is not clipped by the stroke
+const PADDING: f64 = 2.0;
+
+/// Sparkline Component Properties
+#[derive(Properties, PartialEq, Clone)]
+pub struct SparklineProps {
+    /// Recent prices, the oldest first
+    pub points: Vec<f64>,
+    /// Previous close price
+    pub baseline: Option<f64>,
+}
+
+/// Compact price line of the recent ticks. Green above the previous close, red below.
+#[function_component]
+pub fn Sparkline(props: &SparklineProps) -> Html {
+    let (Some(first), Some(last)) = (props.points.first(), props.points.last()) else {
+        return html! {};
+    };
+    let baseline = props.baseline.unwrap_or(*first);
+    let (low, high) = props
+        .points
+        .iter()
+        .chain(props.baseline.iter())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), &price| {
+            (low.min(price), high.max(price))
+        });
+    let span = (high - low).max(f64::EPSILON);
+    let y = |price: f64| PADDING + (high - price) / span * (HEIGHT - 2.0 * PADDING);
+    let step = WIDTH / (props.points.len().max(2) - 1) as f64;
+    let points = props
+        .points
+        .iter()
+        .enumerate()
+        .map(|(index, &price)| format!("{:.1},{:.1}", index as f64 * step, y(price)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let color = if *last >= baseline {
+        "color-green"
+    } else {
+        "color-red"
+    };
+    let baseline_y = format!("{:.1}", y(baseline));
+
+    html! {
+        <svg class={classes!("sparkline", color)} width={WIDTH.to_string()} height={HEIGHT.to_string()}
+             viewBox={format!("0 0 {WIDTH} {HEIGHT}")}>
+            if props.baseline.is_some() {
+                <line class="sparkline-baseline" x1="0" y1={baseline_y.clone()} x2={WIDTH.to_string()} y2={baseline_y} />
+            }
+            <polyline points={points} />
+        </svg>
+    }
+}
@@ -0,0 +1,54 @@
+use yew::{classes, function_component, html, Callback, Html, Properties};
+
+use crate::common::enums::{ConnectionState, Language};
+use crate::common::i18n::Text;
+
+/// Connection Status Component Properties
+#[derive(Properties, PartialEq, Clone)]
+pub struct ConnectionStatusProps {
+    /// Current web socket connection state
+    pub state: ConnectionState,
+    /// User interface language
+    pub language: Language,
+    /// Manual reconnection request
+    pub on_retry: Callback<()>,
+}
+
+#[function_component]
+pub fn ConnectionStatus(props: &ConnectionStatusProps) -> Html {
+    let class = match props.state {
+        ConnectionState::Connecting => "status-connecting",
+        ConnectionState::Live => "status-live",
+        ConnectionState::Reconnecting => "status-reconnecting",
+        ConnectionState::Failed => "status-failed",
+    };
+    let label = props.language.text(Text::ConnectionState(props.state));
+    let on_retry = props.on_retry.reform(|_| ());
+
+    html! {
+        <div class={classes!("connection-status", class)}>
+            <span class="status-dot"></span>
+            <span>{label}</span>
+            if props.state == ConnectionState::Failed {
+                <button class="status-retry" onclick={on_retry}>{props.language.text(Text::Retry)}</button>
+            }
+        </div>
+    }
+}
+
+/// Feed Delay Warning Properties
+#[derive(Properties, PartialEq, Clone)]
+pub struct FeedDelayWarningProps {
+    /// Seconds since the last price
+    pub seconds: u64,
+    /// User interface language
+    pub language: Language,
+}
+
+/// Warning shown while the connection is open but no prices arrive
+#[function_component]
+pub fn FeedDelayWarning(props: &FeedDelayWarningProps) -> Html {
+    html! {
+        <div class="feed-delayed">{props.language.text(Text::FeedDelayed(props.seconds))}</div>
+    }
+}
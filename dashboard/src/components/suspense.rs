@@ -1,18 +1,35 @@
+use std::rc::Rc;
+
 use futures::join;
 use yew::platform::spawn_local;
 use yew::prelude::*;
 use yew::suspense::{Suspension, SuspensionHandle, SuspensionResult};
 
-use crate::common::config::DashboardConfiguration;
-use crate::common::entities::ReferenceData;
-use crate::services::restapi::RestApiService;
+use crate::common::app_config::AppConfig;
+use crate::common::entities::{ReferenceData, Watchlist};
+use crate::services::config::ConfigService;
+use crate::services::providers::ProviderRef;
+use crate::services::watchlists::WatchlistService;
+
+/// Data loaded before the dashboard is rendered
+#[derive(PartialEq, Clone)]
+pub struct LoadedData {
+    /// Runtime configuration
+    pub config: Rc<AppConfig>,
+    /// Market data provider selected by the configuration
+    pub provider: ProviderRef,
+    /// User watchlists
+    pub watchlists: Vec<Watchlist>,
+    /// Reference market data
+    pub reference_data: ReferenceData,
+}
 
 /// State for Suspense Component
 #[derive(PartialEq)]
 pub struct LoadDataState {
     suspension: Suspension,
     handle: Option<SuspensionHandle>,
-    reference_data: ReferenceData,
+    loaded_data: Option<LoadedData>,
 }
 
 impl LoadDataState {
@@ -21,19 +38,22 @@ impl LoadDataState {
         Self {
             suspension,
             handle: Some(handle),
-            reference_data: Default::default(),
+            loaded_data: None,
         }
     }
 }
 
-/// Loading reference market data
+/// Loading runtime configuration and reference market data
 #[hook]
-pub fn use_load_data() -> SuspensionResult<ReferenceData> {
+pub fn use_load_data() -> SuspensionResult<LoadedData> {
     let load_data_state = use_state(LoadDataState::new);
     let loading_started = use_mut_ref(|| false);
 
-    if load_data_state.suspension.resumed() {
-        Ok(load_data_state.reference_data.clone())
+    if let (true, Some(loaded_data)) = (
+        load_data_state.suspension.resumed(),
+        &load_data_state.loaded_data,
+    ) {
+        Ok(loaded_data.clone())
     } else {
         if !*loading_started.borrow() {
             *loading_started.borrow_mut() = true;
@@ -45,12 +65,23 @@ pub fn use_load_data() -> SuspensionResult<ReferenceData> {
 
 fn load_data(state: UseStateHandle<LoadDataState>) {
     spawn_local(async move {
-        let indices = RestApiService::get_indices();
-        let us_stocks = RestApiService::get_us_stocks();
-        let end_of_day =
-            RestApiService::get_end_of_day_data(DashboardConfiguration::get_all_quote_symbols());
-        let last_quote =
-            RestApiService::get_last_quote(DashboardConfiguration::get_all_quote_symbols());
+        let config = match ConfigService::load().await {
+            Ok(config) => config,
+            Err(error) => {
+                log::error!("Failed to load configuration, using defaults: {}", error);
+                AppConfig::default()
+            }
+        };
+        let config = Rc::new(config);
+        let provider = ProviderRef::from_config(config.clone());
+
+        let watchlists = WatchlistService::load();
+        let symbols = WatchlistService::all_symbols(&watchlists);
+
+        let indices = provider.get_indices();
+        let us_stocks = provider.get_us_stocks();
+        let end_of_day = provider.get_end_of_day_data(symbols.clone());
+        let last_quote = provider.get_last_quote(symbols);
 
         let (indices, us_stocks, end_of_day, last_quote) =
             join!(indices, us_stocks, end_of_day, last_quote);
@@ -71,7 +102,12 @@ fn load_data(state: UseStateHandle<LoadDataState>) {
         state.set(LoadDataState {
             suspension: state.suspension.clone(),
             handle: None, //drop handler and resume render
-            reference_data,
+            loaded_data: Some(LoadedData {
+                config,
+                provider,
+                watchlists,
+                reference_data,
+            }),
         });
     });
 }
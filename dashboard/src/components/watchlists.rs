@@ -0,0 +1,138 @@
+use gloo::file::File;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::events::{Event, KeyboardEvent, TargetCast};
+use yew::platform::spawn_local;
+use yew::{function_component, html, use_state, Callback, Html, Properties};
+
+use crate::common::entities::Watchlist;
+use crate::common::enums::{Language, QuoteType};
+use crate::common::i18n::Text;
+use crate::services::files::read_text;
+use crate::services::watchlists::{WatchlistAction, WatchlistService};
+
+/// Watchlist Editor Properties
+#[derive(Properties, PartialEq, Clone)]
+pub struct WatchlistEditorProps {
+    /// Watchlists in the display order
+    pub watchlists: Vec<Watchlist>,
+    /// Watchlist change
+    pub on_action: Callback<WatchlistAction>,
+    /// Saving the watchlists to a file
+    pub on_export: Callback<()>,
+    /// User interface language
+    pub language: Language,
+}
+
+/// Creating, renaming, reordering and deleting watchlists, import and export as JSON
+#[function_component]
+pub fn WatchlistEditor(props: &WatchlistEditorProps) -> Html {
+    let quote_type = use_state(|| QuoteType::USStocks);
+    let import_error = use_state(|| None::<String>);
+
+    let on_create = {
+        let on_action = props.on_action.clone();
+        let quote_type = quote_type.clone();
+        Callback::from(move |event: KeyboardEvent| {
+            if event.key() == "Enter" {
+                let input: HtmlInputElement = event.target_unchecked_into();
+                on_action.emit(WatchlistAction::Create {
+                    name: input.value(),
+                    quote_type: *quote_type,
+                });
+                input.set_value("");
+            }
+        })
+    };
+    let on_quote_type = {
+        let quote_type = quote_type.clone();
+        Callback::from(move |event: Event| {
+            let select: HtmlSelectElement = event.target_unchecked_into();
+            if let Some(&selected) = usize::try_from(select.selected_index())
+                .ok()
+                .and_then(|index| QuoteType::ALL.get(index))
+            {
+                quote_type.set(selected);
+            }
+        })
+    };
+    let on_import = {
+        let on_action = props.on_action.clone();
+        let import_error = import_error.clone();
+        Callback::from(move |event: Event| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            let Some(file) = input.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            input.set_value("");
+            let on_action = on_action.clone();
+            let import_error = import_error.clone();
+            spawn_local(async move {
+                let file = File::from(file);
+                match read_text(&file)
+                    .await
+                    .and_then(|json| WatchlistService::parse_import(&json))
+                {
+                    Ok(watchlists) => {
+                        import_error.set(None);
+                        on_action.emit(WatchlistAction::Import(watchlists));
+                    }
+                    Err(error) => {
+                        log::error!("Failed to import watchlists: {}", error);
+                        import_error.set(Some(error.to_string()));
+                    }
+                }
+            });
+        })
+    };
+    let on_export = props.on_export.reform(|_| ());
+    let last = props.watchlists.len().saturating_sub(1);
+    let language = props.language;
+
+    html! {
+        <div class="watchlist-editor">
+            <ul>
+            {
+                props.watchlists.iter().enumerate().map(|(index, watchlist)| {
+                    let on_rename = props.on_action.reform(move |event: Event| {
+                        let input: HtmlInputElement = event.target_unchecked_into();
+                        WatchlistAction::Rename { index, name: input.value() }
+                    });
+                    let on_left = props.on_action.reform(move |_| WatchlistAction::Move { index, offset: -1 });
+                    let on_right = props.on_action.reform(move |_| WatchlistAction::Move { index, offset: 1 });
+                    let on_delete = props.on_action.reform(move |_| WatchlistAction::Delete { index });
+                    html! {
+                        <li>
+                            <input value={watchlist.name.clone()} placeholder={watchlist.title(language)}
+                                   onchange={on_rename} />
+                            <span>{language.text(Text::QuoteType(watchlist.quote_type))}</span>
+                            <button title={language.text(Text::MoveLeft)} disabled={index == 0} onclick={on_left}>{"←"}</button>
+                            <button title={language.text(Text::MoveRight)} disabled={index == last} onclick={on_right}>{"→"}</button>
+                            <button title={language.text(Text::Delete)} onclick={on_delete}>{"✕"}</button>
+                        </li>
+                    }
+                }).collect::<Html>()
+            }
+            </ul>
+            <div class="watchlist-create">
+                <input placeholder={language.text(Text::NewWatchlist)} onkeydown={on_create} />
+                <select onchange={on_quote_type}>
+                {
+                    QuoteType::ALL.iter().map(|&value| html! {
+                        <option selected={value == *quote_type}>{language.text(Text::QuoteType(value))}</option>
+                    }).collect::<Html>()
+                }
+                </select>
+            </div>
+            <div class="watchlist-files">
+                <button onclick={on_export}>{language.text(Text::Export)}</button>
+                <label>
+                    {language.text(Text::Import)}{" "}
+                    <input type="file" accept="application/json,.json" onchange={on_import} />
+                </label>
+                if let Some(error) = &*import_error {
+                    <span class="color-red">{error}</span>
+                }
+            </div>
+        </div>
+    }
+}
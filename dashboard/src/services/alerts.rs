@@ -0,0 +1,156 @@
+use std::collections::VecDeque;
+
+use gloo::storage::errors::StorageError;
+use gloo::storage::{LocalStorage, Storage};
+use log::warn;
+use wasm_bindgen::JsValue;
+use web_sys::{
+    AudioContext, AudioContextState, Notification, NotificationOptions, NotificationPermission,
+};
+
+use crate::common::alerts::{AlertCondition, AlertEngine, AlertEvent, AlertRule};
+use crate::common::MarketResult;
+
+/// localStorage key with the alert rules
+const ALERT_RULES_STORAGE_KEY: &str = "market_aggregator.alert_rules";
+/// localStorage key with the id of the next alert rule
+const ALERT_NEXT_ID_STORAGE_KEY: &str = "market_aggregator.alert_next_id";
+/// localStorage key with the fired alerts
+const ALERT_HISTORY_STORAGE_KEY: &str = "market_aggregator.alert_history";
+/// Alert beep pitch, Hz
+const BEEP_FREQUENCY: f32 = 880.0;
+/// Alert beep length, seconds
+const BEEP_DURATION: f64 = 0.2;
+
+/// Alert change requested by the user
+#[derive(PartialEq, Clone, Debug)]
+pub enum AlertAction {
+    Create {
+        symbol: String,
+        condition: AlertCondition,
+        cooldown_secs: u64,
+    },
+    /// Enabling or disabling the rule
+    Toggle {
+        id: u64,
+    },
+    Delete {
+        id: u64,
+    },
+    ClearHistory,
+}
+
+/// Alert Rules and History Storage
+pub struct AlertService;
+
+impl AlertService {
+    /// Rules and history saved in localStorage
+    pub fn load() -> AlertEngine {
+        AlertEngine::new(
+            Self::load_key::<Vec<AlertRule>>(ALERT_RULES_STORAGE_KEY),
+            Self::load_key::<VecDeque<AlertEvent>>(ALERT_HISTORY_STORAGE_KEY),
+            Self::load_key::<u64>(ALERT_NEXT_ID_STORAGE_KEY),
+        )
+    }
+
+    pub fn save(engine: &AlertEngine) -> MarketResult<()> {
+        LocalStorage::set(ALERT_RULES_STORAGE_KEY, engine.rules())?;
+        LocalStorage::set(ALERT_NEXT_ID_STORAGE_KEY, engine.next_id())?;
+        LocalStorage::set(ALERT_HISTORY_STORAGE_KEY, engine.history())?;
+        Ok(())
+    }
+    /// Applying the change, returns false if nothing changed
+    pub fn apply(engine: &mut AlertEngine, action: AlertAction) -> bool {
+        match action {
+            AlertAction::Create {
+                symbol,
+                condition,
+                cooldown_secs,
+            } => {
+                let symbol = symbol.trim().to_uppercase();
+                if symbol.is_empty() {
+                    return false;
+                }
+                engine.add_rule(symbol, condition, cooldown_secs);
+                true
+            }
+            AlertAction::Toggle { id } => engine.toggle_rule(id),
+            AlertAction::Delete { id } => engine.remove_rule(id),
+            AlertAction::ClearHistory => engine.clear_history(),
+        }
+    }
+
+    fn load_key<T: Default + for<'de> serde::Deserialize<'de>>(key: &str) -> T {
+        match LocalStorage::get::<T>(key) {
+            Ok(value) => value,
+            Err(StorageError::KeyNotFound(_)) => T::default(),
+            Err(error) => {
+                warn!("Saved {} are not readable, ignoring: {}", key, error);
+                T::default()
+            }
+        }
+    }
+}
+
+/// Browser notifications and the alert sound
+#[derive(Default)]
+pub struct AlertNotifier {
+    /// Created on a user action, browsers do not start audio without one
+    audio: Option<AudioContext>,
+}
+
+impl AlertNotifier {
+    /// Asking the notification permission and enabling the sound, called on a user action
+    pub fn prepare(&mut self, notifications: bool, sound: bool) {
+        if notifications && Self::notifications_supported() {
+            if let Err(error) = Notification::request_permission() {
+                warn!("Notification permission request failed: {:?}", error);
+            }
+        }
+        if sound && self.audio.is_none() {
+            match AudioContext::new() {
+                Ok(audio) => self.audio = Some(audio),
+                Err(error) => warn!("Alert sound is not available: {:?}", error),
+            }
+        }
+    }
+    /// Showing a browser notification and playing the beep, as enabled and permitted
+    pub fn notify(&self, title: &str, body: &str, notifications: bool, sound: bool) {
+        if notifications
+            && Self::notifications_supported()
+            && Notification::permission() == NotificationPermission::Granted
+        {
+            let mut options = NotificationOptions::new();
+            options.body(body);
+            if let Err(error) = Notification::new_with_options(title, &options) {
+                warn!("Failed to show the notification: {:?}", error);
+            }
+        }
+        if let (true, Some(audio)) = (sound, &self.audio) {
+            if let Err(error) = Self::beep(audio) {
+                warn!("Failed to play the alert sound: {:?}", error);
+            }
+        }
+    }
+
+    fn notifications_supported() -> bool {
+        js_sys::Reflect::has(&gloo::utils::window(), &JsValue::from_str("Notification"))
+            .unwrap_or(false)
+    }
+
+    fn beep(audio: &AudioContext) -> Result<(), JsValue> {
+        if audio.state() == AudioContextState::Suspended {
+            // the beep is scheduled on the audio clock, the resume is not awaited
+            let _ = audio.resume()?;
+        }
+        let oscillator = audio.create_oscillator()?;
+        let gain = audio.create_gain()?;
+        oscillator.frequency().set_value(BEEP_FREQUENCY);
+        gain.gain().set_value(0.1);
+        oscillator.connect_with_audio_node(&gain)?;
+        gain.connect_with_audio_node(&audio.destination())?;
+        oscillator.start()?;
+        oscillator.stop_with_when(audio.current_time() + BEEP_DURATION)?;
+        Ok(())
+    }
+}
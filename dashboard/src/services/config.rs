@@ -0,0 +1,100 @@
+use gloo::storage::errors::StorageError;
+use gloo::storage::{LocalStorage, Storage};
+use gloo_net::http::Request;
+use log::{debug, warn};
+use serde_json::Value;
+use web_sys::UrlSearchParams;
+
+use crate::common::app_config::{AppConfig, AppConfigLayer};
+use crate::common::error::MarketError;
+use crate::common::MarketResult;
+
+/// Configuration file served next to index.html
+const CONFIG_URL: &str = "config.json";
+/// localStorage key with configuration overrides
+const CONFIG_STORAGE_KEY: &str = "market_aggregator.config";
+
+/// Runtime Configuration Loader
+pub struct ConfigService;
+
+impl ConfigService {
+    /// Loading configuration from config.json, localStorage overrides and URL query overrides
+    /// (in ascending priority). Overrides change only the user preferences, see `AppConfig::from_sources`.
+    pub async fn load() -> MarketResult<AppConfig> {
+        let file_layer = Self::load_file().await?;
+        let storage_layer = Self::load_local_storage()?;
+        let query_layer = Self::load_url_query()?;
+        let mut config = AppConfig::from_sources(file_layer, [storage_layer, query_layer])?;
+        if config.ws_address.is_empty() {
+            config.ws_address = Self::origin_ws_address()?;
+        }
+        Ok(config)
+    }
+
+    /// Saving a field override in localStorage, it applies on the next start unless the URL
+    /// query overrides it
+    pub fn save_override(field: &str, value: Value) -> MarketResult<()> {
+        let mut layer = Self::load_local_storage()?;
+        layer.insert(field.to_owned(), value);
+        LocalStorage::set(CONFIG_STORAGE_KEY, layer)?;
+        Ok(())
+    }
+
+    async fn load_file() -> MarketResult<AppConfigLayer> {
+        let response = Request::get(CONFIG_URL).send().await?;
+        if !response.ok() {
+            warn!(
+                "{} is not available (status {}), using defaults",
+                CONFIG_URL,
+                response.status()
+            );
+            return Ok(AppConfigLayer::default());
+        }
+        let response_text = response.text().await?;
+        let layer: AppConfigLayer = serde_json::from_str(response_text.as_str())?;
+        Ok(layer)
+    }
+
+    fn load_local_storage() -> MarketResult<AppConfigLayer> {
+        match LocalStorage::get::<AppConfigLayer>(CONFIG_STORAGE_KEY) {
+            Ok(layer) => {
+                debug!("Configuration overrides loaded from localStorage");
+                Ok(layer)
+            }
+            Err(StorageError::KeyNotFound(_)) => Ok(AppConfigLayer::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn load_url_query() -> MarketResult<AppConfigLayer> {
+        let search = gloo::utils::window()
+            .location()
+            .search()
+            .map_err(|_| MarketError::Config("URL query is not available".to_owned()))?;
+        let params = UrlSearchParams::new_with_str(search.as_str())
+            .map_err(|_| MarketError::Config(format!("Invalid URL query: {search}")))?;
+        let mut layer = AppConfigLayer::new();
+        for (field, default) in AppConfig::default_layer() {
+            if let Some(value) = params.get(field.as_str()) {
+                // query values are strings, other types are written as JSON
+                let value = match default {
+                    Value::String(_) => Value::String(value),
+                    _ => serde_json::from_str(value.as_str()).unwrap_or(Value::String(value)),
+                };
+                layer.insert(field, value);
+            }
+        }
+        Ok(layer)
+    }
+
+    /// Web socket address of the origin the dashboard was loaded from
+    fn origin_ws_address() -> MarketResult<String> {
+        let location = gloo::utils::window().location();
+        let (protocol, host) = location
+            .protocol()
+            .and_then(|protocol| Ok((protocol, location.host()?)))
+            .map_err(|_| MarketError::Config("Page location is not available".to_owned()))?;
+        let scheme = if protocol == "https:" { "wss" } else { "ws" };
+        Ok(format!("{scheme}://{host}"))
+    }
+}
@@ -0,0 +1,29 @@
+use gloo::file::{Blob, File, ObjectUrl};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+use crate::common::error::MarketError;
+use crate::common::MarketResult;
+
+/// Saving the text as a file through the browser download
+pub fn download_text(file_name: &str, text: &str, mime_type: &str) -> MarketResult<()> {
+    let blob = Blob::new_with_options(text, Some(mime_type));
+    let url = ObjectUrl::from(blob);
+    let link = gloo::utils::document()
+        .create_element("a")
+        .and_then(|link| {
+            link.set_attribute("href", &url)?;
+            link.set_attribute("download", file_name)?;
+            Ok(link.dyn_into::<HtmlElement>()?)
+        })
+        .map_err(|_| MarketError::General("Download link is not available".to_owned()))?;
+    link.click();
+    Ok(())
+}
+
+/// Reading a file selected by the user as text
+pub async fn read_text(file: &File) -> MarketResult<String> {
+    gloo::file::futures::read_as_text(file)
+        .await
+        .map_err(|error| MarketError::General(format!("Failed to read {}: {error}", file.name())))
+}
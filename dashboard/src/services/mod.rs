@@ -1,2 +1,10 @@
+pub mod alerts;
+pub mod config;
+pub mod files;
+pub mod portfolio;
+pub mod providers;
+pub mod recorder;
 pub mod restapi;
+pub mod supervisor;
+pub mod watchlists;
 pub mod websocket;
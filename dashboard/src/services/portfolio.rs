@@ -0,0 +1,83 @@
+use gloo::storage::errors::StorageError;
+use gloo::storage::{LocalStorage, Storage};
+use log::warn;
+use rust_decimal::Decimal;
+
+use crate::common::portfolio::Position;
+use crate::common::MarketResult;
+
+/// localStorage key with the portfolio positions
+const PORTFOLIO_STORAGE_KEY: &str = "market_aggregator.portfolio";
+
+/// Portfolio change requested by the user
+#[derive(PartialEq, Clone, Debug)]
+pub enum PortfolioAction {
+    /// Adding a position, the currency is already resolved
+    Add {
+        symbol: String,
+        quantity: Decimal,
+        cost_basis: Decimal,
+        currency: String,
+    },
+    Remove {
+        id: u64,
+    },
+}
+
+/// Portfolio Positions Storage
+pub struct PortfolioService;
+
+impl PortfolioService {
+    /// Positions saved in localStorage
+    pub fn load() -> Vec<Position> {
+        match LocalStorage::get::<Vec<Position>>(PORTFOLIO_STORAGE_KEY) {
+            Ok(positions) => positions,
+            Err(StorageError::KeyNotFound(_)) => Vec::new(),
+            Err(error) => {
+                warn!("Saved portfolio is not readable, ignoring: {}", error);
+                Vec::new()
+            }
+        }
+    }
+
+    pub fn save(positions: &[Position]) -> MarketResult<()> {
+        LocalStorage::set(PORTFOLIO_STORAGE_KEY, positions)?;
+        Ok(())
+    }
+    /// Applying the change, returns false if nothing changed
+    pub fn apply(positions: &mut Vec<Position>, action: PortfolioAction) -> bool {
+        match action {
+            PortfolioAction::Add {
+                symbol,
+                quantity,
+                cost_basis,
+                currency,
+            } => {
+                let symbol = symbol.trim().to_uppercase();
+                if symbol.is_empty() || quantity.is_zero() || cost_basis.is_sign_negative() {
+                    return false;
+                }
+                let id = positions
+                    .iter()
+                    .map(|position| position.id + 1)
+                    .max()
+                    .unwrap_or(1);
+                positions.push(Position {
+                    id,
+                    symbol,
+                    quantity,
+                    cost_basis,
+                    currency: currency.trim().to_uppercase(),
+                });
+            }
+            PortfolioAction::Remove { id } => {
+                let count = positions.len();
+                positions.retain(|position| position.id != id);
+                if positions.len() == count {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
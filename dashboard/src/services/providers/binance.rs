@@ -0,0 +1,459 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use futures::future::{self, LocalBoxFuture};
+use futures::FutureExt;
+use serde::Deserialize;
+
+use crate::common::app_config::AppConfig;
+use crate::common::entities::{
+    EndOfDay, Indices, PriceTick, Quote, Stock, SubscribeStatus, SymbolMatch, TimeSeries,
+};
+use crate::common::enums::{BinanceStream, FeedEvent, TimeSeriesInterval};
+use crate::common::error::MarketError;
+use crate::common::MarketResult;
+use crate::services::providers::{MarketDataProvider, PriceFeed};
+use crate::services::websocket::WebSocketService;
+
+/// Exchange quote assets used instead of fiat currencies
+const FIAT_QUOTE_ASSETS: [(&str, &str); 1] = [("USD", "USDT")];
+/// Milliseconds for the first price of a subscribed symbol before it is reported as failed
+const FIRST_PRICE_TIMEOUT_MS: f64 = 30_000.0;
+
+/// Binance compatible public streams provider. Streaming only: crypto quotes are free
+/// on the exchange, reference data stays with the primary provider.
+pub struct BinanceProvider {
+    config: Rc<AppConfig>,
+}
+
+impl BinanceProvider {
+    pub fn new(config: Rc<AppConfig>) -> Self {
+        Self { config }
+    }
+}
+
+impl MarketDataProvider for BinanceProvider {
+    fn get_indices(&self) -> LocalBoxFuture<'_, MarketResult<HashMap<String, Indices>>> {
+        future::ready(Ok(HashMap::new())).boxed_local()
+    }
+
+    fn get_us_stocks(&self) -> LocalBoxFuture<'_, MarketResult<HashMap<String, Stock>>> {
+        future::ready(Ok(HashMap::new())).boxed_local()
+    }
+
+    fn get_end_of_day_data(
+        &self,
+        _symbols: HashSet<String>,
+    ) -> LocalBoxFuture<'_, MarketResult<HashMap<String, EndOfDay>>> {
+        future::ready(Ok(HashMap::new())).boxed_local()
+    }
+
+    fn get_last_quote(
+        &self,
+        _symbols: HashSet<String>,
+    ) -> LocalBoxFuture<'_, MarketResult<HashMap<String, Quote>>> {
+        future::ready(Ok(HashMap::new())).boxed_local()
+    }
+
+    fn get_time_series(
+        &self,
+        symbol: String,
+        _interval: TimeSeriesInterval,
+        _output_size: u32,
+    ) -> LocalBoxFuture<'_, MarketResult<TimeSeries>> {
+        future::ready(Err(MarketError::General(format!(
+            "Time series of {symbol} are not available from Binance streams"
+        ))))
+        .boxed_local()
+    }
+
+    fn search_symbols(
+        &self,
+        _query: String,
+        _output_size: u32,
+    ) -> LocalBoxFuture<'_, MarketResult<Vec<SymbolMatch>>> {
+        future::ready(Ok(Vec::new())).boxed_local()
+    }
+
+    fn open_price_feed(&self) -> MarketResult<Box<dyn PriceFeed>> {
+        let url = format!("{}/ws", self.config.binance_ws_address);
+        let socket = WebSocketService::open_ws_connection(url.as_str())?;
+        Ok(Box::new(BinancePriceFeed {
+            socket,
+            stream: self.config.binance_stream,
+            symbols: RefCell::new(HashMap::new()),
+            next_id: Cell::new(1),
+            subscriptions: RefCell::new(HashMap::new()),
+            watchdog: RefCell::new(FirstPriceWatchdog::default()),
+        }))
+    }
+}
+
+/// Binance web socket connection
+pub struct BinancePriceFeed {
+    socket: WebSocketService,
+    stream: BinanceStream,
+    /// Application symbols by exchange symbol
+    symbols: RefCell<HashMap<String, String>>,
+    /// Identifier of the next method request
+    next_id: Cell<u64>,
+    /// Symbols of the SUBSCRIBE requests waiting for the answer, by request identifier
+    subscriptions: RefCell<HashMap<u64, Vec<String>>>,
+    /// The exchange accepts streams of unknown pairs silently, symbols are reported by their prices
+    watchdog: RefCell<FirstPriceWatchdog>,
+}
+
+impl PriceFeed for BinancePriceFeed {
+    fn subscribe(&self, symbols: HashSet<String>) -> LocalBoxFuture<'_, MarketResult<()>> {
+        let mut exchange_symbols = Vec::new();
+        {
+            let mut symbols_map = self.symbols.borrow_mut();
+            for symbol in &symbols {
+                let exchange_symbol = to_exchange_symbol(symbol);
+                symbols_map.insert(exchange_symbol.clone(), symbol.clone());
+                exchange_symbols.push(exchange_symbol);
+            }
+        }
+        let id = self.next_request_id();
+        self.watchdog
+            .borrow_mut()
+            .watch(symbols.iter().cloned(), js_sys::Date::now());
+        self.subscriptions
+            .borrow_mut()
+            .insert(id, symbols.into_iter().collect());
+        self.send_method("SUBSCRIBE", id, exchange_symbols)
+    }
+
+    fn unsubscribe(&self, symbols: HashSet<String>) -> LocalBoxFuture<'_, MarketResult<()>> {
+        let exchange_symbols = symbols
+            .iter()
+            .map(|symbol| to_exchange_symbol(symbol))
+            .collect::<Vec<_>>();
+        {
+            let mut symbols_map = self.symbols.borrow_mut();
+            for exchange_symbol in &exchange_symbols {
+                symbols_map.remove(exchange_symbol);
+            }
+        }
+        self.watchdog.borrow_mut().forget(symbols.iter());
+        self.send_method("UNSUBSCRIBE", self.next_request_id(), exchange_symbols)
+    }
+
+    /// There is no reset method, all the current streams are unsubscribed
+    fn reset(&self) -> LocalBoxFuture<'_, MarketResult<()>> {
+        *self.watchdog.borrow_mut() = FirstPriceWatchdog::default();
+        let exchange_symbols = self
+            .symbols
+            .borrow_mut()
+            .drain()
+            .map(|(key, _)| key)
+            .collect();
+        self.send_method("UNSUBSCRIBE", self.next_request_id(), exchange_symbols)
+    }
+
+    fn next_frame(&self) -> LocalBoxFuture<'_, Option<MarketResult<String>>> {
+        self.socket.next_message().boxed_local()
+    }
+
+    fn parse_frame(&self, frame: &str) -> MarketResult<FeedEvent> {
+        let message: StreamMessage = serde_json::from_str(frame)?;
+        let Some(exchange_symbol) = message.symbol.as_ref() else {
+            // {"result":null,"id":1} is the answer to SUBSCRIBE, the symbols are reported
+            // by their first prices. {"error":{"code":2,"msg":"..."},"id":1} rejects the whole request.
+            let symbols = message
+                .id
+                .and_then(|id| self.subscriptions.borrow_mut().remove(&id));
+            return Ok(match symbols {
+                Some(symbols) if message.error.is_some() => {
+                    self.watchdog.borrow_mut().forget(symbols.iter());
+                    FeedEvent::SubscribeStatus(SubscribeStatus {
+                        success: Vec::new(),
+                        fails: symbols,
+                    })
+                }
+                Some(_) => FeedEvent::SubscribeStatus(SubscribeStatus::default()),
+                None => FeedEvent::Unknown(frame.to_owned()),
+            });
+        };
+        let Some(symbol) = self.symbols.borrow().get(exchange_symbol).cloned() else {
+            return Ok(FeedEvent::Unknown(frame.to_owned()));
+        };
+        let tick = price_tick(&message, symbol, js_sys::Date::now())?;
+        self.watchdog.borrow_mut().priced(&tick.symbol);
+        Ok(FeedEvent::Price(tick))
+    }
+
+    /// The exchange pings the client itself and the browser answers automatically
+    fn keep_alive(&self) -> LocalBoxFuture<'_, MarketResult<()>> {
+        future::pending().boxed_local()
+    }
+    /// Symbols that delivered their first price or stayed silent for `FIRST_PRICE_TIMEOUT_MS`
+    fn poll_status(&self, now: f64) -> Option<FeedEvent> {
+        self.watchdog
+            .borrow_mut()
+            .poll(now)
+            .map(FeedEvent::SubscribeStatus)
+    }
+}
+
+/// First prices of the subscribed symbols
+#[derive(Default, Debug)]
+struct FirstPriceWatchdog {
+    /// Deadlines of the symbols without a price yet, milliseconds
+    waiting: HashMap<String, f64>,
+    /// Symbols reported as failed, a late price still reports them
+    silent: HashSet<String>,
+    /// Symbols priced since the last poll
+    priced: Vec<String>,
+}
+
+impl FirstPriceWatchdog {
+    fn watch(&mut self, symbols: impl Iterator<Item = String>, now: f64) {
+        for symbol in symbols {
+            self.silent.remove(&symbol);
+            self.waiting.insert(symbol, now + FIRST_PRICE_TIMEOUT_MS);
+        }
+    }
+
+    fn forget<'a>(&mut self, symbols: impl Iterator<Item = &'a String>) {
+        for symbol in symbols {
+            self.waiting.remove(symbol);
+            self.silent.remove(symbol);
+            self.priced.retain(|priced| priced != symbol);
+        }
+    }
+
+    fn priced(&mut self, symbol: &str) {
+        if self.waiting.remove(symbol).is_some() || self.silent.remove(symbol) {
+            self.priced.push(symbol.to_owned());
+        }
+    }
+    /// Symbols priced since the last poll and the ones past the deadline, `None` if there are none
+    fn poll(&mut self, now: f64) -> Option<SubscribeStatus> {
+        let mut fails: Vec<String> = self
+            .waiting
+            .iter()
+            .filter(|(_, deadline)| now >= **deadline)
+            .map(|(symbol, _)| symbol.clone())
+            .collect();
+        for symbol in &fails {
+            self.waiting.remove(symbol);
+            self.silent.insert(symbol.clone());
+        }
+        fails.sort();
+        let success = std::mem::take(&mut self.priced);
+        (!success.is_empty() || !fails.is_empty()).then_some(SubscribeStatus { success, fails })
+    }
+}
+
+/// bookTicker and 24hrTicker stream payload
+#[derive(Deserialize, Debug)]
+struct StreamMessage {
+    /// Exchange symbol
+    #[serde(rename = "s")]
+    symbol: Option<String>,
+    /// Best bid price
+    #[serde(rename = "b")]
+    bid: Option<String>,
+    /// Best ask price
+    #[serde(rename = "a")]
+    ask: Option<String>,
+    /// Last price, ticker stream only
+    #[serde(rename = "c")]
+    last_price: Option<String>,
+    /// Base asset volume of the last 24 hours, ticker stream only
+    #[serde(rename = "v")]
+    volume: Option<String>,
+    /// Event time in milliseconds, ticker stream only
+    #[serde(rename = "E")]
+    event_time: Option<i64>,
+    /// Request identifier of a method response
+    id: Option<u64>,
+    /// Rejection of a method request
+    error: Option<serde_json::Value>,
+}
+
+impl BinancePriceFeed {
+    fn next_request_id(&self) -> u64 {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        id
+    }
+    /// Sending a stream method request, e.g. SUBSCRIBE of "btcusdt@bookTicker"
+    fn send_method(
+        &self,
+        method: &str,
+        id: u64,
+        exchange_symbols: Vec<String>,
+    ) -> LocalBoxFuture<'_, MarketResult<()>> {
+        let stream_suffix = match self.stream {
+            BinanceStream::BookTicker => "bookTicker",
+            BinanceStream::Ticker => "ticker",
+        };
+        let streams = exchange_symbols
+            .iter()
+            .map(|symbol| format!("{}@{}", symbol.to_lowercase(), stream_suffix))
+            .collect::<Vec<_>>();
+        let msg = serde_json::json!({
+            "method": method,
+            "params": streams,
+            "id": id,
+        })
+        .to_string();
+        self.socket.send(msg).boxed_local()
+    }
+}
+
+/// Converting application symbol to exchange symbol, "BTC/USD" -> "BTCUSDT"
+fn to_exchange_symbol(symbol: &str) -> String {
+    match symbol.split_once('/') {
+        Some((base, quote)) => {
+            let quote = FIAT_QUOTE_ASSETS
+                .iter()
+                .find(|(fiat, _)| *fiat == quote)
+                .map_or(quote, |(_, asset)| asset);
+            format!("{base}{quote}")
+        }
+        None => symbol.to_owned(),
+    }
+}
+
+/// Price tick of a stream payload, the browser time in milliseconds stamps bookTicker payloads
+fn price_tick(message: &StreamMessage, symbol: String, now: f64) -> MarketResult<PriceTick> {
+    let bid = parse_number(message.bid.as_deref())?;
+    let ask = parse_number(message.ask.as_deref())?;
+    // bookTicker has no last price, the mid price is used instead
+    let price = match message.last_price.as_deref() {
+        Some(last_price) => parse_number(Some(last_price))?,
+        None => (bid + ask) / 2.0,
+    };
+    let timestamp = match message.event_time {
+        Some(event_time) => event_time / 1000,
+        None => (now / 1000.0) as i64,
+    };
+    Ok(PriceTick {
+        symbol,
+        price,
+        bid,
+        ask,
+        day_volume: parse_number(message.volume.as_deref())?,
+        timestamp,
+    })
+}
+
+fn parse_number(value: Option<&str>) -> MarketResult<f64> {
+    match value {
+        Some(value) => value.parse::<f64>().map_err(|_| {
+            MarketError::General(format!("Invalid number in Binance stream: {value}"))
+        }),
+        None => Ok(0.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::enums::QuoteType;
+    use crate::common::precision::PrecisionTracker;
+
+    const BOOK_TICKER_FRAMES: &str =
+        include_str!("../../../../aggregator-server/fixtures/binance_book_ticker.ndjson");
+    const TICKER_FRAMES: &str =
+        include_str!("../../../../aggregator-server/fixtures/binance_ticker.ndjson");
+    const NOW: f64 = 1_718_611_200_000.0;
+
+    fn parse_fixture(frames: &str) -> Vec<PriceTick> {
+        frames
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let message: StreamMessage = serde_json::from_str(line).unwrap();
+                let symbol = message.symbol.clone().unwrap();
+                price_tick(&message, symbol, NOW).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn book_ticker_frames_are_priced_at_the_mid() {
+        let ticks = parse_fixture(BOOK_TICKER_FRAMES);
+        assert_eq!(ticks.len(), 200);
+        for tick in &ticks {
+            assert!(tick.bid > 0.0 && tick.ask >= tick.bid, "{tick:?}");
+            assert_eq!(tick.price, (tick.bid + tick.ask) / 2.0);
+            assert_eq!(tick.timestamp, (NOW / 1000.0) as i64);
+            assert_eq!(tick.day_volume, 0.0);
+        }
+        assert_eq!(ticks[0].symbol, "BTCUSDT");
+        assert_eq!(ticks[0].bid, 67407.24);
+    }
+
+    #[test]
+    fn recorded_ticks_keep_the_exchange_precision() {
+        let mut precision = PrecisionTracker::default();
+        for tick in parse_fixture(BOOK_TICKER_FRAMES) {
+            precision.observe_tick(&tick.symbol, tick.price, tick.bid, tick.ask);
+        }
+        let decimals = |symbol| precision.decimals(symbol, QuoteType::CryptoCurrency, None);
+        assert_eq!(decimals("BTCUSDT"), 2);
+        assert_eq!(decimals("EOSUSDT"), 4);
+        assert_eq!(decimals("ETHBTC"), 5);
+    }
+
+    #[test]
+    fn ticker_frames_have_the_last_price_and_volume() {
+        let ticks = parse_fixture(TICKER_FRAMES);
+        assert_eq!(ticks.len(), 40);
+        for tick in &ticks {
+            assert!(tick.price >= tick.bid && tick.price <= tick.ask, "{tick:?}");
+            assert!(tick.day_volume > 0.0);
+        }
+        assert_eq!(ticks[0].price, 67407.25);
+        assert_eq!(ticks[0].timestamp, 1_718_611_201);
+    }
+
+    #[test]
+    fn exchange_symbols_use_the_stable_coin() {
+        assert_eq!(to_exchange_symbol("BTC/USD"), "BTCUSDT");
+        assert_eq!(to_exchange_symbol("ETH/BTC"), "ETHBTC");
+        assert_eq!(to_exchange_symbol("BNBUSDT"), "BNBUSDT");
+    }
+
+    #[test]
+    fn silent_symbols_fail_after_the_timeout() {
+        let mut watchdog = FirstPriceWatchdog::default();
+        let symbols = ["BTC/USD", "XYZ/USD", "OLD/USD"].map(str::to_owned);
+        watchdog.watch(symbols.iter().cloned(), NOW);
+        assert_eq!(watchdog.poll(NOW + 1000.0), None);
+
+        watchdog.priced("BTC/USD");
+        watchdog.priced("BTC/USD");
+        watchdog.forget([&symbols[2]].into_iter());
+        assert_eq!(
+            watchdog.poll(NOW + 2000.0),
+            Some(SubscribeStatus {
+                success: vec!["BTC/USD".to_owned()],
+                fails: Vec::new(),
+            })
+        );
+        assert_eq!(
+            watchdog.poll(NOW + FIRST_PRICE_TIMEOUT_MS),
+            Some(SubscribeStatus {
+                success: Vec::new(),
+                fails: vec!["XYZ/USD".to_owned()],
+            })
+        );
+        assert_eq!(watchdog.poll(NOW + 2.0 * FIRST_PRICE_TIMEOUT_MS), None);
+
+        // a late price clears the failure
+        watchdog.priced("XYZ/USD");
+        assert_eq!(
+            watchdog.poll(NOW + 2.0 * FIRST_PRICE_TIMEOUT_MS),
+            Some(SubscribeStatus {
+                success: vec!["XYZ/USD".to_owned()],
+                fails: Vec::new(),
+            })
+        );
+    }
+}
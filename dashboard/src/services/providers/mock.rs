@@ -0,0 +1,468 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::rc::Rc;
+use std::time::Duration;
+
+use futures::future::{self, LocalBoxFuture};
+use futures::FutureExt;
+use gloo_net::http::Request;
+use log::info;
+use yew::platform::time::sleep;
+
+use crate::common::app_config::AppConfig;
+use crate::common::entities::{
+    EndOfDay, Indices, IndicesReferenceData, Quote, SessionFrame, Stock, SymbolMatch, TimeSeries,
+    TimeSeriesMeta, TimeSeriesValue, UsStocksReferenceData,
+};
+use crate::common::enums::{FeedEvent, TimeSeriesInterval};
+use crate::common::error::MarketError;
+use crate::common::utils::bar_start;
+use crate::common::MarketResult;
+use crate::services::providers::twelvedata;
+use crate::services::providers::{MarketDataProvider, PriceFeed};
+
+/// Recorded `/indices` response
+const INDICES_FIXTURE: &str = include_str!("../../../fixtures/indices.json");
+/// Recorded `/stocks` response
+const STOCKS_FIXTURE: &str = include_str!("../../../fixtures/stocks.json");
+/// Recorded `/eod` response
+const EOD_FIXTURE: &str = include_str!("../../../fixtures/eod.json");
+/// Recorded `/quote` response
+const QUOTE_FIXTURE: &str = include_str!("../../../fixtures/quote.json");
+/// Recorded Twelve Data price web socket session
+const SESSION_FIXTURE: &str = include_str!("../../../fixtures/price_session.ndjson");
+
+/// Offline provider: reference data and quotes come from the bundled fixtures,
+/// the price feed replays a recorded Twelve Data session in a loop
+pub struct MockProvider {
+    config: Rc<AppConfig>,
+}
+
+impl MockProvider {
+    pub fn new(config: Rc<AppConfig>) -> Self {
+        Self { config }
+    }
+}
+
+impl MarketDataProvider for MockProvider {
+    fn get_indices(&self) -> LocalBoxFuture<'_, MarketResult<HashMap<String, Indices>>> {
+        let indices = serde_json::from_str::<IndicesReferenceData>(INDICES_FIXTURE)
+            .map(|data| {
+                data.data
+                    .into_iter()
+                    .map(|value| (value.symbol.clone(), value))
+                    .collect()
+            })
+            .map_err(MarketError::from);
+        future::ready(indices).boxed_local()
+    }
+
+    fn get_us_stocks(&self) -> LocalBoxFuture<'_, MarketResult<HashMap<String, Stock>>> {
+        let stocks = serde_json::from_str::<UsStocksReferenceData>(STOCKS_FIXTURE)
+            .map(|data| {
+                data.data
+                    .into_iter()
+                    .map(|value| (value.symbol.clone(), value))
+                    .collect()
+            })
+            .map_err(MarketError::from);
+        future::ready(stocks).boxed_local()
+    }
+
+    fn get_end_of_day_data(
+        &self,
+        symbols: HashSet<String>,
+    ) -> LocalBoxFuture<'_, MarketResult<HashMap<String, EndOfDay>>> {
+        future::ready(select_symbols(EOD_FIXTURE, &symbols)).boxed_local()
+    }
+
+    fn get_last_quote(
+        &self,
+        symbols: HashSet<String>,
+    ) -> LocalBoxFuture<'_, MarketResult<HashMap<String, Quote>>> {
+        future::ready(select_symbols(QUOTE_FIXTURE, &symbols)).boxed_local()
+    }
+
+    fn get_time_series(
+        &self,
+        symbol: String,
+        interval: TimeSeriesInterval,
+        output_size: u32,
+    ) -> LocalBoxFuture<'_, MarketResult<TimeSeries>> {
+        let time_series = select_symbols::<Quote>(QUOTE_FIXTURE, &HashSet::from([symbol.clone()]))
+            .and_then(|mut quotes| {
+                quotes
+                    .remove(&symbol)
+                    .ok_or_else(|| MarketError::General(format!("No recorded quote of {symbol}")))
+            })
+            .map(|quote| generate_time_series(&quote, interval, output_size));
+        future::ready(time_series).boxed_local()
+    }
+
+    fn search_symbols(
+        &self,
+        query: String,
+        output_size: u32,
+    ) -> LocalBoxFuture<'_, MarketResult<Vec<SymbolMatch>>> {
+        future::ready(search_fixtures(&query, output_size as usize)).boxed_local()
+    }
+
+    fn open_price_feed(&self) -> MarketResult<Box<dyn PriceFeed>> {
+        Ok(Box::new(MockPriceFeed {
+            session_url: self.config.mock_session_url.clone(),
+            speed: self.config.mock_replay_speed,
+            frames: RefCell::new(Rc::new(Vec::new())),
+            position: Cell::new(0),
+            symbols: RefCell::new(HashSet::new()),
+            statuses: RefCell::new(VecDeque::new()),
+        }))
+    }
+}
+
+/// Replayed price session. Frames are delivered with the recorded intervals
+/// divided by the replay speed, prices of not subscribed symbols are skipped.
+/// Price timestamps are moved to the replay time, so every loop continues the candles.
+pub struct MockPriceFeed {
+    session_url: String,
+    speed: f64,
+    frames: RefCell<Rc<Vec<SessionFrame>>>,
+    position: Cell<usize>,
+    symbols: RefCell<HashSet<String>>,
+    /// Subscription statuses delivered before the next recorded frame
+    statuses: RefCell<VecDeque<String>>,
+}
+
+impl MockPriceFeed {
+    /// Next frame of the session and the recorded interval before it, starting over at the end
+    fn advance(&self) -> Option<(SessionFrame, Duration)> {
+        let frames = self.frames.borrow().clone();
+        let position = self.position.get();
+        let frame = frames.get(position)?;
+        self.position.set((position + 1) % frames.len());
+        let interval = match position
+            .checked_sub(1)
+            .and_then(|previous| frames.get(previous))
+        {
+            Some(previous) => frame.received_at - previous.received_at,
+            // the pause between the end of the session and its start again
+            None => frames
+                .get(1)
+                .map_or(0.0, |next| next.received_at - frame.received_at),
+        };
+        let delay = Duration::from_secs_f64((interval / self.speed).max(0.0) / 1000.0);
+        Some((frame.clone(), delay))
+    }
+
+    /// Symbols with prices in the session
+    fn recorded_symbols(&self) -> HashSet<String> {
+        self.frames
+            .borrow()
+            .iter()
+            .filter_map(|frame| match twelvedata::parse_frame(&frame.frame) {
+                Ok(FeedEvent::Price(tick)) => Some(tick.symbol),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn is_subscribed(&self, frame: &str) -> bool {
+        match twelvedata::parse_frame(frame) {
+            Ok(FeedEvent::Price(tick)) => self.symbols.borrow().contains(&tick.symbol),
+            // the statuses are generated for the current subscription
+            Ok(FeedEvent::SubscribeStatus(_)) => false,
+            _ => true,
+        }
+    }
+}
+
+impl PriceFeed for MockPriceFeed {
+    /// Loading the session on the first subscription. Symbols without recorded prices fail.
+    fn subscribe(&self, symbols: HashSet<String>) -> LocalBoxFuture<'_, MarketResult<()>> {
+        async move {
+            if self.frames.borrow().is_empty() {
+                let frames = load_session(&self.session_url).await?;
+                info!(
+                    "Replaying {} recorded frames at speed {}",
+                    frames.len(),
+                    self.speed
+                );
+                *self.frames.borrow_mut() = Rc::new(frames);
+            }
+            let recorded = self.recorded_symbols();
+            let (success, fails): (Vec<_>, Vec<_>) = symbols
+                .iter()
+                .partition(|symbol| recorded.contains(*symbol));
+            let status = serde_json::json!({
+                "event": "subscribe-status",
+                "status": if fails.is_empty() { "ok" } else { "error" },
+                "success": success.iter().map(|symbol| serde_json::json!({ "symbol": symbol })).collect::<Vec<_>>(),
+                "fails": fails.iter().map(|symbol| serde_json::json!({ "symbol": symbol })).collect::<Vec<_>>(),
+            });
+            self.statuses.borrow_mut().push_back(status.to_string());
+            self.symbols.borrow_mut().extend(symbols);
+            Ok(())
+        }
+        .boxed_local()
+    }
+
+    fn unsubscribe(&self, symbols: HashSet<String>) -> LocalBoxFuture<'_, MarketResult<()>> {
+        self.symbols
+            .borrow_mut()
+            .retain(|symbol| !symbols.contains(symbol));
+        future::ready(Ok(())).boxed_local()
+    }
+
+    fn reset(&self) -> LocalBoxFuture<'_, MarketResult<()>> {
+        self.symbols.borrow_mut().clear();
+        future::ready(Ok(())).boxed_local()
+    }
+
+    fn next_frame(&self) -> LocalBoxFuture<'_, Option<MarketResult<String>>> {
+        async move {
+            if let Some(status) = self.statuses.borrow_mut().pop_front() {
+                return Some(Ok(status));
+            }
+            loop {
+                let (frame, delay) = self.advance()?;
+                sleep(delay).await;
+                if self.is_subscribed(&frame.frame) {
+                    return Some(Ok(rebase_frame(&frame, js_sys::Date::now())));
+                }
+            }
+        }
+        .boxed_local()
+    }
+
+    fn parse_frame(&self, frame: &str) -> MarketResult<FeedEvent> {
+        twelvedata::parse_frame(frame)
+    }
+
+    /// Nothing to keep alive, the session is local
+    fn keep_alive(&self) -> LocalBoxFuture<'_, MarketResult<()>> {
+        future::pending().boxed_local()
+    }
+
+    fn is_replayable(&self) -> bool {
+        true
+    }
+}
+
+/// The frame with the price timestamp moved by the time since its recording, the browser
+/// time in milliseconds. Other frames are unchanged.
+fn rebase_frame(frame: &SessionFrame, now: f64) -> String {
+    let Ok(mut value) = serde_json::from_str::<serde_json::Value>(&frame.frame) else {
+        return frame.frame.clone();
+    };
+    let shift = ((now - frame.received_at) / 1000.0).round() as i64;
+    match value.get_mut("timestamp") {
+        Some(timestamp) if timestamp.is_i64() => {
+            *timestamp = (timestamp.as_i64().unwrap_or_default() + shift).into();
+            value.to_string()
+        }
+        _ => frame.frame.clone(),
+    }
+}
+
+/// Loading the recorded session from the URL or the bundled recording
+async fn load_session(session_url: &str) -> MarketResult<Vec<SessionFrame>> {
+    let frames = if session_url.is_empty() {
+        parse_session(SESSION_FIXTURE)?
+    } else {
+        let response = Request::get(session_url).send().await?;
+        if !response.ok() {
+            return Err(MarketError::General(format!(
+                "Recorded session {} is not available (status {})",
+                session_url,
+                response.status()
+            )));
+        }
+        parse_session(response.text().await?.as_str())?
+    };
+    if frames.is_empty() {
+        return Err(MarketError::General("Recorded session is empty".to_owned()));
+    }
+    Ok(frames)
+}
+
+/// Parsing a recorded session, one JSON frame per line
+pub fn parse_session(text: &str) -> MarketResult<Vec<SessionFrame>> {
+    let mut frames = Vec::new();
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        frames.push(serde_json::from_str(line)?);
+    }
+    Ok(frames)
+}
+
+/// Entries of a by-symbol fixture for the requested symbols
+fn select_symbols<T: serde::de::DeserializeOwned>(
+    fixture: &str,
+    symbols: &HashSet<String>,
+) -> MarketResult<HashMap<String, T>> {
+    let mut data: HashMap<String, T> = serde_json::from_str(fixture)?;
+    data.retain(|symbol, _| symbols.contains(symbol));
+    Ok(data)
+}
+
+/// Instruments of the fixtures matching the query: symbol matches first, then name matches
+fn search_fixtures(query: &str, output_size: usize) -> MarketResult<Vec<SymbolMatch>> {
+    let indices = serde_json::from_str::<IndicesReferenceData>(INDICES_FIXTURE)?.data;
+    let stocks = serde_json::from_str::<UsStocksReferenceData>(STOCKS_FIXTURE)?.data;
+    let quotes: HashMap<String, Quote> = serde_json::from_str(QUOTE_FIXTURE)?;
+
+    let instruments = indices
+        .into_iter()
+        .map(|index| SymbolMatch {
+            symbol: index.symbol,
+            name: index.name,
+            exchange: index.exchange,
+            mic_code: index.mic_code,
+            instrument_type: "Index".to_owned(),
+            country: index.country,
+        })
+        .chain(stocks.into_iter().map(|stock| SymbolMatch {
+            symbol: stock.symbol,
+            name: stock.name,
+            exchange: stock.exchange,
+            mic_code: stock.mic_code,
+            instrument_type: "Common Stock".to_owned(),
+            country: stock.country,
+        }))
+        .chain(
+            quotes
+                .into_values()
+                .filter(|quote| quote.symbol.contains('/'))
+                .map(|quote| SymbolMatch {
+                    instrument_type: if quote.exchange == "Forex" {
+                        "Physical Currency".to_owned()
+                    } else {
+                        "Digital Currency".to_owned()
+                    },
+                    symbol: quote.symbol,
+                    name: quote.name,
+                    exchange: quote.exchange,
+                    mic_code: quote.mic_code,
+                    country: String::new(),
+                }),
+        );
+
+    let query = query.trim().to_uppercase();
+    let mut found: Vec<(u8, SymbolMatch)> = Vec::new();
+    for instrument in instruments {
+        let rank = if instrument.symbol == query {
+            0
+        } else if instrument.symbol.starts_with(&query) {
+            1
+        } else if instrument.name.to_uppercase().contains(&query) {
+            2
+        } else {
+            continue;
+        };
+        if !found
+            .iter()
+            .any(|(_, other)| other.symbol == instrument.symbol)
+        {
+            found.push((rank, instrument));
+        }
+    }
+    found.sort_by(|(rank, a), (other_rank, b)| rank.cmp(other_rank).then(a.symbol.cmp(&b.symbol)));
+    Ok(found
+        .into_iter()
+        .take(output_size)
+        .map(|(_, instrument)| instrument)
+        .collect())
+}
+
+/// Deterministic random walk ending at the recorded quote close, the newest bar first
+fn generate_time_series(
+    quote: &Quote,
+    interval: TimeSeriesInterval,
+    output_size: u32,
+) -> TimeSeries {
+    let mut random = seed(&format!("{}{}", quote.symbol, interval.api_name()));
+    let mut close = quote.close.to_f64().unwrap_or(1.0);
+    let mut time = bar_start(interval, quote.timestamp);
+    let step = (((time - bar_start(interval, time - 1)) as f64) / 60.0).sqrt() * 0.0005;
+    let volatility = step.min(0.05);
+    let mut values = Vec::new();
+    for _ in 0..output_size {
+        let open = close * (1.0 + volatility * (next_random(&mut random) - 0.5) * 2.0);
+        let high = open.max(close) * (1.0 + volatility * next_random(&mut random) / 2.0);
+        let low = open.min(close) * (1.0 - volatility * next_random(&mut random) / 2.0);
+        let datetime = chrono::DateTime::from_timestamp(time, 0)
+            .unwrap_or_default()
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+        values.push(TimeSeriesValue {
+            datetime,
+            open: format!("{open:.5}"),
+            high: format!("{high:.5}"),
+            low: format!("{low:.5}"),
+            close: format!("{close:.5}"),
+            volume: String::new(),
+        });
+        close = open;
+        time = bar_start(interval, time - 1);
+    }
+    TimeSeries {
+        meta: TimeSeriesMeta {
+            symbol: quote.symbol.clone(),
+            interval: interval.api_name().to_owned(),
+            currency: quote.currency.clone(),
+            exchange_timezone: "UTC".to_owned(),
+            exchange: quote.exchange.clone(),
+            instrument_type: String::new(),
+        },
+        values,
+    }
+}
+
+/// FNV-1a hash as the random seed
+fn seed(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Xorshift random number in [0, 1)
+fn next_random(state: &mut u64) -> f64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    (*state >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn price_timestamp_moves_to_the_replay_time() {
+        let frames = parse_session(SESSION_FIXTURE).unwrap();
+        let (index, recorded) = frames
+            .iter()
+            .enumerate()
+            .find_map(
+                |(index, frame)| match twelvedata::parse_frame(&frame.frame) {
+                    Ok(FeedEvent::Price(tick)) => Some((index, tick)),
+                    _ => None,
+                },
+            )
+            .unwrap();
+        let frame = &frames[index];
+        // a day and an hour later, every loop replays later still
+        let now = frame.received_at + 90_000_000.0;
+        let Ok(FeedEvent::Price(tick)) = twelvedata::parse_frame(&rebase_frame(frame, now)) else {
+            panic!("price frame expected");
+        };
+        assert_eq!(tick.timestamp, recorded.timestamp + 90_000);
+        assert_eq!(tick.price, recorded.price);
+        assert_eq!(tick.symbol, recorded.symbol);
+    }
+
+    #[test]
+    fn status_frames_are_not_changed() {
+        let frames = parse_session(SESSION_FIXTURE).unwrap();
+        let status = &frames[0];
+        assert_eq!(rebase_frame(status, status.received_at + 1e9), status.frame);
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Deref;
+use std::rc::Rc;
+
+use futures::future::LocalBoxFuture;
+
+use crate::common::app_config::AppConfig;
+use crate::common::entities::{EndOfDay, Indices, Quote, Stock, SymbolMatch, TimeSeries};
+use crate::common::enums::{FeedEvent, ProviderKind, QuoteType, TimeSeriesInterval};
+use crate::common::MarketResult;
+use crate::services::providers::binance::BinanceProvider;
+use crate::services::providers::mock::MockProvider;
+use crate::services::providers::twelvedata::TwelveDataProvider;
+
+/// Binance provider
+pub mod binance;
+/// Offline mock provider
+pub mod mock;
+/// Twelve Data provider
+pub mod twelvedata;
+
+/// Market data vendor: reference data, last quote, end of day prices and a streaming price feed
+pub trait MarketDataProvider {
+    /// Indices reference data, by symbol
+    fn get_indices(&self) -> LocalBoxFuture<'_, MarketResult<HashMap<String, Indices>>>;
+    /// US stocks reference data, by symbol
+    fn get_us_stocks(&self) -> LocalBoxFuture<'_, MarketResult<HashMap<String, Stock>>>;
+    /// The latest End of Day (EOD) prices, by symbol
+    fn get_end_of_day_data(
+        &self,
+        symbols: HashSet<String>,
+    ) -> LocalBoxFuture<'_, MarketResult<HashMap<String, EndOfDay>>>;
+    /// The latest quotes, by symbol
+    fn get_last_quote(
+        &self,
+        symbols: HashSet<String>,
+    ) -> LocalBoxFuture<'_, MarketResult<HashMap<String, Quote>>>;
+    /// Historical bars of the instrument, the newest `output_size` bars
+    fn get_time_series(
+        &self,
+        symbol: String,
+        interval: TimeSeriesInterval,
+        output_size: u32,
+    ) -> LocalBoxFuture<'_, MarketResult<TimeSeries>>;
+    /// Instruments matching the symbol or name, at most `output_size` of them
+    fn search_symbols(
+        &self,
+        query: String,
+        output_size: u32,
+    ) -> LocalBoxFuture<'_, MarketResult<Vec<SymbolMatch>>>;
+    /// Opening a new streaming price feed connection
+    fn open_price_feed(&self) -> MarketResult<Box<dyn PriceFeed>>;
+}
+
+/// Single connection of a streaming price feed
+pub trait PriceFeed {
+    /// Subscribing to real-time prices. Resolves once the subscription is sent.
+    fn subscribe(&self, symbols: HashSet<String>) -> LocalBoxFuture<'_, MarketResult<()>>;
+    /// Stopping real-time prices of the symbols. Resolves once the request is sent.
+    fn unsubscribe(&self, symbols: HashSet<String>) -> LocalBoxFuture<'_, MarketResult<()>>;
+    /// Stopping real-time prices of all symbols. Resolves once the request is sent.
+    fn reset(&self) -> LocalBoxFuture<'_, MarketResult<()>>;
+    /// Next raw frame, `None` when the connection is closed
+    fn next_frame(&self) -> LocalBoxFuture<'_, Option<MarketResult<String>>>;
+    /// Converting a raw frame into a provider independent event
+    fn parse_frame(&self, frame: &str) -> MarketResult<FeedEvent>;
+    /// Keeping the connection alive. Resolves with the first error, the connection is dead after that.
+    fn keep_alive(&self) -> LocalBoxFuture<'_, MarketResult<()>>;
+    /// Event raised without a frame, e.g. symbols that never delivered a price.
+    /// Polled by the supervisor every second with the browser time in milliseconds.
+    fn poll_status(&self, _now: f64) -> Option<FeedEvent> {
+        None
+    }
+    /// Frames in the Twelve Data format, the only one the mock provider replays
+    fn is_replayable(&self) -> bool {
+        false
+    }
+}
+
+/// Market data providers selected by the configuration. Reference data and quotes come
+/// from the primary provider, price streams may be routed to other providers by quote type.
+#[derive(Clone)]
+pub struct ProviderRef {
+    primary: Rc<dyn MarketDataProvider>,
+    streams: Rc<HashMap<QuoteType, Rc<dyn MarketDataProvider>>>,
+}
+
+impl ProviderRef {
+    /// Creating the providers selected by the configuration
+    pub fn from_config(config: Rc<AppConfig>) -> Self {
+        let primary = create_provider(config.provider, config.clone());
+        let mut streams = HashMap::new();
+        if let Some(crypto_provider) = config.crypto_provider {
+            if crypto_provider != config.provider {
+                streams.insert(
+                    QuoteType::CryptoCurrency,
+                    create_provider(crypto_provider, config.clone()),
+                );
+            }
+        }
+        Self {
+            primary,
+            streams: Rc::new(streams),
+        }
+    }
+    /// Provider of the price stream for the quote type
+    pub fn stream_provider(&self, quote_type: QuoteType) -> Rc<dyn MarketDataProvider> {
+        self.streams
+            .get(&quote_type)
+            .unwrap_or(&self.primary)
+            .clone()
+    }
+}
+
+impl Deref for ProviderRef {
+    type Target = dyn MarketDataProvider;
+
+    fn deref(&self) -> &Self::Target {
+        self.primary.as_ref()
+    }
+}
+
+impl PartialEq for ProviderRef {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.primary, &other.primary) && Rc::ptr_eq(&self.streams, &other.streams)
+    }
+}
+
+fn create_provider(kind: ProviderKind, config: Rc<AppConfig>) -> Rc<dyn MarketDataProvider> {
+    match kind {
+        ProviderKind::TwelveData => Rc::new(TwelveDataProvider::new(config)),
+        ProviderKind::Binance => Rc::new(BinanceProvider::new(config)),
+        ProviderKind::Mock => Rc::new(MockProvider::new(config)),
+    }
+}
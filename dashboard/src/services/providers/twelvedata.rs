@@ -0,0 +1,329 @@
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::time::Duration;
+
+use futures::future::LocalBoxFuture;
+use futures::FutureExt;
+use serde::{Deserialize, Serialize};
+use yew::platform::time::sleep;
+
+use crate::common::app_config::AppConfig;
+use crate::common::entities::{
+    EndOfDay, Indices, PriceTick, Quote, Stock, SubscribeStatus, SymbolMatch, TimeSeries,
+};
+use crate::common::enums::{FeedEvent, TimeSeriesInterval};
+use crate::common::utils::prepare_symbols_for_url;
+use crate::common::MarketResult;
+use crate::services::providers::{MarketDataProvider, PriceFeed};
+use crate::services::restapi::RestApiService;
+use crate::services::websocket::WebSocketService;
+
+const NINE_SEC: Duration = Duration::from_secs(9);
+
+/// Twelve Data Market Data Provider
+pub struct TwelveDataProvider {
+    config: Rc<AppConfig>,
+    rest_api: RestApiService,
+}
+
+impl TwelveDataProvider {
+    pub fn new(config: Rc<AppConfig>) -> Self {
+        Self {
+            rest_api: RestApiService::new(config.clone()),
+            config,
+        }
+    }
+}
+
+impl MarketDataProvider for TwelveDataProvider {
+    fn get_indices(&self) -> LocalBoxFuture<'_, MarketResult<HashMap<String, Indices>>> {
+        self.rest_api.get_indices().boxed_local()
+    }
+
+    fn get_us_stocks(&self) -> LocalBoxFuture<'_, MarketResult<HashMap<String, Stock>>> {
+        self.rest_api.get_us_stocks().boxed_local()
+    }
+
+    fn get_end_of_day_data(
+        &self,
+        symbols: HashSet<String>,
+    ) -> LocalBoxFuture<'_, MarketResult<HashMap<String, EndOfDay>>> {
+        self.rest_api.get_end_of_day_data(symbols).boxed_local()
+    }
+
+    fn get_last_quote(
+        &self,
+        symbols: HashSet<String>,
+    ) -> LocalBoxFuture<'_, MarketResult<HashMap<String, Quote>>> {
+        self.rest_api.get_last_quote(symbols).boxed_local()
+    }
+
+    fn get_time_series(
+        &self,
+        symbol: String,
+        interval: TimeSeriesInterval,
+        output_size: u32,
+    ) -> LocalBoxFuture<'_, MarketResult<TimeSeries>> {
+        self.rest_api
+            .get_time_series(symbol, interval, output_size)
+            .boxed_local()
+    }
+
+    fn search_symbols(
+        &self,
+        query: String,
+        output_size: u32,
+    ) -> LocalBoxFuture<'_, MarketResult<Vec<SymbolMatch>>> {
+        self.rest_api
+            .symbol_search(query, output_size)
+            .boxed_local()
+    }
+
+    fn open_price_feed(&self) -> MarketResult<Box<dyn PriceFeed>> {
+        let url = format!(
+            "{}{}?apikey={}",
+            self.config.ws_address, self.config.real_time_price_route, self.config.api_key
+        );
+        let socket = WebSocketService::open_ws_connection(url.as_str())?;
+        Ok(Box::new(TwelveDataPriceFeed { socket }))
+    }
+}
+
+/// Twelve Data real-time price web socket
+pub struct TwelveDataPriceFeed {
+    socket: WebSocketService,
+}
+
+impl PriceFeed for TwelveDataPriceFeed {
+    fn subscribe(&self, symbols: HashSet<String>) -> LocalBoxFuture<'_, MarketResult<()>> {
+        let msg = format!(
+            "{{
+                \"action\": \"subscribe\",
+                \"params\": {{
+                \"symbols\": \"{}\"
+                            }}
+        }}",
+            prepare_symbols_for_url(symbols)
+        );
+        self.socket.send(msg).boxed_local()
+    }
+
+    fn unsubscribe(&self, symbols: HashSet<String>) -> LocalBoxFuture<'_, MarketResult<()>> {
+        let msg = serde_json::json!({
+            "action": "unsubscribe",
+            "params": { "symbols": prepare_symbols_for_url(symbols) },
+        })
+        .to_string();
+        self.socket.send(msg).boxed_local()
+    }
+
+    fn reset(&self) -> LocalBoxFuture<'_, MarketResult<()>> {
+        let msg = r#"{"action": "reset"}"#.to_string();
+        self.socket.send(msg).boxed_local()
+    }
+
+    fn next_frame(&self) -> LocalBoxFuture<'_, Option<MarketResult<String>>> {
+        self.socket.next_message().boxed_local()
+    }
+
+    fn parse_frame(&self, frame: &str) -> MarketResult<FeedEvent> {
+        parse_frame(frame)
+    }
+
+    fn is_replayable(&self) -> bool {
+        true
+    }
+
+    /// Sending "heartbeat" events to the server every 9 seconds. This will make sure to keep the connection stable
+    fn keep_alive(&self) -> LocalBoxFuture<'_, MarketResult<()>> {
+        async move {
+            let msg = r#"{"action": "heartbeat"}"#.to_string();
+            loop {
+                self.socket.send(msg.clone()).await?;
+                sleep(NINE_SEC).await;
+            }
+        }
+        .boxed_local()
+    }
+}
+
+/// Converting a Twelve Data web socket frame into a feed event
+pub fn parse_frame(frame: &str) -> MarketResult<FeedEvent> {
+    let response_event: WSResponseEvent = serde_json::from_str(frame).unwrap_or_default();
+    let event = match WSResponseEventType::from(response_event.event) {
+        WSResponseEventType::SubscribeStatus => {
+            let status_message: SubscribeStatusMessage = serde_json::from_str(frame)?;
+            let symbols = |entries: Option<Vec<SubscribedSymbol>>| {
+                entries
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|entry| entry.symbol)
+                    .collect()
+            };
+            FeedEvent::SubscribeStatus(SubscribeStatus {
+                success: symbols(status_message.success),
+                fails: symbols(status_message.fails),
+            })
+        }
+        WSResponseEventType::Price => {
+            let price_message: PriceMessage = serde_json::from_str(frame)?;
+            FeedEvent::Price(PriceTick {
+                symbol: price_message.symbol,
+                price: price_message.price,
+                bid: price_message.bid,
+                ask: price_message.ask,
+                day_volume: price_message.day_volume,
+                timestamp: price_message.timestamp,
+            })
+        }
+        WSResponseEventType::Heartbeat => FeedEvent::Heartbeat,
+        WSResponseEventType::Unknown => FeedEvent::Unknown(frame.to_owned()),
+    };
+    Ok(event)
+}
+
+/// Real-time price message
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct PriceMessage {
+    /// Type of event
+    pub event: String,
+    /// Symbol ticker of instrument
+    pub symbol: String,
+    /// Currency base
+    #[serde(default)]
+    pub currency_base: String,
+    /// Currency quote
+    #[serde(default)]
+    pub currency_quote: String,
+    #[serde(default)]
+    pub exchange: String,
+    /// Symbol ticker of instrument
+    #[serde(default)]
+    pub timestamp: i64,
+    /// Real-time price for the underlying instrument
+    #[serde(default)]
+    pub price: f64,
+    /// Bid price
+    #[serde(default)]
+    pub bid: f64,
+    /// Ask price
+    #[serde(default)]
+    pub ask: f64,
+    /// Real-time price for the underlying instrument
+    #[serde(default)]
+    pub day_volume: f64,
+}
+
+/// Subscription status message, `null` lists are possible
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct SubscribeStatusMessage {
+    /// Subscribed instruments
+    #[serde(default)]
+    pub success: Option<Vec<SubscribedSymbol>>,
+    /// Rejected instruments
+    #[serde(default)]
+    pub fails: Option<Vec<SubscribedSymbol>>,
+}
+
+/// Instrument of the subscription status
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct SubscribedSymbol {
+    /// Symbol ticker of instrument
+    pub symbol: String,
+}
+
+/// Web socket response event
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WSResponseEvent {
+    pub event: String,
+}
+
+impl Default for WSResponseEvent {
+    fn default() -> Self {
+        Self {
+            event: "unknown".to_owned(),
+        }
+    }
+}
+
+/// Web socket response event type
+#[derive(Debug)]
+pub enum WSResponseEventType {
+    SubscribeStatus,
+    Price,
+    Heartbeat,
+    Unknown,
+}
+
+impl From<String> for WSResponseEventType {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "price" => WSResponseEventType::Price,
+            "heartbeat" => WSResponseEventType::Heartbeat,
+            "subscribe-status" => WSResponseEventType::SubscribeStatus,
+            _ => WSResponseEventType::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subscribe_status_frames_list_the_symbols() {
+        let frame = r#"{"event":"subscribe-status","status":"error",
+            "success":[{"symbol":"AAPL","exchange":"NASDAQ","type":"Common Stock"}],
+            "fails":[{"symbol":"XYZ"}]}"#;
+        match parse_frame(frame).unwrap() {
+            FeedEvent::SubscribeStatus(status) => {
+                assert_eq!(status.success, ["AAPL"]);
+                assert_eq!(status.fails, ["XYZ"]);
+            }
+            event => panic!("unexpected event {event:?}"),
+        }
+        // missing lists are empty
+        let frame = r#"{"event":"subscribe-status","status":"ok","success":null}"#;
+        match parse_frame(frame).unwrap() {
+            FeedEvent::SubscribeStatus(status) => assert_eq!(status, SubscribeStatus::default()),
+            event => panic!("unexpected event {event:?}"),
+        }
+    }
+
+    #[test]
+    fn price_frames_become_ticks() {
+        let frame = r#"{"event":"price","symbol":"BTC/USD","currency_base":"Bitcoin",
+            "currency_quote":"US Dollar","exchange":"Coinbase Pro","type":"Digital Currency",
+            "timestamp":1718611201,"price":67407.25,"bid":67407.24,"ask":67407.26,"day_volume":2815}"#;
+        match parse_frame(frame).unwrap() {
+            FeedEvent::Price(tick) => {
+                assert_eq!(tick.symbol, "BTC/USD");
+                assert_eq!(tick.price, 67407.25);
+                assert_eq!((tick.bid, tick.ask), (67407.24, 67407.26));
+                assert_eq!(tick.day_volume, 2815.0);
+                assert_eq!(tick.timestamp, 1_718_611_201);
+            }
+            event => panic!("unexpected event {event:?}"),
+        }
+        // stocks come without bid and ask
+        let frame = r#"{"event":"price","symbol":"AAPL","timestamp":1718611201,"price":190.5}"#;
+        match parse_frame(frame).unwrap() {
+            FeedEvent::Price(tick) => assert_eq!((tick.price, tick.bid), (190.5, 0.0)),
+            event => panic!("unexpected event {event:?}"),
+        }
+    }
+
+    #[test]
+    fn other_frames_are_heartbeats_or_unknown() {
+        let heartbeat = r#"{"event":"heartbeat","status":"ok"}"#;
+        assert!(matches!(parse_frame(heartbeat), Ok(FeedEvent::Heartbeat)));
+        for frame in [r#"{"event":"reset-status"}"#, "not json"] {
+            match parse_frame(frame).unwrap() {
+                FeedEvent::Unknown(payload) => assert_eq!(payload, frame),
+                event => panic!("unexpected event {event:?}"),
+            }
+        }
+        // a price frame without the symbol is malformed
+        assert!(parse_frame(r#"{"event":"price","price":1.0}"#).is_err());
+    }
+}
@@ -0,0 +1,81 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use log::{info, warn};
+
+use crate::common::entities::SessionFrame;
+use crate::common::MarketResult;
+use crate::services::files::download_text;
+
+/// Upper bound of recorded frames, recording stops when it is reached
+const MAX_FRAMES: usize = 1_000_000;
+
+/// Price feed session recorder: keeps every inbound raw frame with its receive time.
+/// The session is saved as NDJSON, the format replayed by the mock provider, so only
+/// the feeds the mock provider can replay are recorded (not the Binance streams).
+#[derive(Clone, Default)]
+pub struct SessionRecorder {
+    state: Rc<RefCell<RecorderState>>,
+}
+
+#[derive(Default)]
+struct RecorderState {
+    recording: bool,
+    frames: Vec<SessionFrame>,
+}
+
+impl SessionRecorder {
+    /// Starting a new session, the previous one is discarded
+    pub fn start(&self) {
+        let mut state = self.state.borrow_mut();
+        state.frames.clear();
+        state.recording = true;
+        info!("Session recording started");
+    }
+    /// Stopping the recording, the session is kept for download
+    pub fn stop(&self) {
+        let mut state = self.state.borrow_mut();
+        state.recording = false;
+        info!("Session recording stopped, {} frames", state.frames.len());
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.state.borrow().recording
+    }
+    /// Number of recorded frames
+    pub fn frame_count(&self) -> usize {
+        self.state.borrow().frames.len()
+    }
+    /// Adding a raw frame received right now, returns true if the recording stopped
+    /// at the frame limit
+    pub fn record(&self, frame: &str) -> bool {
+        let mut state = self.state.borrow_mut();
+        if !state.recording {
+            return false;
+        }
+        if state.frames.len() >= MAX_FRAMES {
+            warn!("Session recording stopped at {MAX_FRAMES} frames");
+            state.recording = false;
+            return true;
+        }
+        state.frames.push(SessionFrame {
+            received_at: js_sys::Date::now(),
+            frame: frame.to_owned(),
+        });
+        false
+    }
+    /// Session as NDJSON, one frame per line
+    pub fn to_ndjson(&self) -> MarketResult<String> {
+        let mut ndjson = String::new();
+        for frame in &self.state.borrow().frames {
+            ndjson.push_str(serde_json::to_string(frame)?.as_str());
+            ndjson.push('\n');
+        }
+        Ok(ndjson)
+    }
+    /// Saving the session as a file through the browser download
+    pub fn download(&self) -> MarketResult<()> {
+        let file_name = format!("session-{}.ndjson", js_sys::Date::now() as i64);
+        download_text(&file_name, &self.to_ndjson()?, "application/x-ndjson")
+    }
+}
@@ -1,58 +1,68 @@
 use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 
 use gloo_net::http::Request;
-use log::debug;
+use log::{debug, warn};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
 
+use crate::common::app_config::AppConfig;
 use crate::common::entities::{
-    EndOfDay, Indices, IndicesReferenceData, Quote, Stock, UsStocksReferenceData,
-};
-use crate::common::env::{
-    MARKET_API_KEY, MARKET_EOD_ROUTE, MARKET_INDICES_ROUTE, MARKET_QUOTE_ROUTE,
-    MARKET_REST_ADDRESS, MARKET_STOCKS_ROUTE,
+    EndOfDay, Indices, IndicesReferenceData, Quote, Stock, SymbolMatch, SymbolSearchResults,
+    TimeSeries, UsStocksReferenceData,
 };
+use crate::common::enums::TimeSeriesInterval;
+use crate::common::error::MarketError;
 use crate::common::utils::prepare_symbols_for_url;
 use crate::common::MarketResult;
 
 /// Twelve Data Rest Api Client
-pub struct RestApiService;
+pub struct RestApiService {
+    config: Rc<AppConfig>,
+}
 
 impl RestApiService {
+    pub fn new(config: Rc<AppConfig>) -> Self {
+        Self { config }
+    }
     /// Fetching the latest End of Day (EOD) price of an instrument
     pub async fn get_end_of_day_data(
+        &self,
         symbols: HashSet<String>,
     ) -> MarketResult<HashMap<String, EndOfDay>> {
         let url = format!(
             "{}{}?symbol={}&apikey={}",
-            MARKET_REST_ADDRESS,
-            MARKET_EOD_ROUTE,
+            self.config.rest_address,
+            self.config.eod_route,
             prepare_symbols_for_url(symbols),
-            MARKET_API_KEY
+            self.config.api_key
         );
-        debug!("get_end_of_day_data. url = {}", url);
+        debug!("get_end_of_day_data. url = {}", redact_api_key(&url));
         let response = Request::get(url.as_str()).send().await?;
         let response_text = response.text().await?;
-        let data: HashMap<String, EndOfDay> = serde_json::from_str(response_text.as_str())?;
-        Ok(data)
+        parse_by_symbol(response_text.as_str())
     }
     /// Fetching the latest quote of the selected instrument
-    pub async fn get_last_quote(symbols: HashSet<String>) -> MarketResult<HashMap<String, Quote>> {
+    pub async fn get_last_quote(
+        &self,
+        symbols: HashSet<String>,
+    ) -> MarketResult<HashMap<String, Quote>> {
         let url = format!(
             "{}{}?symbol={}&interval=1day&apikey={}",
-            MARKET_REST_ADDRESS,
-            MARKET_QUOTE_ROUTE,
+            self.config.rest_address,
+            self.config.quote_route,
             prepare_symbols_for_url(symbols),
-            MARKET_API_KEY
+            self.config.api_key
         );
-        debug!("get_last_quote. url = {}", url);
+        debug!("get_last_quote. url = {}", redact_api_key(&url));
         let response = Request::get(url.as_str()).send().await?;
         let response_text = response.text().await?;
-        let data: HashMap<String, Quote> = serde_json::from_str(response_text.as_str())?;
-        Ok(data)
+        parse_by_symbol(response_text.as_str())
     }
     /// This function return array of indices available at Twelve Data API.
-    pub async fn get_indices() -> MarketResult<HashMap<String, Indices>> {
-        let url = format!("{}{}", MARKET_REST_ADDRESS, MARKET_INDICES_ROUTE);
-        debug!("get_indices. url = {}", url);
+    pub async fn get_indices(&self) -> MarketResult<HashMap<String, Indices>> {
+        let url = format!("{}{}", self.config.rest_address, self.config.indices_route);
+        debug!("get_indices. url = {}", redact_api_key(&url));
         let response = Request::get(url.as_str()).send().await?;
         let response_text = response.text().await?;
         let data: IndicesReferenceData = serde_json::from_str(response_text.as_str())?;
@@ -64,12 +74,12 @@ impl RestApiService {
         Ok(data)
     }
     /// This function return array of US stocks available at Twelve Data API.
-    pub async fn get_us_stocks() -> MarketResult<HashMap<String, Stock>> {
+    pub async fn get_us_stocks(&self) -> MarketResult<HashMap<String, Stock>> {
         let url = format!(
             "{}{}?country=United%20States",
-            MARKET_REST_ADDRESS, MARKET_STOCKS_ROUTE
+            self.config.rest_address, self.config.stocks_route
         );
-        debug!("get_us_stocks. url = {}", url);
+        debug!("get_us_stocks. url = {}", redact_api_key(&url));
         let response = Request::get(url.as_str()).send().await?;
         let response_text = response.text().await?;
         let data: UsStocksReferenceData = serde_json::from_str(response_text.as_str())?;
@@ -80,4 +90,122 @@ impl RestApiService {
             .collect();
         Ok(data)
     }
+    /// Fetching historical bars of the instrument, the newest `output_size` bars in UTC
+    pub async fn get_time_series(
+        &self,
+        symbol: String,
+        interval: TimeSeriesInterval,
+        output_size: u32,
+    ) -> MarketResult<TimeSeries> {
+        let url = format!(
+            "{}{}?symbol={}&interval={}&outputsize={}&timezone=UTC&apikey={}",
+            self.config.rest_address,
+            self.config.time_series_route,
+            String::from(js_sys::encode_uri_component(&symbol)),
+            interval.api_name(),
+            output_size,
+            self.config.api_key
+        );
+        debug!("get_time_series. url = {}", redact_api_key(&url));
+        let response = Request::get(url.as_str()).send().await?;
+        let response_text = response.text().await?;
+        let data: Value = serde_json::from_str(response_text.as_str())?;
+        if is_error(&data) {
+            let message = data.get("message").and_then(Value::as_str).unwrap_or("");
+            return Err(MarketError::General(format!(
+                "Time series of {symbol} is not available: {message}"
+            )));
+        }
+        let data: TimeSeries = serde_json::from_value(data)?;
+        Ok(data)
+    }
+    /// Searching instruments by symbol or name, the best matches first
+    pub async fn symbol_search(
+        &self,
+        query: String,
+        output_size: u32,
+    ) -> MarketResult<Vec<SymbolMatch>> {
+        let url = format!(
+            "{}{}?symbol={}&outputsize={}&apikey={}",
+            self.config.rest_address,
+            self.config.symbol_search_route,
+            String::from(js_sys::encode_uri_component(&query)),
+            output_size,
+            self.config.api_key
+        );
+        debug!("symbol_search. url = {}", redact_api_key(&url));
+        let response = Request::get(url.as_str()).send().await?;
+        let response_text = response.text().await?;
+        let data: Value = serde_json::from_str(response_text.as_str())?;
+        if is_error(&data) {
+            let message = data.get("message").and_then(Value::as_str).unwrap_or("");
+            return Err(MarketError::General(format!(
+                "Symbol search failed: {message}"
+            )));
+        }
+        let data: SymbolSearchResults = serde_json::from_value(data)?;
+        Ok(data.data)
+    }
+}
+
+/// Parsing a batch response keyed by symbol. A single symbol request returns a bare object,
+/// unknown symbols of a batch come as error entries and are skipped.
+fn parse_by_symbol<T: DeserializeOwned>(text: &str) -> MarketResult<HashMap<String, T>> {
+    let data: Value = serde_json::from_str(text)?;
+    if is_error(&data) {
+        let message = data.get("message").and_then(Value::as_str).unwrap_or("");
+        return Err(MarketError::General(format!("Request failed: {message}")));
+    }
+    if let Some(symbol) = data.get("symbol").and_then(Value::as_str) {
+        let symbol = symbol.to_owned();
+        return Ok(HashMap::from([(symbol, serde_json::from_value(data)?)]));
+    }
+    let Value::Object(entries) = data else {
+        return Err(MarketError::General(
+            "Unexpected response, an object is expected".to_owned(),
+        ));
+    };
+    let mut result = HashMap::new();
+    for (symbol, entry) in entries {
+        if is_error(&entry) {
+            warn!("No data for {}: {}", symbol, entry);
+            continue;
+        }
+        result.insert(symbol, serde_json::from_value(entry)?);
+    }
+    Ok(result)
+}
+
+/// Errors come as {"code": 400, "message": "...", "status": "error"}
+fn is_error(data: &Value) -> bool {
+    data.get("status").and_then(Value::as_str) == Some("error")
+}
+
+/// The url without the API key value, for logging
+fn redact_api_key(url: &str) -> String {
+    let Some(start) = url.find("apikey=").map(|index| index + "apikey=".len()) else {
+        return url.to_owned();
+    };
+    let end = url[start..]
+        .find('&')
+        .map_or(url.len(), |index| start + index);
+    format!("{}***{}", &url[..start], &url[end..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_key_is_redacted() {
+        assert_eq!(
+            redact_api_key("https://api.twelvedata.com/eod?symbol=AAPL&apikey=secret"),
+            "https://api.twelvedata.com/eod?symbol=AAPL&apikey=***"
+        );
+        assert_eq!(
+            redact_api_key("/time_series?symbol=AAPL&apikey=secret&timezone=UTC"),
+            "/time_series?symbol=AAPL&apikey=***&timezone=UTC"
+        );
+        assert_eq!(redact_api_key("/indices?country=US"), "/indices?country=US");
+    }
 }
@@ -0,0 +1,443 @@
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::time::Duration;
+
+use futures::future::{select, AbortHandle, Abortable, Either};
+use futures::pin_mut;
+use js_sys::Date;
+use log::{info, warn};
+use wasm_bindgen_futures::spawn_local;
+use yew::platform::time::sleep;
+use yew::Callback;
+
+use crate::common::backoff::Backoff;
+use crate::common::enums::{ConnectionState, FeedEvent, QuoteType};
+use crate::common::error::MarketError;
+use crate::services::providers::{MarketDataProvider, PriceFeed, ProviderRef};
+
+/// Number of consecutive failed attempts after which the supervisor gives up
+const MAX_RECONNECT_ATTEMPTS: u32 = 12;
+/// Period of the price feed status polling
+const STATUS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Callbacks notified by the connection supervisor
+#[derive(Clone)]
+pub struct SupervisorCallbacks {
+    /// Raw inbound frame the mock provider can replay, before parsing
+    pub frame: Callback<String>,
+    /// Price feed event
+    pub event: Callback<FeedEvent>,
+    /// Connection or protocol error
+    pub error: Callback<MarketError>,
+    /// Connection state change, combined over all price feeds
+    pub state: Callback<ConnectionState>,
+}
+
+/// Keeps the price feed connections alive: reopens them after close or error
+/// with jittered exponential backoff and replays the current subscription.
+/// Every stream provider gets its own connection.
+pub struct ConnectionSupervisor {
+    provider: ProviderRef,
+    routes: Vec<FeedRoute>,
+    callbacks: SupervisorCallbacks,
+    states: Rc<RefCell<Vec<ConnectionState>>>,
+}
+
+/// Connection of a single stream provider
+struct FeedRoute {
+    provider: Rc<dyn MarketDataProvider>,
+    symbols: Rc<RefCell<HashSet<String>>>,
+    /// The open price feed, `None` while (re)connecting
+    feed: Rc<RefCell<Option<Rc<dyn PriceFeed>>>>,
+    abort_handle: RefCell<Option<AbortHandle>>,
+}
+
+impl ConnectionSupervisor {
+    pub fn new(
+        provider: &ProviderRef,
+        symbols: HashMap<QuoteType, HashSet<String>>,
+        callbacks: SupervisorCallbacks,
+    ) -> Self {
+        let routes = create_routes(provider, symbols);
+        let states = vec![ConnectionState::default(); routes.len()];
+        Self {
+            provider: provider.clone(),
+            routes,
+            callbacks,
+            states: Rc::new(RefCell::new(states)),
+        }
+    }
+    /// Replacing the subscribed symbols. Open connections subscribe the added symbols
+    /// and unsubscribe the removed ones, a new stream provider gets a new connection.
+    pub fn set_symbols(&mut self, symbols: HashMap<QuoteType, HashSet<String>>) {
+        let mut groups = group_by_provider(&self.provider, symbols);
+        for route in &self.routes {
+            let symbols = groups
+                .iter()
+                .position(|(provider, _)| Rc::ptr_eq(provider, &route.provider))
+                .map(|index| groups.swap_remove(index).1)
+                .unwrap_or_default();
+            route.update(symbols, self.callbacks.error.clone());
+        }
+        for (provider, symbols) in groups {
+            self.routes.push(FeedRoute::new(provider, symbols));
+            self.states.borrow_mut().push(ConnectionState::default());
+            self.start_route(self.routes.len() - 1);
+        }
+    }
+    /// Starting the connection loops. Running loops are stopped and their connections are closed.
+    pub fn start(&self) {
+        for index in 0..self.routes.len() {
+            self.start_route(index);
+        }
+    }
+    /// Restarting the connection loops that gave up
+    pub fn restart_failed(&self) {
+        for index in 0..self.routes.len() {
+            if self.states.borrow()[index] == ConnectionState::Failed {
+                self.start_route(index);
+            }
+        }
+    }
+    /// Stopping the connection loops
+    pub fn stop(&self) {
+        for route in &self.routes {
+            route.stop();
+        }
+    }
+
+    fn start_route(&self, index: usize) {
+        let route = &self.routes[index];
+        route.stop();
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        *route.abort_handle.borrow_mut() = Some(abort_handle);
+
+        let states = self.states.clone();
+        let combined_state = self.callbacks.state.clone();
+        let callbacks = SupervisorCallbacks {
+            state: Callback::from(move |state| {
+                states.borrow_mut()[index] = state;
+                combined_state.emit(combine_states(&states.borrow()));
+            }),
+            ..self.callbacks.clone()
+        };
+        let connection_loop = run(
+            route.provider.clone(),
+            route.symbols.clone(),
+            route.feed.clone(),
+            callbacks,
+        );
+        spawn_local(async move {
+            let _ = Abortable::new(connection_loop, abort_registration).await;
+        });
+    }
+}
+
+impl FeedRoute {
+    fn new(provider: Rc<dyn MarketDataProvider>, symbols: HashSet<String>) -> Self {
+        Self {
+            provider,
+            symbols: Rc::new(RefCell::new(symbols)),
+            feed: Rc::new(RefCell::new(None)),
+            abort_handle: RefCell::new(None),
+        }
+    }
+
+    fn stop(&self) {
+        if let Some(abort_handle) = self.abort_handle.borrow_mut().take() {
+            abort_handle.abort();
+        }
+        self.feed.borrow_mut().take();
+    }
+    /// Changing the subscription of the open feed. Without an open feed the new symbols
+    /// are subscribed on the next connection.
+    fn update(&self, symbols: HashSet<String>, on_error: Callback<MarketError>) {
+        let previous = self.symbols.replace(symbols.clone());
+        let Some(feed) = self.feed.borrow().clone() else {
+            return;
+        };
+        let Some(change) = subscription_change(&previous, &symbols) else {
+            return;
+        };
+        info!(
+            "Price feed subscription change: +{:?} -{:?}",
+            change.added, change.removed
+        );
+        spawn_local(async move {
+            let result = if change.reset {
+                feed.reset().await
+            } else if !change.removed.is_empty() {
+                feed.unsubscribe(change.removed).await
+            } else {
+                Ok(())
+            };
+            let result = match result {
+                Ok(()) if !change.added.is_empty() => feed.subscribe(change.added).await,
+                result => result,
+            };
+            if let Err(error) = result {
+                on_error.emit(error);
+            }
+        });
+    }
+}
+
+/// Commands turning the subscribed symbols of an open feed into the new ones
+#[derive(Debug, PartialEq)]
+struct SubscriptionChange {
+    added: HashSet<String>,
+    removed: HashSet<String>,
+    /// Nothing is kept, e.g. imported watchlists: one reset instead of unsubscribing every symbol
+    reset: bool,
+}
+
+/// `None` when the symbols did not change
+fn subscription_change(
+    previous: &HashSet<String>,
+    symbols: &HashSet<String>,
+) -> Option<SubscriptionChange> {
+    let added: HashSet<String> = symbols.difference(previous).cloned().collect();
+    let removed: HashSet<String> = previous.difference(symbols).cloned().collect();
+    if added.is_empty() && removed.is_empty() {
+        return None;
+    }
+    let reset = !removed.is_empty() && removed.len() == previous.len();
+    Some(SubscriptionChange {
+        added,
+        removed,
+        reset,
+    })
+}
+
+impl Drop for ConnectionSupervisor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// One route per stream provider with the symbols of all its quote types
+fn create_routes(
+    provider: &ProviderRef,
+    symbols: HashMap<QuoteType, HashSet<String>>,
+) -> Vec<FeedRoute> {
+    group_by_provider(provider, symbols)
+        .into_iter()
+        .map(|(provider, symbols)| FeedRoute::new(provider, symbols))
+        .collect()
+}
+
+/// Symbols grouped by the stream provider of their quote type, empty groups are skipped
+fn group_by_provider(
+    provider: &ProviderRef,
+    symbols: HashMap<QuoteType, HashSet<String>>,
+) -> Vec<(Rc<dyn MarketDataProvider>, HashSet<String>)> {
+    let mut groups: Vec<(Rc<dyn MarketDataProvider>, HashSet<String>)> = Vec::new();
+    for (quote_type, quote_symbols) in symbols {
+        if quote_symbols.is_empty() {
+            continue;
+        }
+        let stream_provider = provider.stream_provider(quote_type);
+        match groups
+            .iter_mut()
+            .find(|(provider, _)| Rc::ptr_eq(provider, &stream_provider))
+        {
+            Some((_, symbols)) => symbols.extend(quote_symbols),
+            None => groups.push((stream_provider, quote_symbols)),
+        }
+    }
+    groups
+}
+
+/// The worst state wins
+fn combine_states(states: &[ConnectionState]) -> ConnectionState {
+    let priority = [
+        ConnectionState::Failed,
+        ConnectionState::Reconnecting,
+        ConnectionState::Connecting,
+    ];
+    priority
+        .into_iter()
+        .find(|state| states.contains(state))
+        .unwrap_or(ConnectionState::Live)
+}
+
+async fn run(
+    provider: Rc<dyn MarketDataProvider>,
+    symbols: Rc<RefCell<HashSet<String>>>,
+    live_feed: Rc<RefCell<Option<Rc<dyn PriceFeed>>>>,
+    callbacks: SupervisorCallbacks,
+) {
+    let mut backoff = Backoff::default();
+    callbacks.state.emit(ConnectionState::Connecting);
+    loop {
+        let opened_at = Date::now();
+        let received_price = Cell::new(false);
+        match provider.open_price_feed() {
+            Ok(feed) => {
+                let feed: Rc<dyn PriceFeed> = Rc::from(feed);
+                // later subscription changes go directly to the feed
+                let current_symbols = symbols.borrow().clone();
+                *live_feed.borrow_mut() = Some(feed.clone());
+                let subscribed = if current_symbols.is_empty() {
+                    Ok(())
+                } else {
+                    feed.subscribe(current_symbols).await
+                };
+                match subscribed {
+                    Ok(()) => {
+                        info!("Price feed subscribe Success");
+                        callbacks.state.emit(ConnectionState::Live);
+
+                        let reader = async {
+                            while let Some(frame) = feed.next_frame().await {
+                                match &frame {
+                                    Ok(frame) if feed.is_replayable() => {
+                                        callbacks.frame.emit(frame.clone())
+                                    }
+                                    _ => {}
+                                }
+                                match frame.and_then(|frame| feed.parse_frame(&frame)) {
+                                    Ok(event) => {
+                                        if let FeedEvent::Price(_) = event {
+                                            received_price.set(true);
+                                        }
+                                        callbacks.event.emit(event)
+                                    }
+                                    Err(error) => callbacks.error.emit(error),
+                                }
+                            }
+                        };
+                        let keep_alive = feed.keep_alive();
+                        let status = poll_status(feed.as_ref(), &callbacks.event);
+                        pin_mut!(reader, status);
+                        let watchers = select(keep_alive, status);
+                        if let Either::Right((Either::Left((Err(error), _)), _)) =
+                            select(reader, watchers).await
+                        {
+                            callbacks.error.emit(error);
+                        }
+                    }
+                    Err(error) => callbacks.error.emit(error),
+                }
+                live_feed.borrow_mut().take();
+            }
+            Err(error) => callbacks.error.emit(error),
+        }
+
+        let uptime = Duration::from_secs_f64((Date::now() - opened_at).max(0.0) / 1000.0);
+        backoff.connection_closed(received_price.get(), uptime);
+        let Some(delay) = next_attempt(&mut backoff, js_sys::Math::random()) else {
+            warn!("Price feed reconnection failed after {MAX_RECONNECT_ATTEMPTS} attempts");
+            callbacks.state.emit(ConnectionState::Failed);
+            return;
+        };
+        let attempt = backoff.attempt();
+        warn!("Price feed connection lost, attempt {attempt} in {delay:?}");
+        callbacks.state.emit(ConnectionState::Reconnecting);
+        sleep(delay).await;
+    }
+}
+
+/// Forwarding the events the feed raises without a frame, never resolves
+async fn poll_status(feed: &dyn PriceFeed, event: &Callback<FeedEvent>) {
+    loop {
+        sleep(STATUS_POLL_INTERVAL).await;
+        if let Some(status) = feed.poll_status(Date::now()) {
+            event.emit(status);
+        }
+    }
+}
+
+/// Delay before the next attempt, `None` when the attempts are over and the loop gives up
+fn next_attempt(backoff: &mut Backoff, random: f64) -> Option<Duration> {
+    (backoff.attempt() < MAX_RECONNECT_ATTEMPTS).then(|| backoff.next_delay(random))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(symbols: &[&str]) -> HashSet<String> {
+        symbols.iter().map(|symbol| symbol.to_string()).collect()
+    }
+
+    #[test]
+    fn subscription_change_adds_and_removes_symbols() {
+        assert_eq!(
+            subscription_change(&set(&["AAPL"]), &set(&["AAPL", "MSFT"])),
+            Some(SubscriptionChange {
+                added: set(&["MSFT"]),
+                removed: HashSet::new(),
+                reset: false,
+            })
+        );
+        assert_eq!(
+            subscription_change(&set(&["AAPL", "MSFT"]), &set(&["AAPL", "TSLA"])),
+            Some(SubscriptionChange {
+                added: set(&["TSLA"]),
+                removed: set(&["MSFT"]),
+                reset: false,
+            })
+        );
+        assert_eq!(
+            subscription_change(&set(&["AAPL", "MSFT"]), &set(&["MSFT", "AAPL"])),
+            None
+        );
+        assert_eq!(subscription_change(&HashSet::new(), &HashSet::new()), None);
+    }
+
+    #[test]
+    fn replacing_every_symbol_resets_the_feed() {
+        assert_eq!(
+            subscription_change(&set(&["AAPL", "MSFT"]), &set(&["BTC/USD"])),
+            Some(SubscriptionChange {
+                added: set(&["BTC/USD"]),
+                removed: set(&["AAPL", "MSFT"]),
+                reset: true,
+            })
+        );
+        assert_eq!(
+            subscription_change(&set(&["AAPL"]), &HashSet::new()),
+            Some(SubscriptionChange {
+                added: HashSet::new(),
+                removed: set(&["AAPL"]),
+                reset: true,
+            })
+        );
+        // the first symbols of an empty subscription are only added
+        assert_eq!(
+            subscription_change(&HashSet::new(), &set(&["AAPL"])).map(|change| change.reset),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn attempts_are_over_after_the_limit() {
+        let mut backoff = Backoff::default();
+        let mut delays = Vec::new();
+        loop {
+            backoff.connection_closed(false, Duration::from_millis(200));
+            match next_attempt(&mut backoff, 0.0) {
+                Some(delay) => delays.push(delay),
+                None => break,
+            }
+        }
+        assert_eq!(delays.len(), MAX_RECONNECT_ATTEMPTS as usize);
+        assert!(delays.windows(2).all(|pair| pair[0] <= pair[1]));
+        // the loop has given up, only a restart with a new backoff tries again
+        assert_eq!(next_attempt(&mut backoff, 0.0), None);
+        assert_eq!(
+            next_attempt(&mut Backoff::default(), 0.0),
+            delays.first().copied()
+        );
+    }
+
+    #[test]
+    fn healthy_connection_gives_the_attempts_back() {
+        let mut backoff = Backoff::default();
+        while next_attempt(&mut backoff, 0.0).is_some() {}
+        backoff.connection_closed(true, Duration::ZERO);
+        assert!(next_attempt(&mut backoff, 0.0).is_some());
+    }
+}
@@ -0,0 +1,407 @@
+use std::collections::{HashMap, HashSet};
+
+use gloo::storage::errors::StorageError;
+use gloo::storage::{LocalStorage, Storage};
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::common::config::DashboardConfiguration;
+use crate::common::entities::Watchlist;
+use crate::common::enums::{Language, QuoteSort, QuoteType};
+use crate::common::i18n::Text;
+use crate::common::MarketResult;
+use crate::services::files::download_text;
+
+/// localStorage key with the user watchlists
+const WATCHLISTS_STORAGE_KEY: &str = "market_aggregator.watchlists";
+/// Version of the saved watchlists format
+const STORAGE_VERSION: u32 = 2;
+/// File name of the exported watchlists
+const EXPORT_FILE_NAME: &str = "watchlists.json";
+
+/// Watchlist change requested by the user
+#[derive(PartialEq, Clone, Debug)]
+pub enum WatchlistAction {
+    /// Adding an empty watchlist at the end
+    Create {
+        name: String,
+        quote_type: QuoteType,
+    },
+    Rename {
+        index: usize,
+        name: String,
+    },
+    /// Moving the watchlist by `offset` positions
+    Move {
+        index: usize,
+        offset: isize,
+    },
+    Delete {
+        index: usize,
+    },
+    AddSymbol {
+        index: usize,
+        symbol: String,
+    },
+    RemoveSymbol {
+        index: usize,
+        symbol: String,
+    },
+    /// Changing the row order of the watchlist
+    Sort {
+        index: usize,
+        sort: QuoteSort,
+    },
+    /// Replacing all watchlists
+    Import(Vec<Watchlist>),
+}
+
+/// Watchlists saved in localStorage
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+enum StoredWatchlists {
+    Versioned {
+        version: u32,
+        watchlists: Vec<Watchlist>,
+    },
+    /// Plain list of the first version, saved with the Russian default titles
+    Legacy(Vec<Watchlist>),
+}
+
+/// User Watchlists Storage
+pub struct WatchlistService;
+
+impl WatchlistService {
+    /// Watchlists saved in localStorage, the default ones on the first start.
+    /// Watchlists of an older format are migrated and saved again.
+    pub fn load() -> Vec<Watchlist> {
+        match LocalStorage::get::<StoredWatchlists>(WATCHLISTS_STORAGE_KEY) {
+            Ok(StoredWatchlists::Versioned { watchlists, .. }) => watchlists,
+            Ok(StoredWatchlists::Legacy(watchlists)) => {
+                let watchlists = migrate_legacy(watchlists);
+                if let Err(error) = Self::save(&watchlists) {
+                    warn!("Failed to save the migrated watchlists: {}", error);
+                }
+                watchlists
+            }
+            Err(StorageError::KeyNotFound(_)) => DashboardConfiguration::get_default_watchlists(),
+            Err(error) => {
+                warn!(
+                    "Saved watchlists are not readable, using defaults: {}",
+                    error
+                );
+                DashboardConfiguration::get_default_watchlists()
+            }
+        }
+    }
+
+    pub fn save(watchlists: &[Watchlist]) -> MarketResult<()> {
+        let stored = StoredWatchlists::Versioned {
+            version: STORAGE_VERSION,
+            watchlists: watchlists.to_vec(),
+        };
+        LocalStorage::set(WATCHLISTS_STORAGE_KEY, stored)?;
+        Ok(())
+    }
+    /// Saving the watchlists as a JSON file through the browser download
+    pub fn export(watchlists: &[Watchlist]) -> MarketResult<()> {
+        let json = serde_json::to_string_pretty(watchlists)?;
+        download_text(EXPORT_FILE_NAME, &json, "application/json")
+    }
+    /// Parsing and validating exported watchlists
+    pub fn parse_import(json: &str) -> MarketResult<Vec<Watchlist>> {
+        let watchlists: Vec<Watchlist> = serde_json::from_str(json)?;
+        Ok(watchlists
+            .into_iter()
+            .map(|mut watchlist| {
+                watchlist.name = watchlist.name.trim().to_owned();
+                let mut unique = HashSet::new();
+                watchlist.symbols = watchlist
+                    .symbols
+                    .iter()
+                    .filter_map(|symbol| normalize_symbol(symbol))
+                    .filter(|symbol| unique.insert(symbol.clone()))
+                    .collect();
+                watchlist
+            })
+            .collect())
+    }
+    /// Applying the change, returns false if nothing changed
+    pub fn apply(watchlists: &mut Vec<Watchlist>, action: WatchlistAction) -> bool {
+        match action {
+            WatchlistAction::Create { name, quote_type } => {
+                let name = name.trim();
+                if name.is_empty() {
+                    return false;
+                }
+                watchlists.push(Watchlist {
+                    name: name.to_owned(),
+                    quote_type,
+                    symbols: Vec::new(),
+                    sort: QuoteSort::Manual,
+                });
+            }
+            // an empty name restores the translated default title
+            WatchlistAction::Rename { index, name } => {
+                let name = name.trim();
+                match watchlists.get_mut(index) {
+                    Some(watchlist) if watchlist.name != name => {
+                        watchlist.name = name.to_owned();
+                    }
+                    _ => return false,
+                }
+            }
+            WatchlistAction::Move { index, offset } => {
+                let target = index.checked_add_signed(offset);
+                match target {
+                    Some(target) if index < watchlists.len() && target < watchlists.len() => {
+                        let watchlist = watchlists.remove(index);
+                        watchlists.insert(target, watchlist);
+                    }
+                    _ => return false,
+                }
+            }
+            WatchlistAction::Delete { index } => {
+                if index >= watchlists.len() {
+                    return false;
+                }
+                watchlists.remove(index);
+            }
+            WatchlistAction::AddSymbol { index, symbol } => {
+                let (Some(watchlist), Some(symbol)) =
+                    (watchlists.get_mut(index), normalize_symbol(&symbol))
+                else {
+                    return false;
+                };
+                if watchlist.symbols.contains(&symbol) {
+                    return false;
+                }
+                watchlist.symbols.push(symbol);
+            }
+            WatchlistAction::RemoveSymbol { index, symbol } => {
+                let Some(watchlist) = watchlists.get_mut(index) else {
+                    return false;
+                };
+                let count = watchlist.symbols.len();
+                watchlist.symbols.retain(|value| *value != symbol);
+                if watchlist.symbols.len() == count {
+                    return false;
+                }
+            }
+            WatchlistAction::Sort { index, sort } => match watchlists.get_mut(index) {
+                Some(watchlist) if watchlist.sort != sort => watchlist.sort = sort,
+                _ => return false,
+            },
+            WatchlistAction::Import(imported) => {
+                if *watchlists == imported {
+                    return false;
+                }
+                *watchlists = imported;
+            }
+        }
+        true
+    }
+    /// Symbols of all watchlists grouped by quote type
+    pub fn symbols_by_type(watchlists: &[Watchlist]) -> HashMap<QuoteType, HashSet<String>> {
+        let mut symbols: HashMap<QuoteType, HashSet<String>> = HashMap::new();
+        for watchlist in watchlists {
+            symbols
+                .entry(watchlist.quote_type)
+                .or_default()
+                .extend(watchlist.symbols.iter().cloned());
+        }
+        symbols
+    }
+    /// Symbols of all watchlists
+    pub fn all_symbols(watchlists: &[Watchlist]) -> HashSet<String> {
+        watchlists
+            .iter()
+            .flat_map(|watchlist| watchlist.symbols.iter().cloned())
+            .collect()
+    }
+}
+
+/// Default titles saved before the translation follow the language again, this runs once
+/// as the migrated watchlists are saved in the versioned format
+fn migrate_legacy(mut watchlists: Vec<Watchlist>) -> Vec<Watchlist> {
+    for watchlist in &mut watchlists {
+        if watchlist.name == Language::Ru.text(Text::QuoteType(watchlist.quote_type)) {
+            watchlist.name.clear();
+        }
+    }
+    watchlists
+}
+
+/// Symbols are upper case tickers like "AAPL" or "EUR/USD"
+fn normalize_symbol(symbol: &str) -> Option<String> {
+    let symbol = symbol.trim().to_uppercase();
+    (!symbol.is_empty()).then_some(symbol)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn watchlist(name: &str, quote_type: QuoteType, symbols: &[&str]) -> Watchlist {
+        Watchlist {
+            name: name.to_owned(),
+            quote_type,
+            symbols: symbols.iter().map(|symbol| symbol.to_string()).collect(),
+            sort: QuoteSort::Manual,
+        }
+    }
+
+    fn names(watchlists: &[Watchlist]) -> Vec<&str> {
+        watchlists
+            .iter()
+            .map(|watchlist| watchlist.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn symbols_are_added_once_and_removed() {
+        let mut watchlists = vec![watchlist("Tech", QuoteType::USStocks, &["AAPL"])];
+        let add = |symbol: &str| WatchlistAction::AddSymbol {
+            index: 0,
+            symbol: symbol.to_owned(),
+        };
+        assert!(WatchlistService::apply(&mut watchlists, add(" msft ")));
+        assert!(!WatchlistService::apply(&mut watchlists, add("MSFT")));
+        assert!(!WatchlistService::apply(&mut watchlists, add("aapl")));
+        assert!(!WatchlistService::apply(&mut watchlists, add("  ")));
+        assert_eq!(watchlists[0].symbols, ["AAPL", "MSFT"]);
+
+        let remove = |index, symbol: &str| WatchlistAction::RemoveSymbol {
+            index,
+            symbol: symbol.to_owned(),
+        };
+        assert!(WatchlistService::apply(&mut watchlists, remove(0, "AAPL")));
+        assert!(!WatchlistService::apply(&mut watchlists, remove(0, "AAPL")));
+        assert!(!WatchlistService::apply(&mut watchlists, remove(1, "MSFT")));
+        let add_to_missing = WatchlistAction::AddSymbol {
+            index: 3,
+            symbol: "TSLA".to_owned(),
+        };
+        assert!(!WatchlistService::apply(&mut watchlists, add_to_missing));
+        assert_eq!(watchlists[0].symbols, ["MSFT"]);
+    }
+
+    #[test]
+    fn watchlists_are_created_renamed_moved_and_deleted() {
+        let mut watchlists = vec![watchlist("", QuoteType::CryptoCurrency, &[])];
+        let create = |name: &str| WatchlistAction::Create {
+            name: name.to_owned(),
+            quote_type: QuoteType::USStocks,
+        };
+        assert!(WatchlistService::apply(&mut watchlists, create(" Tech ")));
+        assert!(!WatchlistService::apply(&mut watchlists, create(" ")));
+        assert!(WatchlistService::apply(&mut watchlists, create("Banks")));
+        assert_eq!(names(&watchlists), ["", "Tech", "Banks"]);
+
+        let rename = |index, name: &str| WatchlistAction::Rename {
+            index,
+            name: name.to_owned(),
+        };
+        assert!(WatchlistService::apply(&mut watchlists, rename(0, "Coins")));
+        assert!(!WatchlistService::apply(
+            &mut watchlists,
+            rename(0, " Coins ")
+        ));
+        assert!(!WatchlistService::apply(
+            &mut watchlists,
+            rename(5, "Coins")
+        ));
+        // an empty name is the default title again
+        assert!(WatchlistService::apply(&mut watchlists, rename(1, "")));
+        assert_eq!(names(&watchlists), ["Coins", "", "Banks"]);
+
+        let move_by = |index, offset| WatchlistAction::Move { index, offset };
+        assert!(WatchlistService::apply(&mut watchlists, move_by(2, -2)));
+        assert_eq!(names(&watchlists), ["Banks", "Coins", ""]);
+        assert!(WatchlistService::apply(&mut watchlists, move_by(0, 1)));
+        assert_eq!(names(&watchlists), ["Coins", "Banks", ""]);
+        assert!(!WatchlistService::apply(&mut watchlists, move_by(0, -1)));
+        assert!(!WatchlistService::apply(&mut watchlists, move_by(2, 1)));
+        assert!(!WatchlistService::apply(&mut watchlists, move_by(3, -1)));
+
+        assert!(WatchlistService::apply(
+            &mut watchlists,
+            WatchlistAction::Delete { index: 1 }
+        ));
+        assert!(!WatchlistService::apply(
+            &mut watchlists,
+            WatchlistAction::Delete { index: 2 }
+        ));
+        assert_eq!(names(&watchlists), ["Coins", ""]);
+    }
+
+    #[test]
+    fn imports_are_normalized_and_malformed_ones_rejected() {
+        let json = r#"[
+            {"name": " Tech ", "quote_type": "us_stocks", "symbols": ["aapl", " AAPL", "", "msft"]},
+            {"quote_type": "crypto"}
+        ]"#;
+        let imported = WatchlistService::parse_import(json).unwrap();
+        assert_eq!(
+            imported,
+            [
+                watchlist("Tech", QuoteType::USStocks, &["AAPL", "MSFT"]),
+                watchlist("", QuoteType::CryptoCurrency, &[]),
+            ]
+        );
+
+        for json in [
+            "",
+            "{}",
+            r#"[{"name": "Tech"}]"#,
+            r#"[{"quote_type": "bonds"}]"#,
+            r#"[{"quote_type": "crypto", "symbols": "BTC/USD"}]"#,
+        ] {
+            assert!(WatchlistService::parse_import(json).is_err(), "{json}");
+        }
+    }
+
+    #[test]
+    fn only_legacy_watchlists_lose_the_default_titles() {
+        let title = Language::Ru.text(Text::QuoteType(QuoteType::USStocks));
+        let legacy = serde_json::json!([
+            {"name": title, "quote_type": "us_stocks", "symbols": ["AAPL"]},
+            {"name": "Tech", "quote_type": "us_stocks"}
+        ]);
+        let stored: StoredWatchlists = serde_json::from_value(legacy).unwrap();
+        let StoredWatchlists::Legacy(watchlists) = stored else {
+            panic!("not a legacy format: {stored:?}");
+        };
+        assert_eq!(names(&migrate_legacy(watchlists)), ["", "Tech"]);
+
+        // a user title equal to the default one is kept once migrated
+        let versioned = serde_json::json!({
+            "version": STORAGE_VERSION,
+            "watchlists": [{"name": title, "quote_type": "us_stocks"}]
+        });
+        let stored: StoredWatchlists = serde_json::from_value(versioned).unwrap();
+        let StoredWatchlists::Versioned { watchlists, .. } = stored else {
+            panic!("not a versioned format: {stored:?}");
+        };
+        assert_eq!(names(&watchlists), [title.as_str()]);
+    }
+
+    #[test]
+    fn importing_the_same_watchlists_changes_nothing() {
+        let mut watchlists = vec![watchlist("Tech", QuoteType::USStocks, &["AAPL"])];
+        let imported = WatchlistService::parse_import(
+            r#"[{"name": "Tech", "quote_type": "us_stocks", "symbols": ["aapl"]}]"#,
+        )
+        .unwrap();
+        assert!(!WatchlistService::apply(
+            &mut watchlists,
+            WatchlistAction::Import(imported)
+        ));
+        let replaced = vec![watchlist("", QuoteType::Currency, &["EUR/USD"])];
+        assert!(WatchlistService::apply(
+            &mut watchlists,
+            WatchlistAction::Import(replaced.clone())
+        ));
+        assert_eq!(watchlists, replaced);
+    }
+}